@@ -30,3 +30,12 @@ CREATE TABLE players (
         FOREIGN KEY ( player_id ) REFERENCES accounts ( account_id )
         ON DELETE SET NULL
 );
+
+
+-- Reserved accounts for the server's bots, one per seat for each bot strength,
+-- with empty public keys so they cannot be logged in to
+INSERT INTO accounts ( account_id, username, public_key )
+    OVERRIDING SYSTEM VALUE
+    SELECT -( strength * 32 + seat ), name || '.Bot.' || seat, ''::bytea
+    FROM ( VALUES ( 0, 'Random' ), ( 1, 'Greedy' ), ( 2, 'Searched' ) ) AS bots ( strength, name ),
+        generate_series( 1, 32 ) AS seat;
@@ -0,0 +1,351 @@
+use crate::{
+    board::Board,
+    coordinate::{Coordinate, CoordinateDelta},
+    delta::Delta,
+    error::Error,
+    piece_set::{PieceSet, NEUTRAL},
+};
+
+/// The directions a blockable attack can travel in
+const DIRECTIONS: [(isize, isize); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+/// A piece which cannot move off a line without exposing a royal piece to an attack
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pin {
+    /// The position of the pinned piece
+    pub pinned: Coordinate,
+    /// The position of the attacking piece
+    pub pinner: Coordinate,
+    /// The position of the royal piece behind the pinned piece
+    pub royal: Coordinate,
+    /// The spots the pinned piece can move to while still blocking the attack,
+    /// from the pinner to the royal piece
+    pub line: Vec<Coordinate>,
+}
+
+/// A piece which would reveal an attack on an opponent's piece by moving
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DiscoveredAttack {
+    /// The position of the piece which can move to reveal the attack
+    pub piece: Coordinate,
+    /// The position of the piece whose attack would be revealed
+    pub attacker: Coordinate,
+    /// The position of the opponent's piece which would be attacked
+    pub target: Coordinate,
+}
+
+impl<Set: PieceSet> Board<Set> {
+    /// Get the positions of a player's pieces attacking a spot
+    ///
+    /// # Errors
+    /// - [`Error<Set>::CoordinateNotOnBoard`] - `coordinate` is not on the board
+    pub fn attackers(
+        &self,
+        coordinate: &Coordinate,
+        player: u8,
+    ) -> Result<Vec<Coordinate>, Error<Set>> {
+        Ok(self
+            .get(coordinate)?
+            .attackers()
+            .iter()
+            .filter(|(attacker, _, _)| attacker == &player)
+            .map(|(_, from, _)| *from)
+            .collect())
+    }
+
+    /// Get the positions of the pieces defending the piece in a spot,
+    /// those belonging to the same player which attack its spot
+    ///
+    /// # Errors
+    /// - [`Error<Set>::CoordinateNotOnBoard`] - `coordinate` is not on the board
+    /// - [`Error<Set>::NoPieceAtSpot`] - There is no piece in the `coordinate`
+    pub fn defenders(&self, coordinate: &Coordinate) -> Result<Vec<Coordinate>, Error<Set>> {
+        let Some(piece) = self.get(coordinate)?.get() else {
+            return Err(Error::NoPieceAtSpot(*coordinate));
+        };
+
+        self.attackers(coordinate, piece.player())
+    }
+
+    /// Check if a piece capturing another could be captured back by another player
+    ///
+    /// # Errors
+    /// - [`Error<Set>::CoordinateNotOnBoard`] - `from` or `to` is not on the board
+    /// - [`Error<Set>::NoPieceAtSpot`] - There is no piece in `from` or `to`
+    /// - [`Error<Set>::PieceError`] - Error from a piece
+    pub fn is_capture_defended(
+        &self,
+        from: &Coordinate,
+        to: &Coordinate,
+    ) -> Result<bool, Error<Set>> {
+        let Some(piece) = self.get(from)?.get() else {
+            return Err(Error::NoPieceAtSpot(*from));
+        };
+        let player = piece.player();
+
+        // Make the capture on a copy of the board, so attacks revealed by the move are included
+        let mut board = self.clone();
+        board.apply_delta(Delta::Delete(*to))?;
+        board.apply_delta(Delta::Move(*from, *to))?;
+
+        board.is_being_attacked(to, player)
+    }
+
+    /// Get the pieces of a player which are pinned to one of their royal pieces
+    ///
+    /// Only blockable attacks in straight lines are considered
+    ///
+    /// # Errors
+    /// - [`Error<Set>::PieceError`] - Error from a piece
+    pub fn pins(&self, player: u8) -> Result<Vec<Pin>, Error<Set>> {
+        let mut pins = Vec::new();
+
+        for (pinned, _) in self.pieces_of(player) {
+            for &(attacker, pinner, blockable) in self.get(&pinned)?.attackers() {
+                if !blockable || attacker == player || attacker == NEUTRAL {
+                    continue;
+                }
+
+                // Find the piece behind the pinned piece
+                let Some((mut line, Some(royal))) = self.x_ray(&pinner, &pinned) else { continue };
+                let Some(piece) = self.get(&royal)?.get() else { continue };
+                if piece.player() != player
+                    || piece
+                        .is_in_check(self, &royal)
+                        .map_err(|err| Error::PieceError(err))?
+                        .is_none()
+                {
+                    continue;
+                }
+
+                line.insert(0, pinner);
+                pins.push(Pin {
+                    pinned,
+                    pinner,
+                    royal,
+                    line,
+                });
+            }
+        }
+
+        Ok(pins)
+    }
+
+    /// Get the pieces of a player which would reveal an attack on an opponent's piece by moving
+    ///
+    /// Only blockable attacks in straight lines are considered
+    ///
+    /// # Errors
+    /// - [`Error<Set>::CoordinateNotOnBoard`] - A piece attacks a coordinate not on the board
+    pub fn discovered_attacks(&self, player: u8) -> Result<Vec<DiscoveredAttack>, Error<Set>> {
+        let mut discovered_attacks = Vec::new();
+
+        for (piece, _) in self.pieces_of(player) {
+            for &(attacker_player, attacker, blockable) in self.get(&piece)?.attackers() {
+                if !blockable || attacker_player != player {
+                    continue;
+                }
+
+                // Find the piece behind the moving piece
+                let Some((_, Some(target))) = self.x_ray(&attacker, &piece) else { continue };
+                let Some(target_piece) = self.get(&target)?.get() else { continue };
+                if target_piece.player() == player
+                    || target_piece.player() == NEUTRAL
+                    || target_piece.capture_points().is_none()
+                {
+                    continue;
+                }
+
+                discovered_attacks.push(DiscoveredAttack {
+                    piece,
+                    attacker,
+                    target,
+                });
+            }
+        }
+
+        Ok(discovered_attacks)
+    }
+
+    /// Get the positions and pieces belonging to a player
+    fn pieces_of(&self, player: u8) -> Vec<(Coordinate, &Set)> {
+        self.raw()
+            .iter()
+            .enumerate()
+            .flat_map(|(y, rank)| {
+                rank.iter().enumerate().filter_map(move |(x, spot)| {
+                    spot.get()
+                        .as_ref()
+                        .filter(|piece| piece.player() == player)
+                        .map(|piece| (Coordinate(x, y), piece))
+                })
+            })
+            .collect()
+    }
+
+    /// Follow the straight line from `from` through the piece at `through`,
+    /// returning the empty spots along it and the first occupied spot behind `through`
+    ///
+    /// Returns `None` if `through` cannot be reached in a straight line of empty spots
+    pub(crate) fn x_ray(
+        &self,
+        from: &Coordinate,
+        through: &Coordinate,
+    ) -> Option<(Vec<Coordinate>, Option<Coordinate>)> {
+        let max_distance = isize::try_from(self.height()).ok()?;
+
+        for (dx, dy) in DIRECTIONS {
+            let mut line = Vec::new();
+            let mut passed = false;
+
+            for d in 1..max_distance {
+                let Some(position) = from + (&CoordinateDelta(dx * d, dy * d), self) else { break };
+                let Ok(spot) = self.get(&position) else { break };
+
+                if position == *through {
+                    passed = true;
+                    continue;
+                }
+
+                if spot.is_occupied() {
+                    if passed {
+                        return Some((line, Some(position)));
+                    }
+                    break;
+                }
+
+                line.push(position);
+            }
+
+            if passed {
+                return Some((line, None));
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(all(test, feature = "standard_pieces"))]
+mod test {
+    use crate::{
+        coordinate::Coordinate,
+        game::Game,
+        standard_pieces::{Bishop, King, Knight, Rook},
+    };
+
+    use super::DiscoveredAttack;
+
+    #[test]
+    fn attackers_and_defenders() {
+        const KNIGHT_POSITION: Coordinate = Coordinate(3, 3);
+
+        let mut game = Game::new(2, 8, 8);
+        game.add_pieces([
+            (Knight::new(0), KNIGHT_POSITION),
+            (Rook::new(0), Coordinate(3, 0)),
+            (Bishop::new(1), Coordinate(5, 5)),
+            (Knight::new(1), Coordinate(1, 4)),
+        ])
+        .expect("failed to add pieces to board");
+
+        let board = game.board();
+        assert_eq!(
+            board
+                .defenders(&KNIGHT_POSITION)
+                .expect("failed to get defenders"),
+            vec![Coordinate(3, 0)],
+            "wrong defenders"
+        );
+
+        let mut attackers = board
+            .attackers(&KNIGHT_POSITION, 1)
+            .expect("failed to get attackers");
+        attackers.sort_by_key(|coordinate| (coordinate.0, coordinate.1));
+        assert_eq!(
+            attackers,
+            vec![Coordinate(1, 4), Coordinate(5, 5)],
+            "wrong attackers"
+        );
+
+        // The rook recaptures on the knight's spot
+        assert!(
+            board
+                .is_capture_defended(&Coordinate(5, 5), &KNIGHT_POSITION)
+                .expect("failed to check capture"),
+            "defended capture reported as undefended"
+        );
+        assert!(
+            !board
+                .is_capture_defended(&KNIGHT_POSITION, &Coordinate(1, 4))
+                .expect("failed to check capture"),
+            "undefended capture reported as defended"
+        );
+    }
+
+    #[test]
+    fn pins() {
+        const KING_POSITION: Coordinate = Coordinate(4, 0);
+        const PINNED_POSITION: Coordinate = Coordinate(4, 2);
+        const PINNER_POSITION: Coordinate = Coordinate(4, 4);
+
+        let mut game = Game::new(2, 8, 8);
+        game.add_pieces([
+            (King::new(0), KING_POSITION),
+            (Knight::new(0), PINNED_POSITION),
+            (Rook::new(1), PINNER_POSITION),
+            (Knight::new(0), Coordinate(1, 3)),
+            (Knight::new(0), Coordinate(2, 2)),
+            (Bishop::new(1), Coordinate(0, 4)),
+        ])
+        .expect("failed to add pieces to board");
+
+        let pins = game.board().pins(0).expect("failed to get pins");
+        assert_eq!(pins.len(), 1, "wrong number of pins: {pins:?}");
+
+        let pin = &pins[0];
+        assert_eq!(pin.pinned, PINNED_POSITION, "wrong pinned piece");
+        assert_eq!(pin.pinner, PINNER_POSITION, "wrong pinner");
+        assert_eq!(pin.royal, KING_POSITION, "wrong royal piece");
+        assert_eq!(
+            pin.line,
+            vec![PINNER_POSITION, Coordinate(4, 3), Coordinate(4, 1)],
+            "wrong pin line"
+        );
+    }
+
+    #[test]
+    fn discovered_attacks() {
+        let mut game = Game::new(2, 8, 8);
+        game.add_pieces([
+            (Rook::new(0), Coordinate(0, 0)),
+            (Knight::new(0), Coordinate(0, 2)),
+            (Knight::new(1), Coordinate(0, 5)),
+            (Bishop::new(0), Coordinate(7, 0)),
+            (Knight::new(0), Coordinate(6, 1)),
+            (Knight::new(0), Coordinate(5, 2)),
+        ])
+        .expect("failed to add pieces to board");
+
+        assert_eq!(
+            game.board()
+                .discovered_attacks(0)
+                .expect("failed to get discovered attacks"),
+            vec![DiscoveredAttack {
+                piece: Coordinate(0, 2),
+                attacker: Coordinate(0, 0),
+                target: Coordinate(0, 5),
+            }],
+            "wrong discovered attacks"
+        );
+    }
+}
@@ -2,7 +2,7 @@ use crate::{
     coordinate::Coordinate,
     delta::{Delta, PartialDelta},
     error::Error,
-    piece_set::PieceSet,
+    piece_set::{PieceSet, NEUTRAL},
     r#move::Move,
     spot::Spot,
 };
@@ -11,6 +11,7 @@ use crate::{
 pub struct Board<Set: PieceSet>(Vec<Vec<Spot<Set>>>);
 
 pub type MovePartialDeltas<PieceId> = (Vec<PartialDelta<PieceId>>, u16);
+pub type VisibleExport<PieceId> = (usize, usize, Vec<Option<(u8, PieceId)>>);
 
 impl<Set: PieceSet> Board<Set> {
     #[must_use]
@@ -144,6 +145,46 @@ impl<Set: PieceSet> Board<Set> {
         Ok(in_check)
     }
 
+    /// Get the players with a piece which can be in check
+    ///
+    /// # Errors
+    /// - [`Error<Set>::PieceError`] - An error from a piece
+    pub fn royal_players(&self) -> Result<Vec<u8>, Error<Set>> {
+        let mut royal = Vec::new();
+
+        // For each spot on the board
+        for (y, rank) in self.raw().iter().enumerate() {
+            for (x, spot) in rank.iter().enumerate() {
+                // If it is empty, continue
+                let Some(piece) = spot.get() else { continue };
+                // If the player already has a royal piece, continue
+                if royal.contains(&piece.player()) {
+                    continue;
+                }
+
+                // If the piece can be in check, add the player to the list
+                if piece
+                    .is_in_check(self, &Coordinate(x, y))
+                    .map_err(|err| Error::PieceError(err))?
+                    .is_some()
+                {
+                    royal.push(piece.player());
+                }
+            }
+        }
+
+        Ok(royal)
+    }
+
+    #[must_use]
+    /// Check if there are any neutral pieces on the board
+    pub fn has_neutral_pieces(&self) -> bool {
+        self.0
+            .iter()
+            .flatten()
+            .any(|spot| spot.get().as_ref().is_some_and(|piece| piece.player() == NEUTRAL))
+    }
+
     /// Add the attacks from a piece to the board
     ///
     /// # Errors
@@ -177,6 +218,40 @@ impl<Set: PieceSet> Board<Set> {
         Ok(())
     }
 
+    /// Get the spots whose attacks differ from those found by placing every piece on an empty board
+    ///
+    /// The attacks are kept up to date as deltas are applied, so this should always be empty
+    ///
+    /// # Errors
+    /// - [`Error<Set>::CoordinateNotOnBoard`] - A piece attacks a coordinate not on the board
+    /// - [`Error<Set>::PieceError`] - An error from a piece
+    pub fn verify_attacks(&self) -> Result<Vec<Coordinate>, Error<Set>> {
+        let mut recomputed = Self(vec![vec![Spot::new(); self.width()]; self.height()]);
+        for (y, rank) in self.0.iter().enumerate() {
+            for (x, spot) in rank.iter().enumerate() {
+                let Some(piece) = spot.get() else { continue };
+                recomputed.apply_delta(Delta::Replace(Coordinate(x, y), piece.clone()))?;
+            }
+        }
+
+        let mut differences = Vec::new();
+        for (y, (rank, recomputed_rank)) in self.0.iter().zip(&recomputed.0).enumerate() {
+            for (x, (spot, recomputed_spot)) in rank.iter().zip(recomputed_rank).enumerate() {
+                let (attackers, recomputed_attackers) = (spot.attackers(), recomputed_spot.attackers());
+
+                if attackers.len() != recomputed_attackers.len()
+                    || !attackers
+                        .iter()
+                        .all(|attacker| recomputed_attackers.contains(attacker))
+                {
+                    differences.push(Coordinate(x, y));
+                }
+            }
+        }
+
+        Ok(differences)
+    }
+
     /// Applies a delta to the board
     ///
     /// # Errors
@@ -357,6 +432,11 @@ impl<Set: PieceSet> Board<Set> {
             self.add_attacks(&blocked_piece, &block)?;
         }
 
+        // Remove the attacks from the piece to be taken
+        if let Some(taken) = self.get(&r#move.to)?.get().clone() {
+            self.remove_attacks(&taken, &r#move.to)?;
+        }
+
         let mut partial_deltas = vec![PartialDelta::Move(r#move.from, r#move.to)];
 
         // Get the piece's mid-move deltas
@@ -452,6 +532,99 @@ impl<Set: PieceSet> Board<Set> {
         Ok(partial_deltas)
     }
 
+    /// Get the spots a player can see, in the same order as [`Self::export`]
+    ///
+    /// A spot is visible if one of the player's pieces occupies it, attacks it or can move to it
+    ///
+    /// # Errors
+    /// - [`Error<Set>::PieceError`] - An error from a piece
+    pub fn visible_spots(
+        &self,
+        player: u8,
+        turn: u16,
+        n_players: u8,
+    ) -> Result<Vec<bool>, Error<Set>> {
+        let width = self.width();
+        let mut visible = vec![false; width * self.height()];
+
+        for (y, rank) in self.0.iter().enumerate() {
+            for (x, spot) in rank.iter().enumerate() {
+                // The spots the player attacks are visible
+                if spot.is_attacked_by(player) {
+                    visible[y * width + x] = true;
+                }
+
+                let Some(piece) = spot.get() else { continue };
+                if piece.player() != player {
+                    continue;
+                }
+
+                // The spots the player occupies are visible
+                visible[y * width + x] = true;
+
+                // The spots the player can move to are visible
+                for (to, _) in piece
+                    .valid_moves(self, &Coordinate(x, y), turn, n_players)
+                    .map_err(|err| Error::PieceError(err))?
+                {
+                    if let Some(is_visible) = visible.get_mut(to.1 * width + to.0) {
+                        *is_visible = true;
+                    }
+                }
+            }
+        }
+
+        Ok(visible)
+    }
+
+    /// Filter the partial deltas of a move down to what a player can see
+    ///
+    /// `visible_before` and `visible_after` are the player's visible spots before and after
+    /// the move, and the board must be the board after the move.
+    #[must_use]
+    pub fn filter_deltas(
+        &self,
+        partial_deltas: Vec<PartialDelta<Set::PieceId>>,
+        visible_before: &[bool],
+        visible_after: &[bool],
+    ) -> Vec<PartialDelta<Set::PieceId>> {
+        let width = self.width();
+        let is_visible = |visible: &[bool], coordinate: &Coordinate| {
+            visible
+                .get(coordinate.1 * width + coordinate.0)
+                .copied()
+                .unwrap_or(false)
+        };
+
+        partial_deltas
+            .into_iter()
+            .filter_map(|delta| match delta {
+                PartialDelta::Move(from, to) => {
+                    match (
+                        is_visible(visible_before, &from),
+                        is_visible(visible_after, &to),
+                    ) {
+                        (true, true) => Some(delta),
+                        // The piece moved out of sight
+                        (true, false) => Some(PartialDelta::Delete(from)),
+                        // The piece moved into sight
+                        (false, true) => self.get(&to).ok()?.get().as_ref().map(|piece| {
+                            PartialDelta::Replace(to, piece.type_id(), piece.player())
+                        }),
+                        (false, false) => None,
+                    }
+                }
+                PartialDelta::Delete(position) => (is_visible(visible_before, &position)
+                    || is_visible(visible_after, &position))
+                .then_some(delta),
+                PartialDelta::Replace(position, ..) => {
+                    is_visible(visible_after, &position).then_some(delta)
+                }
+                delta => Some(delta),
+            })
+            .collect()
+    }
+
     #[must_use]
     pub fn export(&self) -> (usize, usize, Vec<(u8, Set::PieceId)>) {
         (
@@ -463,10 +636,129 @@ impl<Set: PieceSet> Board<Set> {
                     rank.iter().map(|spot| {
                         spot.get()
                             .as_ref()
-                            .map_or((0, Set::NONE_ID), |piece| (piece.player(), piece.type_id()))
+                            .map_or((NEUTRAL, Set::NONE_ID), |piece| (piece.player(), piece.type_id()))
                     })
                 })
                 .collect(),
         )
     }
+
+    #[must_use]
+    /// Export the board, hiding the spots which are not visible
+    pub fn export_visible(
+        &self,
+        visible: &[bool],
+    ) -> VisibleExport<Set::PieceId> {
+        let (width, height, spots) = self.export();
+
+        (
+            width,
+            height,
+            spots
+                .into_iter()
+                .zip(visible)
+                .map(|(spot, &is_visible)| is_visible.then_some(spot))
+                .collect(),
+        )
+    }
+}
+
+#[cfg(all(test, feature = "standard_pieces"))]
+mod test {
+    use crate::{
+        coordinate::Coordinate,
+        delta::PartialDelta,
+        game::Game,
+        r#move::Move,
+        standard_pieces::{Knight, Rook},
+    };
+
+    #[test]
+    fn fog_of_war() {
+        const ROOK_POSITION: Coordinate = Coordinate(0, 0);
+        const ROOK_TARGET: Coordinate = Coordinate(2, 0);
+        const KNIGHT_POSITION: Coordinate = Coordinate(3, 2);
+
+        let mut game = Game::new(2, 4, 5);
+        game.set_fog_of_war(true);
+        game.add_piece(Rook::new(0), &ROOK_POSITION)
+            .expect("failed to add rook to board");
+        game.add_piece(Knight::new(1), &KNIGHT_POSITION)
+            .expect("failed to add knight to board");
+
+        let (width, _, view) = game.export_for(0).expect("failed to export board");
+        assert!(
+            view[KNIGHT_POSITION.1 * width + KNIGHT_POSITION.0].is_none(),
+            "test failed: {KNIGHT_POSITION} is visible"
+        );
+        assert!(
+            matches!(view[ROOK_POSITION.1 * width + ROOK_POSITION.0], Some((0, 4))),
+            "test failed: {ROOK_POSITION} is not visible"
+        );
+
+        let visible_before = [
+            game.visible_spots(0).expect("failed to get visible spots"),
+            game.visible_spots(1).expect("failed to get visible spots"),
+        ];
+
+        let partial_deltas = game
+            .make_move(&Move {
+                from: ROOK_POSITION,
+                to: ROOK_TARGET,
+                data: 0,
+                player: 0,
+            })
+            .expect("failed to make rook move");
+
+        let visible_after = [
+            game.visible_spots(0).expect("failed to get visible spots"),
+            game.visible_spots(1).expect("failed to get visible spots"),
+        ];
+
+        let seen = game.board().filter_deltas(
+            partial_deltas.clone(),
+            &visible_before[0],
+            &visible_after[0],
+        );
+        assert!(
+            matches!(seen[..], [PartialDelta::Move(from, to)] if from == ROOK_POSITION && to == ROOK_TARGET),
+            "test failed: mover does not see {ROOK_POSITION} -> {ROOK_TARGET}"
+        );
+
+        let seen =
+            game.board()
+                .filter_deltas(partial_deltas, &visible_before[1], &visible_after[1]);
+        assert!(
+            matches!(seen[..], [PartialDelta::Replace(position, 4, 0)] if position == ROOK_TARGET),
+            "test failed: opponent does not see the rook appear at {ROOK_TARGET}"
+        );
+    }
+
+    #[test]
+    fn capture_removes_attacks() {
+        const ROOK_POSITION: Coordinate = Coordinate(0, 0);
+        const KNIGHT_POSITION: Coordinate = Coordinate(0, 4);
+
+        let mut game = Game::new(2, 8, 8);
+        game.add_piece(Rook::new(0), &ROOK_POSITION)
+            .expect("failed to add rook to board");
+        game.add_piece(Knight::new(1), &KNIGHT_POSITION)
+            .expect("failed to add knight to board");
+
+        game.make_move(&Move {
+            from: ROOK_POSITION,
+            to: KNIGHT_POSITION,
+            data: 0,
+            player: 0,
+        })
+        .expect("failed to capture knight");
+
+        // The spots attacked by the captured knight, which the rook does not attack
+        for position in [Coordinate(1, 2), Coordinate(2, 3), Coordinate(2, 5), Coordinate(1, 6)] {
+            assert!(
+                !game.board().is_being_attacked(&position, 0).unwrap(),
+                "test failed: {position} is still attacked by the captured knight"
+            );
+        }
+    }
 }
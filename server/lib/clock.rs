@@ -0,0 +1,163 @@
+use std::time::{Duration, Instant};
+
+/// How much time each player has
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TimeControl {
+    /// A starting time, with `increment` added after each move
+    Fischer { initial: Duration, increment: Duration },
+    /// A starting time, where up to `delay` of the time used for each move is given back
+    Bronstein { initial: Duration, delay: Duration },
+    /// A fixed time for each move, which does not carry over
+    PerMove(Duration),
+}
+
+impl TimeControl {
+    #[must_use]
+    /// Get the time each player starts with
+    pub const fn initial(&self) -> Duration {
+        match *self {
+            Self::Fischer { initial, .. } | Self::Bronstein { initial, .. } | Self::PerMove(initial) => {
+                initial
+            }
+        }
+    }
+}
+
+/// The remaining time of each player
+///
+/// Only the current player's clock runs, from the last move made
+#[derive(Clone, Debug)]
+pub struct Clock {
+    control: TimeControl,
+    remaining: Vec<Duration>,
+    /// When the current player's clock started, if it has
+    since: Option<Instant>,
+}
+
+impl Clock {
+    #[must_use]
+    pub fn new(control: TimeControl, n_players: u8) -> Self {
+        Self {
+            control,
+            remaining: vec![control.initial(); usize::from(n_players)],
+            since: None,
+        }
+    }
+
+    #[must_use]
+    pub const fn control(&self) -> TimeControl {
+        self.control
+    }
+
+    /// Start the current player's clock, if it has not started
+    pub fn start(&mut self, now: Instant) {
+        self.since.get_or_insert(now);
+    }
+
+    #[must_use]
+    /// Get the time a player has left, with the current player's clock running since the last move
+    pub fn remaining(&self, player: u8, current_player: u8, now: Instant) -> Duration {
+        let remaining = self
+            .remaining
+            .get(usize::from(player))
+            .copied()
+            .unwrap_or_default();
+
+        match self.since {
+            Some(since) if player == current_player => {
+                remaining.saturating_sub(now.saturating_duration_since(since))
+            }
+            _ => remaining,
+        }
+    }
+
+    #[must_use]
+    /// Get whether the current player has run out of time
+    pub fn is_flagged(&self, current_player: u8, now: Instant) -> bool {
+        self.since.is_some() && self.remaining(current_player, current_player, now).is_zero()
+    }
+
+    /// Charge the current player for a move, and restart the clock
+    ///
+    /// The increment or delay is only given for a player's own move, not for moving neutral pieces
+    ///
+    /// Returns false if the player ran out of time before the move
+    pub fn charge(&mut self, current_player: u8, is_own_move: bool, now: Instant) -> bool {
+        let since = *self.since.get_or_insert(now);
+        let elapsed = now.saturating_duration_since(since);
+        self.since = Some(now);
+
+        let Some(remaining) = self.remaining.get_mut(usize::from(current_player)) else {
+            return true;
+        };
+        if elapsed > *remaining {
+            *remaining = Duration::ZERO;
+            return false;
+        }
+
+        *remaining -= elapsed;
+        if is_own_move {
+            match self.control {
+                TimeControl::Fischer { increment, .. } => *remaining += increment,
+                TimeControl::Bronstein { delay, .. } => *remaining += elapsed.min(delay),
+                TimeControl::PerMove(time) => *remaining = time,
+            }
+        }
+
+        true
+    }
+
+    /// Restart the clock, for the next player after one is out of time
+    pub const fn restart(&mut self, now: Instant) {
+        self.since = Some(now);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, Instant};
+
+    use super::{Clock, TimeControl};
+
+    #[test]
+    fn time_controls() {
+        let second = Duration::from_secs(1);
+        let start = Instant::now();
+
+        let tests = [
+            (
+                TimeControl::Fischer {
+                    initial: second * 10,
+                    increment: second,
+                },
+                second * 8,
+            ),
+            (
+                TimeControl::Bronstein {
+                    initial: second * 10,
+                    delay: second * 5,
+                },
+                second * 10,
+            ),
+            (TimeControl::PerMove(second * 5), second * 5),
+        ];
+
+        for (control, expected) in tests {
+            let mut clock = Clock::new(control, 2);
+            clock.start(start);
+
+            // Player 0 moves after 3 seconds
+            assert!(clock.charge(0, true, start + second * 3), "test failed: {control:?} flagged");
+            assert_eq!(clock.remaining(0, 1, start + second * 4), expected, "test failed: {control:?}");
+            assert_eq!(
+                clock.remaining(1, 1, start + second * 4),
+                control.initial().saturating_sub(second),
+                "test failed: {control:?} wrong clock running"
+            );
+            assert!(
+                clock.is_flagged(1, start + second * 3 + control.initial()),
+                "test failed: {control:?} not flagged"
+            );
+        }
+    }
+}
@@ -0,0 +1,493 @@
+use std::fmt::Debug;
+
+use crate::{
+    board::Board,
+    coordinate::Coordinate,
+    error::Error,
+    piece_set::{PieceSet, NEUTRAL},
+    r#move::Move,
+};
+
+/// The result of a condition for a player
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Won(u8),
+    Eliminated(u8),
+}
+
+/// A win or elimination condition, evaluated after each move
+pub trait Condition<Set: PieceSet>
+where
+    Self: Debug + Send,
+{
+    /// Prepare the condition from the board at the start of the game
+    ///
+    /// # Errors
+    /// [`Error<Set>`]
+    fn start(&mut self, _board: &Board<Set>) -> Result<(), Error<Set>> {
+        Ok(())
+    }
+
+    /// Evaluate the condition after `move` has been made on the board
+    ///
+    /// # Errors
+    /// [`Error<Set>`]
+    fn after_move(&mut self, board: &Board<Set>, r#move: &Move)
+        -> Result<Vec<Outcome>, Error<Set>>;
+
+    // Custom
+    fn clone(&self) -> Box<dyn Condition<Set>>;
+}
+
+impl<Set: PieceSet> Clone for Box<dyn Condition<Set>> {
+    fn clone(&self) -> Self {
+        Condition::clone(&**self)
+    }
+}
+
+/// Get the positions of a player's royal pieces
+fn royal_positions<Set: PieceSet>(
+    board: &Board<Set>,
+    player: u8,
+) -> Result<Vec<Coordinate>, Error<Set>> {
+    let mut positions = Vec::new();
+
+    for (y, rank) in board.raw().iter().enumerate() {
+        for (x, spot) in rank.iter().enumerate() {
+            let Some(piece) = spot.get() else { continue };
+            if piece.player() != player {
+                continue;
+            }
+
+            let position = Coordinate(x, y);
+            if piece
+                .is_in_check(board, &position)
+                .map_err(|err| Error::PieceError(err))?
+                .is_some()
+            {
+                positions.push(position);
+            }
+        }
+    }
+
+    Ok(positions)
+}
+
+/// Get each check on the board, as the checking and checked players
+fn standing_checks<Set: PieceSet>(board: &Board<Set>) -> Result<Vec<(u8, u8)>, Error<Set>> {
+    let mut standing = Vec::new();
+
+    for (y, rank) in board.raw().iter().enumerate() {
+        for (x, spot) in rank.iter().enumerate() {
+            let Some(piece) = spot.get() else { continue };
+            if piece
+                .is_in_check(board, &Coordinate(x, y))
+                .map_err(|err| Error::PieceError(err))?
+                != Some(true)
+            {
+                continue;
+            }
+
+            for &(attacker, _, _) in spot.attackers() {
+                let check = (attacker, piece.player());
+                if attacker != piece.player() && attacker != NEUTRAL && !standing.contains(&check) {
+                    standing.push(check);
+                }
+            }
+        }
+    }
+
+    Ok(standing)
+}
+
+/// A player wins by checking other players three times
+#[derive(Clone, Debug)]
+pub struct ThreeCheck {
+    checks: Vec<u8>,
+    /// The checks standing after the last move, as the checking and checked players
+    standing: Vec<(u8, u8)>,
+}
+
+impl ThreeCheck {
+    /// The number of checks needed to win
+    pub const CHECKS: u8 = 3;
+
+    #[must_use]
+    pub fn new(n_players: u8) -> Self {
+        Self {
+            checks: vec![0; usize::from(n_players)],
+            standing: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    /// Get the number of checks given by each player
+    pub fn checks(&self) -> &[u8] {
+        &self.checks
+    }
+}
+
+impl<Set: PieceSet> Condition<Set> for ThreeCheck {
+    fn after_move(
+        &mut self,
+        board: &Board<Set>,
+        r#move: &Move,
+    ) -> Result<Vec<Outcome>, Error<Set>> {
+        let standing = standing_checks(board)?;
+        let previous = std::mem::replace(&mut self.standing, standing);
+
+        let Some(checks) = self.checks.get_mut(usize::from(r#move.player)) else {
+            return Ok(Vec::with_capacity(0));
+        };
+
+        // Count each player put in check by the mover, unless the check was already standing
+        for check in &self.standing {
+            if check.0 == r#move.player && !previous.contains(check) {
+                *checks = checks.saturating_add(1);
+            }
+        }
+
+        Ok(if *checks >= Self::CHECKS {
+            vec![Outcome::Won(r#move.player)]
+        } else {
+            Vec::with_capacity(0)
+        })
+    }
+
+    fn clone(&self) -> Box<dyn Condition<Set>> {
+        Box::new(Clone::clone(self))
+    }
+}
+
+/// A player wins by moving a royal piece to the centre of another player's section
+///
+/// The centre is the middle two spots of the middle two rows of the section
+#[derive(Clone, Debug)]
+pub struct KingOfTheHill {
+    hills: Vec<(u8, Vec<Coordinate>)>,
+}
+
+impl KingOfTheHill {
+    #[must_use]
+    pub fn new(n_players: u8, width: usize, height: usize) -> Self {
+        let rows = height / usize::from(n_players.max(1));
+
+        let hills = (0..n_players)
+            .map(|player| {
+                let y = usize::from(player) * rows + rows.saturating_sub(1) / 2;
+                let x = width.saturating_sub(1) / 2;
+
+                (
+                    player,
+                    vec![
+                        Coordinate(x, y),
+                        Coordinate(x + 1, y),
+                        Coordinate(x, y + 1),
+                        Coordinate(x + 1, y + 1),
+                    ],
+                )
+            })
+            .collect();
+
+        Self { hills }
+    }
+
+    #[must_use]
+    /// Get the spots of each player's hill
+    pub fn hills(&self) -> &[(u8, Vec<Coordinate>)] {
+        &self.hills
+    }
+}
+
+impl<Set: PieceSet> Condition<Set> for KingOfTheHill {
+    fn after_move(
+        &mut self,
+        board: &Board<Set>,
+        r#move: &Move,
+    ) -> Result<Vec<Outcome>, Error<Set>> {
+        for position in royal_positions(board, r#move.player)? {
+            if self.hills.iter().any(|(owner, hill)| {
+                *owner != r#move.player && hill.contains(&position)
+            }) {
+                return Ok(vec![Outcome::Won(r#move.player)]);
+            }
+        }
+
+        Ok(Vec::with_capacity(0))
+    }
+
+    fn clone(&self) -> Box<dyn Condition<Set>> {
+        Box::new(Clone::clone(self))
+    }
+}
+
+/// A player is eliminated when they lose every piece of any type they started with
+///
+/// Should be used with non-royal pieces, as no player is ever in check
+#[derive(Clone, Debug)]
+pub struct Extinction<PieceId> {
+    types: Vec<Vec<PieceId>>,
+}
+
+impl<PieceId> Extinction<PieceId> {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { types: Vec::new() }
+    }
+}
+
+impl<PieceId> Default for Extinction<PieceId> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<PieceId: PartialEq> Extinction<PieceId> {
+    /// Get the types of pieces each player has
+    fn types<Set: PieceSet<PieceId = PieceId>>(board: &Board<Set>) -> Vec<Vec<PieceId>> {
+        let mut types: Vec<Vec<PieceId>> = Vec::new();
+
+        for spot in board.raw().iter().flatten() {
+            let Some(piece) = spot.get() else { continue };
+            if piece.player() == NEUTRAL {
+                continue;
+            }
+            let player = usize::from(piece.player());
+
+            if types.len() <= player {
+                types.resize_with(player + 1, Vec::new);
+            }
+
+            let id = piece.type_id();
+            if !types[player].contains(&id) {
+                types[player].push(id);
+            }
+        }
+
+        types
+    }
+}
+
+impl<Set: PieceSet> Condition<Set> for Extinction<Set::PieceId>
+where
+    Set::PieceId: PartialEq + Clone + Debug + Send + 'static,
+{
+    fn start(&mut self, board: &Board<Set>) -> Result<(), Error<Set>> {
+        self.types = Self::types(board);
+        Ok(())
+    }
+
+    fn after_move(
+        &mut self,
+        board: &Board<Set>,
+        _move: &Move,
+    ) -> Result<Vec<Outcome>, Error<Set>> {
+        let remaining = Self::types(board);
+        let mut outcomes = Vec::new();
+
+        for (player, types) in self.types.iter_mut().enumerate() {
+            let remaining = remaining.get(player).map_or(&[][..], Vec::as_slice);
+
+            if types.iter().any(|id| !remaining.contains(id)) {
+                outcomes.push(Outcome::Eliminated(
+                    u8::try_from(player).expect("exceeded maximum number of players"),
+                ));
+
+                // Only eliminate the player once
+                types.clear();
+            }
+        }
+
+        Ok(outcomes)
+    }
+
+    fn clone(&self) -> Box<dyn Condition<Set>> {
+        Box::new(Clone::clone(self))
+    }
+}
+
+#[cfg(all(test, feature = "standard_pieces"))]
+mod test {
+    use crate::{
+        coordinate::Coordinate,
+        delta::PartialDelta,
+        error::Error,
+        game::Game,
+        r#move::Move,
+        standard_pieces::{King, Knight, Rook, StandardCompatiblePieceSet},
+    };
+
+    use super::{Extinction, KingOfTheHill, ThreeCheck};
+
+    /// Make a move for the current player and start the next turn
+    fn play(
+        game: &mut Game<StandardCompatiblePieceSet>,
+        from: Coordinate,
+        to: Coordinate,
+    ) -> Vec<PartialDelta<u8>> {
+        let mut deltas = game
+            .make_move(&Move {
+                from,
+                to,
+                data: 0,
+                player: game.current_player(),
+            })
+            .unwrap_or_else(|err| panic!("failed to move {from} -> {to}: {err}"));
+        deltas.extend(game.start_turn().expect("failed to start next turn"));
+        deltas
+    }
+
+    #[test]
+    fn three_check() {
+        let mut game = Game::new(2, 8, 16);
+        game.add_piece(King::new(0), &Coordinate(0, 1))
+            .expect("failed to add king to board");
+        game.add_piece(Rook::new(0), &Coordinate(1, 5))
+            .expect("failed to add rook to board");
+        game.add_piece(King::new(1), &Coordinate(4, 12))
+            .expect("failed to add king to board");
+        game.add_condition(Box::new(ThreeCheck::new(2)))
+            .expect("failed to add condition");
+
+        // The rook checks the king along each row the king steps to
+        let moves = [
+            (Coordinate(1, 5), Coordinate(1, 12)),
+            (Coordinate(4, 12), Coordinate(4, 13)),
+            (Coordinate(1, 12), Coordinate(1, 13)),
+            (Coordinate(4, 13), Coordinate(4, 14)),
+            (Coordinate(1, 13), Coordinate(1, 14)),
+        ];
+        for (n, (from, to)) in moves.into_iter().enumerate() {
+            let deltas = play(&mut game, from, to);
+
+            let won = deltas
+                .iter()
+                .any(|delta| matches!(delta, PartialDelta::Won(0)));
+            assert!(
+                won == (n == moves.len() - 1),
+                "test failed: move {n}, won: {won}"
+            );
+        }
+
+        let after_win = game.make_move(&Move {
+            from: Coordinate(1, 14),
+            to: Coordinate(1, 13),
+            data: 0,
+            player: game.current_player(),
+        });
+        assert!(
+            matches!(after_win, Err(Error::GameOver)),
+            "test failed: move made after the game was won"
+        );
+    }
+
+    #[test]
+    fn three_check_counts_own_checks() {
+        let mut game = Game::new(3, 8, 24);
+        game.add_piece(King::new(0), &Coordinate(0, 2))
+            .expect("failed to add king to board");
+        game.add_piece(Rook::new(0), &Coordinate(0, 15))
+            .expect("failed to add rook to board");
+        game.add_piece(King::new(1), &Coordinate(7, 10))
+            .expect("failed to add king to board");
+        game.add_piece(Rook::new(1), &Coordinate(6, 5))
+            .expect("failed to add rook to board");
+        game.add_piece(King::new(2), &Coordinate(4, 20))
+            .expect("failed to add king to board");
+        game.add_condition(Box::new(ThreeCheck::new(3)))
+            .expect("failed to add condition");
+
+        // Player 1 gives one check, while the checks player 0 gives player 2 are standing
+        let moves = [
+            (Coordinate(0, 15), Coordinate(0, 20)),
+            (Coordinate(7, 10), Coordinate(7, 11)),
+            (Coordinate(4, 20), Coordinate(4, 21)),
+            (Coordinate(0, 20), Coordinate(0, 21)),
+            (Coordinate(6, 5), Coordinate(0, 5)),
+        ];
+        for (n, (from, to)) in moves.into_iter().enumerate() {
+            let deltas = play(&mut game, from, to);
+
+            assert!(
+                !deltas
+                    .iter()
+                    .any(|delta| matches!(delta, PartialDelta::Won(_))),
+                "test failed: move {n} won the game"
+            );
+        }
+        assert!(!game.is_over(), "test failed: game is over");
+    }
+
+    #[test]
+    fn king_of_the_hill() {
+        const KING_1_POSITION: Coordinate = Coordinate(0, 0);
+
+        // The hills are the middle of rows 0 to 6 and rows 7 to 13
+        for (from, to, expected) in [
+            (Coordinate(3, 2), Coordinate(3, 3), false),
+            (Coordinate(3, 9), Coordinate(3, 10), true),
+        ] {
+            let mut game = Game::new(2, 8, 14);
+            game.add_piece(King::new(0), &from)
+                .expect("failed to add first king to board");
+            game.add_piece(King::new(1), &KING_1_POSITION)
+                .expect("failed to add second king to board");
+            game.add_condition(Box::new(KingOfTheHill::new(2, 8, 14)))
+                .expect("failed to add condition");
+
+            let partial_deltas = game
+                .make_move(&Move {
+                    from,
+                    to,
+                    data: 0,
+                    player: 0,
+                })
+                .expect("failed to make king move");
+
+            let won = partial_deltas
+                .iter()
+                .any(|delta| matches!(delta, PartialDelta::Won(0)));
+            assert!(
+                won == expected && game.is_over() == expected,
+                "test failed: {from} -> {to}, {won} ({expected})"
+            );
+        }
+    }
+
+    #[test]
+    fn extinction() {
+        const KNIGHT_0_POSITION: Coordinate = Coordinate(2, 2);
+        const KNIGHT_1_POSITION: Coordinate = Coordinate(3, 4);
+        const KING_1_POSITION: Coordinate = Coordinate(6, 6);
+
+        let mut game = Game::new(2, 8, 8);
+        game.add_piece(Knight::new(0), &KNIGHT_0_POSITION)
+            .expect("failed to add first knight to board");
+        game.add_piece(Knight::new(1), &KNIGHT_1_POSITION)
+            .expect("failed to add second knight to board");
+        game.add_piece(King::new_non_royal(1), &KING_1_POSITION)
+            .expect("failed to add king to board");
+        game.add_condition(Box::new(Extinction::new()))
+            .expect("failed to add condition");
+
+        let partial_deltas = game
+            .make_move(&Move {
+                from: KNIGHT_0_POSITION,
+                to: KNIGHT_1_POSITION,
+                data: 0,
+                player: 0,
+            })
+            .expect("failed to make capture");
+
+        assert!(
+            partial_deltas
+                .iter()
+                .any(|delta| matches!(delta, PartialDelta::Eliminated(1))),
+            "test failed: player 1 was not eliminated"
+        );
+        assert!(
+            !game.board().get(&KING_1_POSITION).unwrap().is_occupied(),
+            "test failed: eliminated player's king remains"
+        );
+    }
+}
@@ -39,6 +39,8 @@ impl Display for CoordinateDelta {
 //     }
 // }
 
+/// Add a delta on a board, which is a tube:
+/// the x coordinate stops at the sides, while the y coordinate wraps around in both directions
 impl<Set: PieceSet> Add<(&CoordinateDelta, &Board<Set>)> for &Coordinate {
     type Output = Option<Coordinate>;
 
@@ -48,9 +50,42 @@ impl<Set: PieceSet> Add<(&CoordinateDelta, &Board<Set>)> for &Coordinate {
                 a if a < rhs.1.width() => a,
                 _ => return None,
             },
-            usize::try_from(isize::try_from(self.1).ok()?.add(rhs.0 .1))
-                .ok()?
-                .rem_euclid(rhs.1.height()),
+            usize::try_from(
+                isize::try_from(self.1)
+                    .ok()?
+                    .add(rhs.0 .1)
+                    .rem_euclid(isize::try_from(rhs.1.height()).ok()?),
+            )
+            .ok()?,
         ))
     }
 }
+
+#[cfg(all(test, feature = "standard_pieces"))]
+mod test {
+    use crate::{board::Board, standard_pieces::StandardCompatiblePieceSet};
+
+    use super::{Coordinate, CoordinateDelta};
+
+    #[test]
+    fn wrap() {
+        let board = Board::<StandardCompatiblePieceSet>::new(5, 5);
+
+        let tests = [
+            (Coordinate(2, 0), CoordinateDelta(0, -1), Some(Coordinate(2, 4))),
+            (Coordinate(2, 1), CoordinateDelta(1, -3), Some(Coordinate(3, 3))),
+            (Coordinate(2, 4), CoordinateDelta(0, 1), Some(Coordinate(2, 0))),
+            (Coordinate(2, 3), CoordinateDelta(-1, 7), Some(Coordinate(1, 0))),
+            (Coordinate(0, 2), CoordinateDelta(-1, 0), None),
+            (Coordinate(4, 2), CoordinateDelta(1, 1), None),
+        ];
+
+        for (coordinate, delta, expected) in tests {
+            assert_eq!(
+                &coordinate + (&delta, &board),
+                expected,
+                "test failed: {coordinate} + {delta}"
+            );
+        }
+    }
+}
@@ -15,4 +15,10 @@ pub enum PartialDelta<PieceId> {
     Replace(Coordinate, PieceId, u8),
     Checkmate(u8),
     Stalemate(u8),
+    /// The player lost all of their royal pieces, or met an elimination condition
+    Eliminated(u8),
+    /// The player met a win condition
+    Won(u8),
+    /// The player ran out of time
+    Timeout(u8),
 }
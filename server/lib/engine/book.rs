@@ -0,0 +1,455 @@
+use std::{
+    cmp::Reverse,
+    collections::HashMap,
+    io::{self, Read, Write},
+};
+
+use crate::{
+    coordinate::Coordinate,
+    error::Error,
+    game::Game,
+    r#move::{Move, PartialMove},
+    rng::Rng,
+    standard_pieces::StandardCompatiblePieceSet,
+};
+
+use super::{record::GameRecord, Position};
+
+/// The first bytes of a book file
+const MAGIC: &[u8; 4] = b"CHBK";
+/// The version of the book file format
+const VERSION: u8 = 2;
+
+/// The variant of games played with the standard rules, the default for records without a
+/// `Variant` tag
+///
+/// Other variants use the ids of the server's variants
+pub const STANDARD_VARIANT: u8 = 0;
+
+const FNV_OFFSET: u64 = 0xCBF2_9CE4_8422_2325;
+const FNV_PRIME: u64 = 0x0100_0000_01B3;
+
+#[must_use]
+/// Get a hash of a position, from the pieces on the board and whether they have moved,
+/// the player whose turn it is and the players still in play
+///
+/// Castling rights are included, as they depend on whether the pieces have moved,
+/// but en passant is not, so positions differing only by that share a hash
+pub fn position_hash(game: &Game<StandardCompatiblePieceSet>) -> u64 {
+    let mut bytes = vec![game.current_player()];
+    bytes.extend((0..game.n_players()).map(|player| u8::from(game.is_in_play(player))));
+    for spot in game.board().raw().iter().flatten() {
+        bytes.extend(spot.get().as_ref().map_or([0; 3], |piece| {
+            [piece.player(), piece.type_id(), u8::from(piece.is_initial())]
+        }));
+    }
+
+    // FNV-1a, which is stable between builds, unlike the standard library's hasher
+    bytes.into_iter().fold(FNV_OFFSET, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(FNV_PRIME)
+    })
+}
+
+/// The games a book's positions can be in
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BookKey {
+    pub variant: u8,
+    pub players: u8,
+    pub width: u16,
+    pub height: u16,
+}
+
+impl BookKey {
+    #[must_use]
+    /// Get the key of a game played with a variant's rules
+    ///
+    /// # Panics
+    /// Will panic if the board is larger than `u16::MAX` in either direction
+    pub fn of(game: &Game<StandardCompatiblePieceSet>, variant: u8) -> Self {
+        Self {
+            variant,
+            players: game.n_players(),
+            width: u16::try_from(game.board().width()).expect("board width exceeded u16"),
+            height: u16::try_from(game.board().height()).expect("board height exceeded u16"),
+        }
+    }
+}
+
+/// An opening book, with weighted moves for positions
+///
+/// Positions are found by their [`position_hash`],
+/// separately for each variant, player count and board size
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Book(HashMap<BookKey, HashMap<u64, Vec<(PartialMove, u32)>>>);
+
+impl Book {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build a book from the first moves of game records
+    ///
+    /// # Errors
+    /// - Error from [`Self::add_record`]
+    pub fn from_records<'a>(
+        records: impl IntoIterator<Item = &'a GameRecord>,
+        depth: usize,
+    ) -> Result<Self, Error<StandardCompatiblePieceSet>> {
+        let mut book = Self::new();
+        for record in records {
+            book.add_record(record, depth)?;
+        }
+
+        Ok(book)
+    }
+
+    /// Add up to `depth` moves of a game record to the book
+    ///
+    /// Each move adds 1 to its weight,
+    /// or 2 if the record's `Result` tag gives points to the player who made it.
+    /// The moves are added for the variant in the record's `Variant` tag, or [`STANDARD_VARIANT`]
+    ///
+    /// # Errors
+    /// - Error from creating the game, or making its moves
+    pub fn add_record(
+        &mut self,
+        record: &GameRecord,
+        depth: usize,
+    ) -> Result<(), Error<StandardCompatiblePieceSet>> {
+        let mut game = Position {
+            setup: record.position.setup.clone(),
+            moves: Vec::new(),
+        }
+        .game()?;
+
+        let points: Vec<f64> = record
+            .tag("Result")
+            .map(|result| result.split_whitespace().filter_map(|points| points.parse().ok()).collect())
+            .unwrap_or_default();
+        let variant = record
+            .tag("Variant")
+            .and_then(|variant| variant.parse().ok())
+            .unwrap_or(STANDARD_VARIANT);
+
+        for &(from, to, data) in record.position.moves.iter().take(depth) {
+            let player = game.current_player();
+            let scored = points.get(usize::from(player)).is_some_and(|&points| points > 0.);
+            self.add(&game, variant, (from, to, data), if scored { 2 } else { 1 });
+
+            game.make_move(&Move {
+                from,
+                to,
+                data,
+                player,
+            })?;
+            game.start_turn()?;
+        }
+
+        Ok(())
+    }
+
+    /// Add weight to a move from a position in a variant
+    pub fn add(
+        &mut self,
+        game: &Game<StandardCompatiblePieceSet>,
+        variant: u8,
+        r#move: PartialMove,
+        weight: u32,
+    ) {
+        let moves = self
+            .0
+            .entry(BookKey::of(game, variant))
+            .or_default()
+            .entry(position_hash(game))
+            .or_default();
+
+        match moves.iter_mut().find(|(book_move, _)| *book_move == r#move) {
+            Some((_, book_weight)) => *book_weight = book_weight.saturating_add(weight),
+            None => moves.push((r#move, weight)),
+        }
+        moves.sort_by_key(|&(_, weight)| Reverse(weight));
+    }
+
+    #[must_use]
+    /// Get the moves from a position in a variant and their weights, with the heaviest first
+    ///
+    /// Only moves which are valid in the game are returned
+    pub fn moves(&self, game: &Game<StandardCompatiblePieceSet>, variant: u8) -> Vec<(PartialMove, u32)> {
+        let valid_moves = game.valid_moves();
+
+        self.0
+            .get(&BookKey::of(game, variant))
+            .and_then(|positions| positions.get(&position_hash(game)))
+            .map(|moves| {
+                moves
+                    .iter()
+                    .filter(|(r#move, _)| valid_moves.contains(r#move))
+                    .copied()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Choose a move from a position in a variant, with a chance proportional to its weight
+    pub fn choose(
+        &self,
+        game: &Game<StandardCompatiblePieceSet>,
+        variant: u8,
+        rng: &mut Rng,
+    ) -> Option<PartialMove> {
+        let moves = self.moves(game, variant);
+        let total: u64 = moves.iter().map(|&(_, weight)| u64::from(weight)).sum();
+        if total == 0 {
+            return None;
+        }
+
+        let mut chosen = rng.next_u64() % total;
+        for (r#move, weight) in moves {
+            if chosen < u64::from(weight) {
+                return Some(r#move);
+            }
+            chosen -= u64::from(weight);
+        }
+
+        None
+    }
+
+    #[must_use]
+    /// Get the number of positions in the book
+    pub fn len(&self) -> usize {
+        self.0.values().map(HashMap::len).sum()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Write the book in its binary format
+    ///
+    /// All numbers are little endian:
+    /// - `CHBK`, then the version as a byte
+    /// - The number of keys as a u32, then for each key:
+    ///   - The variant and players as bytes, the width and height as u16s
+    ///   - The number of positions as a u32, then for each position:
+    ///     - The hash as a u64
+    ///     - The number of moves as a u16, then for each move:
+    ///       - The from and to coordinates as u16s, the data as a byte, and the weight as a u32
+    ///
+    /// # Errors
+    /// - [`io::Error`] - The book could not be written, or is too large for the format
+    pub fn write(&self, writer: &mut impl Write) -> io::Result<()> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[VERSION])?;
+
+        // Sort the keys and positions, so the same book is always written the same way
+        let mut keys: Vec<_> = self.0.iter().collect();
+        keys.sort_by_key(|&(key, _)| *key);
+
+        writer.write_all(&too_large(u32::try_from(keys.len()))?.to_le_bytes())?;
+        for (key, positions) in keys {
+            writer.write_all(&[key.variant, key.players])?;
+            writer.write_all(&key.width.to_le_bytes())?;
+            writer.write_all(&key.height.to_le_bytes())?;
+
+            let mut positions: Vec<_> = positions.iter().collect();
+            positions.sort_by_key(|&(hash, _)| *hash);
+
+            writer.write_all(&too_large(u32::try_from(positions.len()))?.to_le_bytes())?;
+            for (hash, moves) in positions {
+                writer.write_all(&hash.to_le_bytes())?;
+                writer.write_all(&too_large(u16::try_from(moves.len()))?.to_le_bytes())?;
+
+                for &((from, to, data), weight) in moves {
+                    for value in [from.0, from.1, to.0, to.1] {
+                        writer.write_all(&too_large(u16::try_from(value))?.to_le_bytes())?;
+                    }
+                    writer.write_all(&[data])?;
+                    writer.write_all(&weight.to_le_bytes())?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Read a book written by [`Self::write`]
+    ///
+    /// # Errors
+    /// - [`io::Error`] - The book could not be read, or is not a valid book
+    pub fn read(reader: &mut impl Read) -> io::Result<Self> {
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid_data("not an opening book"));
+        }
+        if read_bytes::<1>(reader)? != [VERSION] {
+            return Err(invalid_data("unsupported opening book version"));
+        }
+
+        let mut book = Self::new();
+        for _ in 0..u32::from_le_bytes(read_bytes(reader)?) {
+            let [variant, players] = read_bytes(reader)?;
+            let key = BookKey {
+                variant,
+                players,
+                width: u16::from_le_bytes(read_bytes(reader)?),
+                height: u16::from_le_bytes(read_bytes(reader)?),
+            };
+            let positions = book.0.entry(key).or_default();
+
+            for _ in 0..u32::from_le_bytes(read_bytes(reader)?) {
+                let hash = u64::from_le_bytes(read_bytes(reader)?);
+                let n_moves = u16::from_le_bytes(read_bytes(reader)?);
+
+                let mut moves = Vec::with_capacity(usize::from(n_moves));
+                for _ in 0..n_moves {
+                    let mut values = [0; 4];
+                    for value in &mut values {
+                        *value = usize::from(u16::from_le_bytes(read_bytes(reader)?));
+                    }
+                    let [data] = read_bytes(reader)?;
+                    let weight = u32::from_le_bytes(read_bytes(reader)?);
+
+                    moves.push((
+                        (Coordinate(values[0], values[1]), Coordinate(values[2], values[3]), data),
+                        weight,
+                    ));
+                }
+                positions.insert(hash, moves);
+            }
+        }
+
+        Ok(book)
+    }
+}
+
+/// Read a fixed number of bytes
+fn read_bytes<const N: usize>(reader: &mut impl Read) -> io::Result<[u8; N]> {
+    let mut bytes = [0; N];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Convert a failed number conversion into an error
+fn too_large<T, E>(result: Result<T, E>) -> io::Result<T> {
+    result.map_err(|_| invalid_data("opening book too large for its format"))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        coordinate::Coordinate,
+        engine::{record::GameRecord, Position, Setup},
+        rng::Rng,
+        standard_pieces::BackRank,
+    };
+
+    use super::{position_hash, Book, STANDARD_VARIANT};
+
+    fn record(players: u8, result: &str, moves: Vec<crate::r#move::PartialMove>) -> GameRecord {
+        GameRecord {
+            tags: vec![("Result".to_owned(), result.to_owned())],
+            position: Position {
+                setup: Setup::Start {
+                    players,
+                    width: 8,
+                    rows: 8,
+                    back_rank: BackRank::Standard,
+                    seed: 0,
+                },
+                moves,
+            },
+        }
+    }
+
+    #[test]
+    fn book() {
+        let e4 = (Coordinate(4, 1), Coordinate(4, 3), 0);
+        let d4 = (Coordinate(3, 1), Coordinate(3, 3), 0);
+        let records = [
+            record(2, "1 0", vec![e4, (Coordinate(4, 9), Coordinate(4, 11), 0)]),
+            record(2, "0 1", vec![d4]),
+            record(2, "0.5 0.5", vec![e4]),
+            record(3, "1 0 0", vec![d4]),
+            GameRecord {
+                tags: vec![
+                    ("Result".to_owned(), "1 0".to_owned()),
+                    ("Variant".to_owned(), "2".to_owned()),
+                ],
+                ..record(2, "1 0", vec![d4])
+            },
+        ];
+        let book = Book::from_records(&records, 1).expect("failed to build book");
+
+        // Only the first move of each record is added, separately for each variant and player count
+        assert_eq!(book.len(), 3, "wrong number of positions");
+
+        let game = Position {
+            setup: records[0].position.setup.clone(),
+            moves: Vec::new(),
+        }
+        .game()
+        .expect("failed to create game");
+        assert_eq!(book.moves(&game, STANDARD_VARIANT), vec![(e4, 4), (d4, 1)], "wrong moves");
+        assert_eq!(book.moves(&game, 2), vec![(d4, 2)], "wrong variant moves");
+
+        let mut rng = Rng::new(0);
+        for _ in 0..8 {
+            let r#move = book.choose(&game, STANDARD_VARIANT, &mut rng).expect("no move chosen");
+            assert!(r#move == e4 || r#move == d4, "chose a move not in the book");
+        }
+
+        let mut bytes = Vec::new();
+        book.write(&mut bytes).expect("failed to write book");
+        let read = Book::read(&mut bytes.as_slice()).expect("failed to read book");
+        assert_eq!(read, book, "book changed when written and read");
+
+        assert!(Book::read(&mut &bytes[..bytes.len() - 1]).is_err(), "read a truncated book");
+    }
+
+    #[test]
+    fn castling_rights() {
+        // The knights move out and back, so only the pawns' moves differ from the start
+        let position = |moves: Vec<crate::r#move::PartialMove>| {
+            Position {
+                setup: record(2, "", Vec::new()).position.setup,
+                moves,
+            }
+            .game()
+            .expect("failed to create game")
+        };
+        let knights = vec![
+            (Coordinate(1, 0), Coordinate(2, 2), 0),
+            (Coordinate(1, 8), Coordinate(2, 10), 0),
+            (Coordinate(2, 2), Coordinate(1, 0), 0),
+            (Coordinate(2, 10), Coordinate(1, 8), 0),
+        ];
+        assert_eq!(
+            position_hash(&position(Vec::new())),
+            position_hash(&position(knights)),
+            "unmoved pieces changed the hash"
+        );
+
+        let rooks = vec![
+            (Coordinate(0, 1), Coordinate(0, 3), 0),
+            (Coordinate(0, 9), Coordinate(0, 11), 0),
+            (Coordinate(0, 0), Coordinate(0, 2), 0),
+            (Coordinate(0, 8), Coordinate(0, 10), 0),
+            (Coordinate(0, 2), Coordinate(0, 0), 0),
+            (Coordinate(0, 10), Coordinate(0, 8), 0),
+        ];
+        let mut pawns = rooks.clone();
+        pawns.retain(|&(from, _, _)| from.1 == 1 || from.1 == 9);
+        assert_ne!(
+            position_hash(&position(rooks)),
+            position_hash(&position(pawns)),
+            "rooks which lost their castling rights share a hash"
+        );
+    }
+}
@@ -0,0 +1,198 @@
+use std::{
+    error,
+    ffi::OsStr,
+    fmt,
+    io::{self, BufRead, BufReader, Write},
+    process::{Child, ChildStdin, ChildStdout, Command as Process, Stdio},
+};
+
+use crate::r#move::PartialMove;
+
+use super::{Command, Info, Limit, ParseError, Position, Reply};
+
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    Parse(ParseError),
+    /// The engine closed its output
+    Closed,
+    /// The engine could not process a command
+    Engine(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "Engine IO: {err}"),
+            Self::Parse(err) => write!(f, "Engine reply: {err}"),
+            Self::Closed => write!(f, "Engine closed!"),
+            Self::Engine(message) => write!(f, "Engine error: {message}"),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn cause(&self) -> Option<&dyn error::Error> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::Parse(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<ParseError> for Error {
+    fn from(err: ParseError) -> Self {
+        Self::Parse(err)
+    }
+}
+
+/// An engine subprocess, driven over its standard input and output
+///
+/// Each call blocks until the engine replies,
+/// so async code should run it on a blocking thread
+pub struct Engine {
+    process: Child,
+    input: ChildStdin,
+    output: BufReader<ChildStdout>,
+    ids: Vec<(String, String)>,
+}
+
+impl Engine {
+    /// Start an engine and the protocol
+    ///
+    /// # Errors
+    /// - [`Error::Io`] - The engine could not be started or communicated with
+    /// - [`Error::Parse`] - The engine sent an invalid reply
+    /// - [`Error::Closed`] - The engine stopped before starting the protocol
+    pub fn spawn<S: AsRef<OsStr>>(
+        program: S,
+        arguments: impl IntoIterator<Item = S>,
+    ) -> Result<Self, Error> {
+        let mut process = Process::new(program)
+            .args(arguments)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+
+        let (Some(input), Some(output)) = (process.stdin.take(), process.stdout.take()) else {
+            return Err(Error::Closed);
+        };
+
+        let mut engine = Self {
+            process,
+            input,
+            output: BufReader::new(output),
+            ids: Vec::new(),
+        };
+
+        engine.send(&Command::Chessehc)?;
+        loop {
+            match engine.receive()? {
+                Reply::Id(key, value) => engine.ids.push((key, value)),
+                Reply::Ok => break,
+                _ => {}
+            }
+        }
+
+        Ok(engine)
+    }
+
+    #[must_use]
+    /// Get an id sent by the engine, such as `name` or `author`
+    pub fn id(&self, key: &str) -> Option<&str> {
+        self.ids
+            .iter()
+            .find(|(id_key, _)| id_key == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Send a command to the engine
+    ///
+    /// # Errors
+    /// - [`Error::Io`] - The engine could not be written to
+    pub fn send(&mut self, command: &Command) -> Result<(), Error> {
+        writeln!(self.input, "{command}")?;
+        self.input.flush()?;
+
+        Ok(())
+    }
+
+    /// Wait for the next reply from the engine
+    ///
+    /// # Errors
+    /// - [`Error::Io`] - The engine could not be read from
+    /// - [`Error::Parse`] - The engine sent an invalid reply
+    /// - [`Error::Closed`] - The engine stopped
+    pub fn receive(&mut self) -> Result<Reply, Error> {
+        let mut line = String::new();
+
+        loop {
+            line.clear();
+            if self.output.read_line(&mut line)? == 0 {
+                return Err(Error::Closed);
+            }
+
+            // Skip empty lines
+            if !line.trim().is_empty() {
+                return Ok(line.parse()?);
+            }
+        }
+    }
+
+    /// Wait for the engine to finish processing commands
+    ///
+    /// # Errors
+    /// - [`Error::Engine`] - The engine could not process a command
+    /// - Error from sending or receiving
+    pub fn is_ready(&mut self) -> Result<(), Error> {
+        self.send(&Command::IsReady)?;
+
+        loop {
+            match self.receive()? {
+                Reply::ReadyOk => return Ok(()),
+                Reply::Error(message) => return Err(Error::Engine(message)),
+                _ => {}
+            }
+        }
+    }
+
+    /// Search a position, returning the engine's move and search statistics
+    ///
+    /// # Errors
+    /// - [`Error::Engine`] - The engine could not process the position or search
+    /// - Error from sending or receiving
+    pub fn go(
+        &mut self,
+        position: Position,
+        limit: Limit,
+    ) -> Result<(Option<PartialMove>, Vec<Info>), Error> {
+        self.send(&Command::Position(position))?;
+        self.send(&Command::Go(limit))?;
+
+        let mut infos = Vec::new();
+        loop {
+            match self.receive()? {
+                Reply::Info(info) => infos.push(info),
+                Reply::BestMove(r#move) => return Ok((r#move, infos)),
+                Reply::Error(message) => return Err(Error::Engine(message)),
+                _ => {}
+            }
+        }
+    }
+}
+
+impl Drop for Engine {
+    fn drop(&mut self) {
+        // Ask the engine to stop, then make sure it has
+        let _ = self.send(&Command::Quit);
+        let _ = self.process.kill();
+        let _ = self.process.wait();
+    }
+}
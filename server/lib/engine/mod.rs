@@ -0,0 +1,712 @@
+//! A line-based text protocol between a controller and an engine, like UCI for chess
+//!
+//! See `protocol_specification/engine.md` for the commands and replies
+
+use std::{error, fmt, str::FromStr, time::Duration};
+
+use crate::{
+    coordinate::Coordinate,
+    error::Error,
+    game::Game,
+    r#move::{Move, PartialMove},
+    standard_pieces::{
+        self, setup, BackRank, Bishop, Duck, King, Knight, Pawn, Queen, Rook,
+        StandardCompatiblePieceSet, Wall, BISHOP_ID, DUCK_ID, KING_ID, KNIGHT_ID, PAWN_ID,
+        QUEEN_ID, ROOK_ID, WALL_ID,
+    },
+};
+
+pub mod book;
+pub mod client;
+pub mod record;
+
+/// A line which could not be parsed
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError(pub String);
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Could not parse: {}!", self.0)
+    }
+}
+
+impl error::Error for ParseError {}
+
+/// Parse the next token of a line
+fn next<T: FromStr>(tokens: &mut std::str::SplitWhitespace, name: &str) -> Result<T, ParseError> {
+    let token = tokens
+        .next()
+        .ok_or_else(|| ParseError(format!("missing {name}")))?;
+
+    token
+        .parse()
+        .map_err(|_| ParseError(format!("invalid {name}: {token}")))
+}
+
+/// A move written as `x,y-x,y`, with `/data` if the data is not 0
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MoveToken(pub PartialMove);
+
+impl fmt::Display for MoveToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (from, to, data) = self.0;
+        write!(f, "{},{}-{},{}", from.0, from.1, to.0, to.1)?;
+
+        if data != 0 {
+            write!(f, "/{data}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for MoveToken {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseError(format!("invalid move: {s}"));
+        let coordinate = |s: &str| -> Result<Coordinate, ParseError> {
+            let (x, y) = s.split_once(',').ok_or_else(error)?;
+            Ok(Coordinate(
+                x.parse().map_err(|_| error())?,
+                y.parse().map_err(|_| error())?,
+            ))
+        };
+
+        let (coordinates, data) = s.split_once('/').unwrap_or((s, "0"));
+        let (from, to) = coordinates.split_once('-').ok_or_else(error)?;
+
+        Ok(Self((
+            coordinate(from)?,
+            coordinate(to)?,
+            data.parse().map_err(|_| error())?,
+        )))
+    }
+}
+
+/// A piece written as `player:type:x,y`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PieceToken {
+    pub player: u8,
+    pub type_id: u8,
+    pub position: Coordinate,
+}
+
+impl fmt::Display for PieceToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{},{}",
+            self.player, self.type_id, self.position.0, self.position.1
+        )
+    }
+}
+
+impl FromStr for PieceToken {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseError(format!("invalid piece: {s}"));
+
+        let mut parts = s.splitn(3, ':');
+        let mut part = || parts.next().ok_or_else(error);
+        let player = part()?.parse().map_err(|_| error())?;
+        let type_id = part()?.parse().map_err(|_| error())?;
+        let (x, y) = part()?.split_once(',').ok_or_else(error)?;
+
+        Ok(Self {
+            player,
+            type_id,
+            position: Coordinate(x.parse().map_err(|_| error())?, y.parse().map_err(|_| error())?),
+        })
+    }
+}
+
+/// The board size of a start position written without one
+const DEFAULT_WIDTH: u16 = 8;
+const DEFAULT_ROWS: u16 = 8;
+
+/// How a position's board is set up, before any moves
+#[derive(Clone, Debug, PartialEq)]
+pub enum Setup {
+    /// The starting position from [`setup`], on a board `width` wide with `rows` rows per player
+    Start {
+        players: u8,
+        width: u16,
+        rows: u16,
+        back_rank: BackRank,
+        seed: u64,
+    },
+    /// An arbitrary board, where pieces have not moved before
+    Pieces {
+        width: u16,
+        height: u16,
+        players: u8,
+        current_player: u8,
+        pieces: Vec<PieceToken>,
+    },
+}
+
+impl Setup {
+    #[must_use]
+    /// Get the setup of a game's current board
+    ///
+    /// Returns `None` if the setup cannot represent the game: when a piece has moved
+    /// or been changed since it was created, a player is out of play or has scored,
+    /// or the game has conditions, fog of war or a neutral phase.
+    ///
+    /// # Panics
+    /// Will panic if the board is larger than `u16::MAX` in either direction
+    pub fn from_game(game: &Game<StandardCompatiblePieceSet>) -> Option<Self> {
+        if game.has_conditions() || game.is_fog_of_war() || game.is_neutral_phase() {
+            return None;
+        }
+        if (0..game.n_players()).any(|player| !game.is_in_play(player))
+            || game.scores().iter().any(|&score| score != 0)
+        {
+            return None;
+        }
+
+        let board = game.board();
+        let (width, height) = (board.width(), board.height());
+        let mut pieces = Vec::new();
+        for y in 0..height {
+            for x in 0..width {
+                let position = Coordinate(x, y);
+                let Some(piece) = board.get(&position).ok()?.get().as_ref() else {
+                    continue;
+                };
+                if !piece.is_initial() {
+                    return None;
+                }
+
+                pieces.push(PieceToken {
+                    player: piece.player(),
+                    type_id: piece.type_id(),
+                    position,
+                });
+            }
+        }
+
+        Some(Self::Pieces {
+            width: u16::try_from(width).expect("board width exceeded u16"),
+            height: u16::try_from(height).expect("board height exceeded u16"),
+            players: game.n_players(),
+            current_player: game.current_player(),
+            pieces,
+        })
+    }
+}
+
+/// A position, from a setup and the moves made since
+#[derive(Clone, Debug, PartialEq)]
+pub struct Position {
+    pub setup: Setup,
+    pub moves: Vec<PartialMove>,
+}
+
+impl Position {
+    /// Create the game in the position
+    ///
+    /// # Errors
+    /// - [`Error::PieceError`] - A piece has an unknown type id
+    /// - [`Error::InvalidMove`] - A move could not be made
+    /// - Error from setting up the board
+    pub fn game(&self) -> Result<Game<StandardCompatiblePieceSet>, Error<StandardCompatiblePieceSet>> {
+        let mut game = match &self.setup {
+            &Setup::Start {
+                players,
+                width,
+                rows,
+                back_rank,
+                seed,
+            } => {
+                let mut game = Game::new(players, width, rows * u16::from(players));
+                game.set_seed(seed);
+                setup(&mut game, back_rank)?;
+                game
+            }
+            Setup::Pieces {
+                width,
+                height,
+                players,
+                current_player,
+                pieces,
+            } => {
+                let mut game = Game::new(*players, *width, *height);
+                let rows = usize::from(*height) / usize::from((*players).max(1));
+
+                let mut new_pieces = Vec::with_capacity(pieces.len());
+                for piece in pieces {
+                    new_pieces.push((
+                        new_piece(piece, rows, usize::from(*height))?,
+                        piece.position,
+                    ));
+                }
+                game.add_pieces(new_pieces)?;
+                game.set_current_player(*current_player)?;
+                game
+            }
+        };
+
+        for &(from, to, data) in &self.moves {
+            game.make_move(&Move {
+                from,
+                to,
+                data,
+                player: game.current_player(),
+            })?;
+            game.start_turn()?;
+        }
+
+        Ok(game)
+    }
+}
+
+/// Create a piece from its token, with pawns moving towards the next player's back rank
+fn new_piece(
+    piece: &PieceToken,
+    rows: usize,
+    height: usize,
+) -> Result<StandardCompatiblePieceSet, Error<StandardCompatiblePieceSet>> {
+    let player = piece.player;
+
+    Ok(match piece.type_id {
+        PAWN_ID => Pawn::new(player, 1, (usize::from(player) * rows + rows) % height.max(1)),
+        BISHOP_ID => Bishop::new(player),
+        KNIGHT_ID => Knight::new(player),
+        ROOK_ID => Rook::new(player),
+        QUEEN_ID => Queen::new(player),
+        KING_ID => King::new(player),
+        WALL_ID => Wall::new(),
+        DUCK_ID => Duck::new(),
+        id => {
+            return Err(Error::PieceError(standard_pieces::Error::InvalidPieceId(
+                id,
+            )))
+        }
+    })
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.setup {
+            Setup::Start {
+                players,
+                width,
+                rows,
+                back_rank,
+                seed,
+            } => {
+                let back_rank = match back_rank {
+                    BackRank::Standard => "standard",
+                    BackRank::Shared => "shared",
+                    BackRank::Individual => "individual",
+                };
+                write!(f, "start {players}")?;
+                if (*width, *rows) != (DEFAULT_WIDTH, DEFAULT_ROWS) {
+                    write!(f, " {width} {rows}")?;
+                }
+                write!(f, " {back_rank} {seed}")?;
+            }
+            Setup::Pieces {
+                width,
+                height,
+                players,
+                current_player,
+                pieces,
+            } => {
+                write!(f, "pieces {width} {height} {players} {current_player}")?;
+                for piece in pieces {
+                    write!(f, " {piece}")?;
+                }
+            }
+        }
+
+        if !self.moves.is_empty() {
+            write!(f, " moves")?;
+            for &r#move in &self.moves {
+                write!(f, " {}", MoveToken(r#move))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Position {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (setup, moves) = s
+            .split_once(" moves")
+            .unwrap_or((s, ""));
+        let mut tokens = setup.split_whitespace();
+
+        let setup = match tokens.next() {
+            Some("start") => {
+                let players = next(&mut tokens, "players")?;
+
+                // The board size is optional, and comes before the back rank
+                let (width, rows) = if tokens
+                    .clone()
+                    .next()
+                    .is_some_and(|token| token.parse::<u16>().is_ok())
+                {
+                    (next(&mut tokens, "width")?, next(&mut tokens, "rows")?)
+                } else {
+                    (DEFAULT_WIDTH, DEFAULT_ROWS)
+                };
+
+                Setup::Start {
+                    players,
+                    width,
+                    rows,
+                    back_rank: match tokens.next() {
+                        Some("standard") => BackRank::Standard,
+                        Some("shared") => BackRank::Shared,
+                        Some("individual") => BackRank::Individual,
+                        _ => return Err(ParseError(format!("invalid back rank: {s}"))),
+                    },
+                    seed: next(&mut tokens, "seed")?,
+                }
+            }
+            Some("pieces") => Setup::Pieces {
+                width: next(&mut tokens, "width")?,
+                height: next(&mut tokens, "height")?,
+                players: next(&mut tokens, "players")?,
+                current_player: next(&mut tokens, "current player")?,
+                pieces: tokens.by_ref().map(str::parse).collect::<Result<_, _>>()?,
+            },
+            _ => return Err(ParseError(format!("invalid position: {s}"))),
+        };
+
+        if let Some(token) = tokens.next() {
+            return Err(ParseError(format!("unexpected token: {token}")));
+        }
+
+        Ok(Self {
+            setup,
+            moves: moves
+                .split_whitespace()
+                .map(|token| token.parse().map(|MoveToken(r#move)| r#move))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+/// The limits on a search
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Limit {
+    /// The time to search for
+    pub move_time: Option<Duration>,
+    /// The time left on the current player's clock
+    pub remaining: Option<Duration>,
+    /// The number of search iterations
+    pub iterations: Option<u32>,
+}
+
+/// A command from a controller to an engine
+#[derive(Clone, Debug, PartialEq)]
+pub enum Command {
+    /// Start the protocol, the engine replies with its ids then [`Reply::Ok`]
+    Chessehc,
+    /// The engine replies with [`Reply::ReadyOk`] once it has finished processing commands
+    IsReady,
+    /// The next position is from a different game
+    NewGame,
+    /// Set the position to search
+    Position(Position),
+    /// Search the position, the engine replies with [`Reply::Info`]s then [`Reply::BestMove`]
+    Go(Limit),
+    /// Stop the engine
+    Quit,
+}
+
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Chessehc => write!(f, "chessehc"),
+            Self::IsReady => write!(f, "isready"),
+            Self::NewGame => write!(f, "newgame"),
+            Self::Position(position) => write!(f, "position {position}"),
+            Self::Go(limit) => {
+                write!(f, "go")?;
+                if let Some(move_time) = limit.move_time {
+                    write!(f, " movetime {}", move_time.as_millis())?;
+                }
+                if let Some(remaining) = limit.remaining {
+                    write!(f, " remaining {}", remaining.as_millis())?;
+                }
+                if let Some(iterations) = limit.iterations {
+                    write!(f, " iterations {iterations}")?;
+                }
+                Ok(())
+            }
+            Self::Quit => write!(f, "quit"),
+        }
+    }
+}
+
+impl FromStr for Command {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (command, arguments) = s.split_once(' ').unwrap_or((s, ""));
+
+        Ok(match command {
+            "chessehc" => Self::Chessehc,
+            "isready" => Self::IsReady,
+            "newgame" => Self::NewGame,
+            "position" => Self::Position(arguments.parse()?),
+            "go" => {
+                let mut limit = Limit::default();
+                let mut tokens = arguments.split_whitespace();
+
+                while let Some(token) = tokens.next() {
+                    match token {
+                        "movetime" => {
+                            limit.move_time =
+                                Some(Duration::from_millis(next(&mut tokens, "move time")?));
+                        }
+                        "remaining" => {
+                            limit.remaining =
+                                Some(Duration::from_millis(next(&mut tokens, "remaining time")?));
+                        }
+                        "iterations" => {
+                            limit.iterations = Some(next(&mut tokens, "iterations")?);
+                        }
+                        _ => return Err(ParseError(format!("unknown limit: {token}"))),
+                    }
+                }
+
+                Self::Go(limit)
+            }
+            "quit" => Self::Quit,
+            _ => return Err(ParseError(format!("unknown command: {s}"))),
+        })
+    }
+}
+
+/// Search statistics for a move
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Info {
+    pub r#move: PartialMove,
+    pub visits: u32,
+    /// The mean reward of the current player after the move, between 0 and 1
+    pub reward: f64,
+}
+
+/// A reply from an engine to a controller
+#[derive(Clone, Debug, PartialEq)]
+pub enum Reply {
+    /// Information about the engine, such as its `name` or `author`
+    Id(String, String),
+    /// The engine has started the protocol
+    Ok,
+    /// The engine has finished processing commands
+    ReadyOk,
+    Info(Info),
+    /// The move chosen by a search, or `None` if there are no valid moves
+    BestMove(Option<PartialMove>),
+    /// A command could not be processed
+    Error(String),
+}
+
+impl fmt::Display for Reply {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Id(key, value) => write!(f, "id {key} {value}"),
+            Self::Ok => write!(f, "chessehcok"),
+            Self::ReadyOk => write!(f, "readyok"),
+            Self::Info(info) => write!(
+                f,
+                "info move {} visits {} reward {}",
+                MoveToken(info.r#move),
+                info.visits,
+                info.reward
+            ),
+            Self::BestMove(Some(r#move)) => write!(f, "bestmove {}", MoveToken(*r#move)),
+            Self::BestMove(None) => write!(f, "bestmove none"),
+            Self::Error(message) => write!(f, "error {message}"),
+        }
+    }
+}
+
+impl FromStr for Reply {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (reply, arguments) = s.split_once(' ').unwrap_or((s, ""));
+
+        Ok(match reply {
+            "id" => {
+                let (key, value) = arguments.split_once(' ').unwrap_or((arguments, ""));
+                Self::Id(key.to_owned(), value.to_owned())
+            }
+            "chessehcok" => Self::Ok,
+            "readyok" => Self::ReadyOk,
+            "info" => {
+                let mut tokens = arguments.split_whitespace();
+                let mut r#move = None;
+                let mut visits = 0;
+                let mut reward = 0.;
+
+                while let Some(token) = tokens.next() {
+                    match token {
+                        "move" => {
+                            r#move = Some(next::<MoveToken>(&mut tokens, "move")?.0);
+                        }
+                        "visits" => visits = next(&mut tokens, "visits")?,
+                        "reward" => reward = next(&mut tokens, "reward")?,
+                        _ => return Err(ParseError(format!("unknown info: {token}"))),
+                    }
+                }
+
+                Self::Info(Info {
+                    r#move: r#move.ok_or_else(|| ParseError(format!("missing move: {s}")))?,
+                    visits,
+                    reward,
+                })
+            }
+            "bestmove" => Self::BestMove(match arguments {
+                "none" => None,
+                r#move => Some(r#move.parse::<MoveToken>()?.0),
+            }),
+            "error" => Self::Error(arguments.to_owned()),
+            _ => return Err(ParseError(format!("unknown reply: {s}"))),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use crate::{coordinate::Coordinate, standard_pieces::BackRank};
+
+    use super::{Command, Info, Limit, PieceToken, Position, Reply, Setup};
+
+    #[test]
+    fn commands() {
+        let commands = [
+            Command::Chessehc,
+            Command::IsReady,
+            Command::NewGame,
+            Command::Position(Position {
+                setup: Setup::Start {
+                    players: 3,
+                    width: 12,
+                    rows: 4,
+                    back_rank: BackRank::Individual,
+                    seed: 42,
+                },
+                moves: vec![
+                    (Coordinate(4, 1), Coordinate(4, 3), 0),
+                    (Coordinate(4, 8), Coordinate(4, 8), 2),
+                ],
+            }),
+            Command::Position(Position {
+                setup: Setup::Pieces {
+                    width: 8,
+                    height: 16,
+                    players: 2,
+                    current_player: 1,
+                    pieces: vec![PieceToken {
+                        player: 1,
+                        type_id: 6,
+                        position: Coordinate(4, 8),
+                    }],
+                },
+                moves: Vec::new(),
+            }),
+            Command::Go(Limit {
+                move_time: Some(Duration::from_millis(1500)),
+                remaining: None,
+                iterations: Some(200),
+            }),
+            Command::Quit,
+        ];
+
+        for command in commands {
+            let line = command.to_string();
+            assert_eq!(
+                line.parse::<Command>(),
+                Ok(command),
+                "test failed: {line}"
+            );
+        }
+    }
+
+    #[test]
+    fn replies() {
+        let replies = [
+            Reply::Id("name".to_owned(), "Chessehc MCTS".to_owned()),
+            Reply::Ok,
+            Reply::ReadyOk,
+            Reply::Info(Info {
+                r#move: (Coordinate(1, 0), Coordinate(2, 2), 0),
+                visits: 12,
+                reward: 0.75,
+            }),
+            Reply::BestMove(Some((Coordinate(6, 1), Coordinate(6, 0), 5))),
+            Reply::BestMove(None),
+            Reply::Error("Could not parse: unknown command: go!".to_owned()),
+        ];
+
+        for reply in replies {
+            let line = reply.to_string();
+            assert_eq!(line.parse::<Reply>(), Ok(reply), "test failed: {line}");
+        }
+    }
+
+    #[test]
+    fn position() {
+        let position: Position = "start 2 standard 0 moves 4,1-4,3 4,9-4,11"
+            .parse()
+            .expect("failed to parse position");
+        let game = position.game().expect("failed to create game");
+
+        assert_eq!(game.current_player(), 0, "wrong current player");
+        assert!(
+            game.board()
+                .get(&Coordinate(4, 11))
+                .expect("coordinate not on board")
+                .is_occupied(),
+            "move not made"
+        );
+    }
+    #[test]
+    fn from_game() {
+        let start: Position = "start 2 10 6 shared 7"
+            .parse()
+            .expect("failed to parse position");
+        let game = start.game().expect("failed to create game");
+        assert_eq!(game.board().width(), 10, "wrong width");
+        assert_eq!(game.board().height(), 12, "wrong height");
+
+        let setup = Setup::from_game(&game).expect("start position not represented");
+        let pieces = Position {
+            setup,
+            moves: vec![(Coordinate(4, 1), Coordinate(4, 3), 0)],
+        }
+        .game()
+        .expect("failed to create game");
+        let moved = Position {
+            moves: vec![(Coordinate(4, 1), Coordinate(4, 3), 0)],
+            ..start
+        }
+        .game()
+        .expect("failed to create game");
+        assert_eq!(
+            pieces.board().export(),
+            moved.board().export(),
+            "setup changed the board"
+        );
+
+        // The pawn's first move can't be represented
+        assert_eq!(Setup::from_game(&moved), None, "moved piece represented");
+    }
+}
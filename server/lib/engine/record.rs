@@ -0,0 +1,132 @@
+use std::{fmt, str::FromStr};
+
+use super::{ParseError, Position};
+
+/// A record of a game, with tags describing it and the position reached
+///
+/// Written as `[Key "Value"]` tag lines, followed by a line with the position,
+/// in the same format as the `position` command
+#[derive(Clone, Debug, PartialEq)]
+pub struct GameRecord {
+    pub tags: Vec<(String, String)>,
+    pub position: Position,
+}
+
+impl GameRecord {
+    #[must_use]
+    /// Get the value of a tag
+    pub fn tag(&self, key: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(tag_key, _)| tag_key == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Parse many records, separated by empty lines
+    ///
+    /// # Errors
+    /// - [`ParseError`] - A record could not be parsed
+    pub fn parse_all(s: &str) -> Result<Vec<Self>, ParseError> {
+        s.split("\n\n")
+            .filter(|record| !record.trim().is_empty())
+            .map(str::parse)
+            .collect()
+    }
+}
+
+impl fmt::Display for GameRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (key, value) in &self.tags {
+            writeln!(f, "[{key} \"{value}\"]")?;
+        }
+
+        writeln!(f, "{}", self.position)
+    }
+}
+
+impl FromStr for GameRecord {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut tags = Vec::new();
+        let mut position = None;
+
+        for line in s.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            if position.is_some() {
+                return Err(ParseError(format!("unexpected line after position: {line}")));
+            }
+
+            let Some(tag) = line.strip_prefix('[').and_then(|tag| tag.strip_suffix(']')) else {
+                position = Some(line.parse()?);
+                continue;
+            };
+
+            let (key, value) = tag
+                .split_once(' ')
+                .ok_or_else(|| ParseError(format!("invalid tag: {line}")))?;
+            let value = value
+                .strip_prefix('"')
+                .and_then(|value| value.strip_suffix('"'))
+                .ok_or_else(|| ParseError(format!("invalid tag value: {line}")))?;
+
+            tags.push((key.to_owned(), value.to_owned()));
+        }
+
+        Ok(Self {
+            tags,
+            position: position.ok_or_else(|| ParseError(format!("missing position: {s}")))?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{coordinate::Coordinate, engine::Setup, standard_pieces::BackRank};
+
+    use super::{GameRecord, Position};
+
+    #[test]
+    fn records() {
+        let records = [
+            GameRecord {
+                tags: vec![
+                    ("Seat0".to_owned(), "random".to_owned()),
+                    ("Result".to_owned(), "0 1".to_owned()),
+                ],
+                position: Position {
+                    setup: Setup::Start {
+                        players: 2,
+                        width: 8,
+                        rows: 8,
+                        back_rank: BackRank::Shared,
+                        seed: 3,
+                    },
+                    moves: vec![(Coordinate(4, 1), Coordinate(4, 3), 0)],
+                },
+            },
+            GameRecord {
+                tags: Vec::new(),
+                position: Position {
+                    setup: Setup::Start {
+                        players: 4,
+                        width: 10,
+                        rows: 6,
+                        back_rank: BackRank::Standard,
+                        seed: 0,
+                    },
+                    moves: Vec::new(),
+                },
+            },
+        ];
+
+        let text = records
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("\n");
+        let parsed = GameRecord::parse_all(&text).expect("failed to parse records");
+
+        assert_eq!(parsed, records, "test failed: {text}");
+        assert_eq!(parsed[0].tag("Result"), Some("0 1"), "wrong tag value");
+    }
+}
@@ -10,14 +10,17 @@ pub enum Error<Set: PieceSet> {
     PieceOwnedByWrongPlayer(Coordinate, u8),
     PieceNotCapturable(Coordinate),
     InvalidMove(Move),
+    UnknownPlayer(u8),
+    OutOfTime(u8),
+    GameOver,
     PieceError(Set::Error),
 }
 
 impl<Set: PieceSet> fmt::Display for Error<Set> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         use Error::{
-            CoordinateNotOnBoard, InvalidMove, NoPieceAtSpot, PieceError, PieceNotCapturable,
-            PieceOwnedByWrongPlayer, SpotOccupied,
+            CoordinateNotOnBoard, GameOver, InvalidMove, NoPieceAtSpot, OutOfTime, PieceError,
+            PieceNotCapturable, PieceOwnedByWrongPlayer, SpotOccupied, UnknownPlayer,
         };
         match self {
             CoordinateNotOnBoard(coordinate, width, height) => write!(
@@ -33,6 +36,9 @@ impl<Set: PieceSet> fmt::Display for Error<Set> {
                 write!(f, "{position}: Piece at spot is not capturable!")
             }
             InvalidMove(r#move) => write!(f, "Move is invalid: {move:?}"),
+            UnknownPlayer(player) => write!(f, "{player}: Player not in game!"),
+            OutOfTime(player) => write!(f, "{player}: Player out of time!"),
+            GameOver => write!(f, "Game is over!"),
             PieceError(error) => write!(f, "Piece error: {error}"),
         }
     }
@@ -1,22 +1,46 @@
+use std::time::{Duration, Instant};
+
 use crate::{
-    board::Board,
+    board::{Board, VisibleExport},
+    clock::{Clock, TimeControl},
+    condition::{Condition, Outcome},
     coordinate::Coordinate,
     delta::{Delta, PartialDelta},
     error::Error,
-    piece_set::PieceSet,
+    piece_set::{PieceSet, NEUTRAL},
     r#move::{Move, PartialMove},
+    rng::Rng,
 };
 
+#[derive(Clone)]
 pub struct Game<Set: PieceSet> {
     players: Vec<(bool, u16)>,
     board: Board<Set>,
     turn: (u16, u8),
     valid_moves: Vec<PartialMove>,
+    fog_of_war: bool,
+    seed: u64,
+    neutral_phase: bool,
+    conditions: Vec<Box<dyn Condition<Set>>>,
+    clock: Option<Clock>,
 }
 
 pub type AttemptedMove<Set, PieceId> = Option<(Board<Set>, Vec<PartialDelta<PieceId>>, u16)>;
 
-impl<Set: PieceSet> Game<Set> {
+/// A reason a position could not be reached in a game
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum InvalidPosition {
+    /// A player in play has no royal piece
+    NoRoyalPiece(u8),
+    /// The player who moved last is in check
+    InCheckOutOfTurn(u8),
+    /// The player whose turn it is is out of play
+    CurrentPlayerOutOfPlay(u8),
+    /// A piece belongs to a player not in the game
+    UnknownPlayer(Coordinate, u8),
+}
+
+impl<Set: PieceSet + 'static> Game<Set> {
     #[must_use]
     pub fn new(n_players: u8, width: u16, height: u16) -> Self {
         Self {
@@ -24,7 +48,111 @@ impl<Set: PieceSet> Game<Set> {
             board: Board::new(width, height),
             turn: (0, 0),
             valid_moves: Vec::with_capacity(0),
+            fog_of_war: false,
+            seed: 0,
+            neutral_phase: false,
+            conditions: Vec::new(),
+            clock: None,
+        }
+    }
+
+    /// Set the seed used to randomise the game, so that it can be reproduced
+    pub const fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+    }
+
+    #[must_use]
+    /// Get the seed used to randomise the game
+    pub const fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Set how much time each player has, or `None` for no time limit
+    ///
+    /// Every player starts with the initial time, and the clock starts with [`Self::start_clock`]
+    pub fn set_time_control(&mut self, control: Option<TimeControl>) {
+        self.clock = control.map(|control| Clock::new(control, self.n_players()));
+    }
+
+    #[must_use]
+    pub const fn clock(&self) -> Option<&Clock> {
+        self.clock.as_ref()
+    }
+
+    /// Start the current player's clock, if the game has one
+    ///
+    /// Otherwise, it starts with the first move
+    pub fn start_clock(&mut self, now: Instant) {
+        if let Some(clock) = &mut self.clock {
+            clock.start(now);
+        }
+    }
+
+    #[must_use]
+    /// Get the time a player has left, or `None` if the game has no clock
+    pub fn remaining_time(&self, player: u8, now: Instant) -> Option<Duration> {
+        self.clock
+            .as_ref()
+            .map(|clock| clock.remaining(player, self.turn.1, now))
+    }
+
+    /// Eliminate the current player if they have run out of time,
+    /// the same way as checkmate, and start the next player's turn
+    ///
+    /// # Errors
+    /// - [`Error<Set>::PieceError`] - An error from a piece
+    pub fn check_timeout(
+        &mut self,
+        now: Instant,
+    ) -> Result<Vec<PartialDelta<Set::PieceId>>, Error<Set>> {
+        let player = self.turn.1;
+        let is_over = self.is_over();
+        let Some(clock) = &mut self.clock else { return Ok(Vec::new()) };
+        if is_over || !clock.is_flagged(player, now) {
+            return Ok(Vec::new());
+        }
+        // The next player's clock starts now
+        clock.restart(now);
+
+        let mut partial_deltas = self.board.remove_player(player)?;
+        partial_deltas.push(PartialDelta::Timeout(player));
+        if let Some((is_in_game, _)) = self.players.get_mut(player as usize) {
+            *is_in_game = false;
         }
+
+        self.neutral_phase = false;
+        partial_deltas.extend(self.start_next_player_turn()?);
+
+        Ok(partial_deltas)
+    }
+
+    /// Add a win or elimination condition, evaluated after each move
+    ///
+    /// Should be added once the pieces are on the board
+    ///
+    /// # Errors
+    /// [`Error<Set>`] - Error from the condition
+    pub fn add_condition(&mut self, mut condition: Box<dyn Condition<Set>>) -> Result<(), Error<Set>> {
+        condition.start(&self.board)?;
+        self.conditions.push(condition);
+        Ok(())
+    }
+
+    #[must_use]
+    /// Get whether any win or elimination conditions have been added
+    pub fn has_conditions(&self) -> bool {
+        !self.conditions.is_empty()
+    }
+
+    /// Set whether players can only see the spots their pieces occupy, attack or can move to
+    pub const fn set_fog_of_war(&mut self, fog_of_war: bool) {
+        self.fog_of_war = fog_of_war;
+    }
+
+    #[must_use]
+    /// Get whether players can only see the spots their pieces occupy, attack or can move to
+    pub const fn is_fog_of_war(&self) -> bool {
+        self.fog_of_war
     }
 
     /// Add a piece to the board
@@ -52,6 +180,192 @@ impl<Set: PieceSet> Game<Set> {
         Ok(partial_delta)
     }
 
+    /// Add many pieces to the board, only regenerating the valid moves once
+    ///
+    /// # Errors
+    /// - [`Error<Set>::CoordinateNotOnBoard`] - A position is not on the board
+    /// - [`Error<Set>::SpotOccupied`] - A piece is already in a position
+    /// - [`Error<Set>::PieceError`] - Error from a piece
+    pub fn add_pieces(
+        &mut self,
+        pieces: impl IntoIterator<Item = (Set, Coordinate)>,
+    ) -> Result<Vec<PartialDelta<Set::PieceId>>, Error<Set>> {
+        let mut partial_deltas = Vec::new();
+
+        for (piece, position) in pieces {
+            let spot = self.board.get(&position)?;
+            if spot.is_occupied() {
+                return Err(Error::SpotOccupied(position, Some(piece)));
+            }
+
+            partial_deltas.push(self.board.apply_delta(Delta::Replace(position, piece))?);
+        }
+
+        // Regenerate the valid moves
+        self.generate_valid_moves()?;
+        Ok(partial_deltas)
+    }
+
+    /// Remove a piece from the board
+    ///
+    /// # Errors
+    /// - [`Error<Set>::CoordinateNotOnBoard`] - `position` is not on the board
+    /// - [`Error<Set>::NoPieceAtSpot`] - There is no piece in the `position`
+    /// - [`Error<Set>::PieceError`] - Error from a piece
+    pub fn remove_piece(
+        &mut self,
+        position: &Coordinate,
+    ) -> Result<PartialDelta<Set::PieceId>, Error<Set>> {
+        let partial_delta = self.board.apply_delta(Delta::Delete(*position))?;
+
+        self.generate_valid_moves()?;
+        Ok(partial_delta)
+    }
+
+    /// Move a piece to an empty spot, without it being a move in the game
+    ///
+    /// # Errors
+    /// - [`Error<Set>::CoordinateNotOnBoard`] - `from` or `to` is not on the board
+    /// - [`Error<Set>::NoPieceAtSpot`] - There is no piece in `from`
+    /// - [`Error<Set>::SpotOccupied`] - A piece is already in `to`
+    /// - [`Error<Set>::PieceError`] - Error from a piece
+    pub fn move_piece(
+        &mut self,
+        from: &Coordinate,
+        to: &Coordinate,
+    ) -> Result<PartialDelta<Set::PieceId>, Error<Set>> {
+        if self.board.get(to)?.is_occupied() {
+            return Err(Error::SpotOccupied(*to, None));
+        }
+
+        let partial_delta = self.board.apply_delta(Delta::Move(*from, *to))?;
+
+        self.generate_valid_moves()?;
+        Ok(partial_delta)
+    }
+
+    /// Place a piece on the board, replacing any piece already there
+    ///
+    /// # Errors
+    /// - [`Error<Set>::CoordinateNotOnBoard`] - `position` is not on the board
+    /// - [`Error<Set>::PieceError`] - Error from a piece
+    pub fn replace_piece(
+        &mut self,
+        piece: Set,
+        position: &Coordinate,
+    ) -> Result<PartialDelta<Set::PieceId>, Error<Set>> {
+        let partial_delta = self.board.apply_delta(Delta::Replace(*position, piece))?;
+
+        self.generate_valid_moves()?;
+        Ok(partial_delta)
+    }
+
+    /// Set the player whose turn it is
+    ///
+    /// # Errors
+    /// - [`Error<Set>::UnknownPlayer`] - `player` is not in the game
+    /// - [`Error<Set>::PieceError`] - Error from a piece
+    pub fn set_current_player(&mut self, player: u8) -> Result<(), Error<Set>> {
+        if usize::from(player) >= self.players.len() {
+            return Err(Error::UnknownPlayer(player));
+        }
+
+        self.turn.1 = player;
+        self.neutral_phase = false;
+
+        self.generate_valid_moves()
+    }
+
+    /// Set a player's score
+    ///
+    /// # Errors
+    /// - [`Error<Set>::UnknownPlayer`] - `player` is not in the game
+    pub fn set_score(&mut self, player: u8, score: u16) -> Result<(), Error<Set>> {
+        let (_, player_score) = self
+            .players
+            .get_mut(usize::from(player))
+            .ok_or(Error::UnknownPlayer(player))?;
+        *player_score = score;
+
+        Ok(())
+    }
+
+    /// Set whether a player is in play
+    ///
+    /// # Errors
+    /// - [`Error<Set>::UnknownPlayer`] - `player` is not in the game
+    /// - [`Error<Set>::PieceError`] - Error from a piece
+    pub fn set_in_play(&mut self, player: u8, in_play: bool) -> Result<(), Error<Set>> {
+        let (is_in_play, _) = self
+            .players
+            .get_mut(usize::from(player))
+            .ok_or(Error::UnknownPlayer(player))?;
+        *is_in_play = in_play;
+
+        // The number of players in play affects some moves
+        self.generate_valid_moves()
+    }
+
+    #[must_use]
+    /// Get whether a player is in play
+    pub fn is_in_play(&self, player: u8) -> bool {
+        self.players
+            .get(usize::from(player))
+            .is_some_and(|&(is_in_play, _)| is_in_play)
+    }
+
+    /// Get the reasons the position could not be reached in a game
+    ///
+    /// # Errors
+    /// - [`Error<Set>::PieceError`] - Error from a piece
+    pub fn validate(&self) -> Result<Vec<InvalidPosition>, Error<Set>> {
+        let mut invalid = Vec::new();
+
+        if !self.is_in_play(self.turn.1) {
+            invalid.push(InvalidPosition::CurrentPlayerOutOfPlay(self.turn.1));
+        }
+
+        // Every piece must belong to a player in the game, or be neutral
+        for (y, rank) in self.board.raw().iter().enumerate() {
+            for (x, spot) in rank.iter().enumerate() {
+                let Some(piece) = spot.get() else { continue };
+                let player = piece.player();
+
+                if player != NEUTRAL && usize::from(player) >= self.players.len() {
+                    invalid.push(InvalidPosition::UnknownPlayer(Coordinate(x, y), player));
+                }
+            }
+        }
+
+        // Unless the game has no royal pieces, every player in play must have one
+        let royal_players = self.board.royal_players()?;
+        if !royal_players.is_empty() {
+            for player in 0..self.n_players() {
+                if self.is_in_play(player) && !royal_players.contains(&player) {
+                    invalid.push(InvalidPosition::NoRoyalPiece(player));
+                }
+            }
+        }
+
+        // The player who moved last cannot have left themselves in check, though with
+        // more than two players, others can still be in check from earlier moves
+        let last_mover = if self.neutral_phase {
+            Some(self.turn.1)
+        } else {
+            (0..self.turn.1)
+                .rev()
+                .chain((self.turn.1 + 1..self.n_players()).rev())
+                .find(|&player| self.is_in_play(player))
+        };
+        if let Some(player) = last_mover {
+            if self.board.is_player_in_check(player)? {
+                invalid.push(InvalidPosition::InCheckOutOfTurn(player));
+            }
+        }
+
+        Ok(invalid)
+    }
+
     /// Generate the valid moves for the current player
     ///
     /// # Errors
@@ -61,22 +375,26 @@ impl<Set: PieceSet> Game<Set> {
         self.valid_moves = Vec::new();
 
         // Get the number of players still in play
-        let n_players_in_play = u8::try_from(
-            self.players
-                .iter()
-                .filter(|&&(is_in_play, _)| is_in_play)
-                .count(),
-        )
-        .expect("exceeded maximum number of players in play");
+        let n_players_in_play = self.n_players_in_play();
+
+        // After their own move, the current player moves the neutral pieces
+        let owner = if self.neutral_phase {
+            NEUTRAL
+        } else {
+            self.turn.1
+        };
+
+        // The players with royal pieces are the same before each move
+        let royal_players = self.board.royal_players()?;
 
         for (y, rank) in self.board.raw().iter().enumerate() {
             for (x, spot) in rank.iter().enumerate() {
                 let Some(piece) = spot.get() else { continue };
-                if piece.player() != self.turn.1 {
+                if piece.player() != owner {
                     continue;
                 }
 
-                // For each piece owned by the current player:
+                // For each piece moved by the current player:
 
                 let from = Coordinate(x, y);
 
@@ -89,11 +407,11 @@ impl<Set: PieceSet> Game<Set> {
                         from,
                         to,
                         data,
-                        player: piece.player(),
+                        player: self.turn.1,
                     };
 
                     // Attempt the move, if it is valid, add it to the valid moves
-                    if self.attempt_move(&r#move)?.is_some() {
+                    if self.attempt_move_with(&r#move, &royal_players)?.is_some() {
                         self.valid_moves.push((Coordinate(x, y), to, data));
                     };
                 }
@@ -103,12 +421,40 @@ impl<Set: PieceSet> Game<Set> {
         Ok(())
     }
 
+    /// Get the number of players still in play
+    fn n_players_in_play(&self) -> u8 {
+        u8::try_from(
+            self.players
+                .iter()
+                .filter(|&&(is_in_play, _)| is_in_play)
+                .count(),
+        )
+        .expect("exceeded maximum number of players in play")
+    }
+
     #[must_use]
     /// Get the valid moves for the current player
     pub const fn valid_moves(&self) -> &Vec<PartialMove> {
         &self.valid_moves
     }
 
+    /// Choose one of the current player's valid moves at random
+    ///
+    /// Returns `None` if the current player has no valid moves
+    pub fn random_move(&self, rng: &mut Rng) -> Option<Move> {
+        if self.valid_moves.is_empty() {
+            return None;
+        }
+
+        let (from, to, data) = self.valid_moves[rng.below(self.valid_moves.len())];
+        Some(Move {
+            from,
+            to,
+            data,
+            player: self.turn.1,
+        })
+    }
+
     /// Attempt a move on the board
     ///
     /// Returns an option with the board and partial moves if the move can be made
@@ -119,6 +465,15 @@ impl<Set: PieceSet> Game<Set> {
     pub fn attempt_move(
         &self,
         r#move: &Move,
+    ) -> Result<AttemptedMove<Set, Set::PieceId>, Error<Set>> {
+        self.attempt_move_with(r#move, &self.board.royal_players()?)
+    }
+
+    /// Attempt a move on the board, given the players with royal pieces before the move
+    fn attempt_move_with(
+        &self,
+        r#move: &Move,
+        royal_players: &[u8],
     ) -> Result<AttemptedMove<Set, Set::PieceId>, Error<Set>> {
         // Make sure the move is by the current player
         if r#move.player != self.turn.1 {
@@ -126,13 +481,7 @@ impl<Set: PieceSet> Game<Set> {
         }
 
         // Get the number of players still in play
-        let n_players_in_play = u8::try_from(
-            self.players
-                .iter()
-                .filter(|&&(is_in_play, _)| is_in_play)
-                .count(),
-        )
-        .expect("exceeded maximum number of players in play");
+        let n_players_in_play = self.n_players_in_play();
 
         // Clone the board and attempt the move
         let mut new_state = self.board.clone();
@@ -144,6 +493,15 @@ impl<Set: PieceSet> Game<Set> {
             return Ok(None);
         }
 
+        // Make sure the player did not lose their royal pieces,
+        // which only moves that delete pieces (e.g. explosions) can do
+        if royal_players.contains(&r#move.player)
+            && deletes_pieces(&partial_deltas)
+            && !new_state.royal_players()?.contains(&r#move.player)
+        {
+            return Ok(None);
+        }
+
         Ok(Some((new_state, partial_deltas, points)))
     }
 
@@ -157,7 +515,7 @@ impl<Set: PieceSet> Game<Set> {
         self.turn.0 += 1;
 
         // Update which player's turn it is
-        for i in 0..players_n {
+        for i in 1..=players_n {
             let j = (self.turn.1 + i).rem_euclid(players_n);
             if let Some(&(is_in_game, _)) = self.players.get(j as usize) {
                 if is_in_game {
@@ -168,12 +526,37 @@ impl<Set: PieceSet> Game<Set> {
         }
     }
 
-    /// Start the next turn by incrementing turn,
-    /// checking for check and generating the valid moves
+    /// Start the next turn
+    ///
+    /// After a player's own move, if any neutral pieces can be moved,
+    /// the same player must move one of them before the next player's turn
     ///
     /// # Errors
     /// - [`Error<Set>::PieceError`] - An error from a piece
     pub fn start_turn(&mut self) -> Result<Vec<PartialDelta<Set::PieceId>>, Error<Set>> {
+        // Without neutral pieces, there is no need to look for their moves
+        if !self.neutral_phase && self.board.has_neutral_pieces() {
+            self.neutral_phase = true;
+            self.generate_valid_moves()?;
+
+            if !self.valid_moves.is_empty() {
+                return Ok(Vec::new());
+            }
+        }
+
+        self.neutral_phase = false;
+        self.start_next_player_turn()
+    }
+
+    #[must_use]
+    /// Get whether the current player is moving the neutral pieces
+    pub const fn is_neutral_phase(&self) -> bool {
+        self.neutral_phase
+    }
+
+    /// Start the next player's turn by incrementing turn,
+    /// checking for check and generating the valid moves
+    fn start_next_player_turn(&mut self) -> Result<Vec<PartialDelta<Set::PieceId>>, Error<Set>> {
         self.increment_turn();
 
         // Check if the current player is in check
@@ -206,25 +589,62 @@ impl<Set: PieceSet> Game<Set> {
             }
 
             // Start the next turn
-            partial_deltas.extend(self.start_turn()?);
+            partial_deltas.extend(self.start_next_player_turn()?);
         }
 
         Ok(partial_deltas)
     }
 
-    /// Make a move
+    /// Make a move, charging the time taken to the player's clock
     ///
     /// # Errors
     /// - [`Error<Set>::CoordinateNotOnBoard`] - A coordinate in `move` is not on the board
+    /// - [`Error<Set>::OutOfTime`] - The player ran out of time before the move
+    /// - [`Error<Set>::GameOver`] - The game has ended
     /// - [`Error<Set>::PieceError`] - An error from a piece
     pub fn make_move(
         &mut self,
         r#move: &Move,
     ) -> Result<Vec<PartialDelta<Set::PieceId>>, Error<Set>> {
+        self.make_move_at(r#move, Instant::now())
+    }
+
+    /// Make a move at a time, charging the time since the last move to the player's clock
+    ///
+    /// # Errors
+    /// - [`Error<Set>::CoordinateNotOnBoard`] - A coordinate in `move` is not on the board
+    /// - [`Error<Set>::OutOfTime`] - The player ran out of time before the move
+    /// - [`Error<Set>::GameOver`] - The game has ended
+    /// - [`Error<Set>::PieceError`] - An error from a piece
+    pub fn make_move_at(
+        &mut self,
+        r#move: &Move,
+        now: Instant,
+    ) -> Result<Vec<PartialDelta<Set::PieceId>>, Error<Set>> {
+        // Games set up with one player, to try out positions, never end
+        if self.players.len() > 1 && self.is_over() {
+            return Err(Error::GameOver);
+        }
+
+        if self
+            .clock
+            .as_ref()
+            .is_some_and(|clock| clock.is_flagged(self.turn.1, now))
+        {
+            return Err(Error::OutOfTime(self.turn.1));
+        }
+
         // Attempt the move, and update the board if successful
-        let Some((new_state, partial_deltas, points)) = self.attempt_move(r#move)? else {
+        let royal_players = self.board.royal_players()?;
+        let Some((new_state, mut partial_deltas, points)) =
+            self.attempt_move_with(r#move, &royal_players)?
+        else {
             return Err(Error::InvalidMove(*r#move));
         };
+        if let Some(clock) = &mut self.clock {
+            clock.charge(self.turn.1, !self.neutral_phase, now);
+        }
+        let captures = self.board.get(&r#move.to)?.is_occupied();
         self.board = new_state;
 
         // Update the player's score
@@ -234,26 +654,452 @@ impl<Set: PieceSet> Game<Set> {
             .expect("exceeded maximum number of players in game");
         *score += points;
 
+        // Eliminate the players who lost their royal pieces
+        let remaining_royal_players = if captures || deletes_pieces(&partial_deltas) {
+            self.board.royal_players()?
+        } else {
+            royal_players.clone()
+        };
+        for player in royal_players {
+            if remaining_royal_players.contains(&player) {
+                continue;
+            }
+
+            partial_deltas.extend(self.eliminate(player)?);
+        }
+
+        // Evaluate the win and elimination conditions
+        let mut outcomes = Vec::new();
+        for condition in &mut self.conditions {
+            outcomes.extend(condition.after_move(&self.board, r#move)?);
+        }
+
+        for outcome in outcomes {
+            match outcome {
+                Outcome::Won(player) => {
+                    partial_deltas.push(PartialDelta::Won(player));
+
+                    // Every other player is out of play
+                    for (other, (is_in_game, _)) in self.players.iter_mut().enumerate() {
+                        if other != player as usize {
+                            *is_in_game = false;
+                        }
+                    }
+                }
+                Outcome::Eliminated(player) => {
+                    if self.players.get(player as usize).is_some_and(|&(is_in_game, _)| is_in_game) {
+                        partial_deltas.extend(self.eliminate(player)?);
+                    }
+                }
+            }
+        }
+
+        Ok(partial_deltas)
+    }
+
+    /// Remove a player's pieces and set them as out of play
+    fn eliminate(&mut self, player: u8) -> Result<Vec<PartialDelta<Set::PieceId>>, Error<Set>> {
+        let mut partial_deltas = self.board.remove_player(player)?;
+        partial_deltas.push(PartialDelta::Eliminated(player));
+
+        if let Some((is_in_game, _)) = self.players.get_mut(player as usize) {
+            *is_in_game = false;
+        }
+
         Ok(partial_deltas)
     }
 
+    #[must_use]
+    /// Get whether at most one player is still in play
+    pub fn is_over(&self) -> bool {
+        self.n_players_in_play() <= 1
+    }
+
     #[must_use]
     /// Get the board
     pub const fn board(&self) -> &Board<Set> {
         &self.board
     }
 
-    /// Remove a player from the game
+    #[must_use]
+    /// Get the number of players, including those no longer in play
+    ///
+    /// # Panics
+    /// Will panic if there are more than 255 players
+    pub fn n_players(&self) -> u8 {
+        u8::try_from(self.players.len()).expect("exceeded maximum number of players in game")
+    }
+
+    #[must_use]
+    /// Get the player whose turn it is
+    pub const fn current_player(&self) -> u8 {
+        self.turn.1
+    }
+
+    #[must_use]
+    /// Get the score of each player
+    pub fn scores(&self) -> Vec<u16> {
+        self.players.iter().map(|(_, score)| *score).collect()
+    }
+
+    /// Get the spots a player can see, in the same order as [`Board::export`]
+    ///
+    /// Without fog of war, every spot is visible
+    ///
+    /// # Errors
+    /// - [`Error<Set>::PieceError`] - An error from a piece
+    pub fn visible_spots(&self, player: u8) -> Result<Vec<bool>, Error<Set>> {
+        if !self.fog_of_war {
+            return Ok(vec![true; self.board.width() * self.board.height()]);
+        }
+
+        self.board
+            .visible_spots(player, self.turn.0, self.n_players_in_play())
+    }
+
+    /// Export the board as seen by a player, with `None` for hidden spots
+    ///
+    /// # Errors
+    /// - [`Error<Set>::PieceError`] - An error from a piece
+    pub fn export_for(
+        &self,
+        player: u8,
+    ) -> Result<VisibleExport<Set::PieceId>, Error<Set>> {
+        Ok(self.board.export_visible(&self.visible_spots(player)?))
+    }
+
+    /// Get a copy of the game as seen by a player, without the pieces on spots hidden from them
+    ///
+    /// The copy's valid moves are those valid in both the copy and the game,
+    /// so a move chosen from the copy can be made in the game.
+    /// There may be none, when every move the player sees is made invalid by a hidden piece
+    ///
+    /// # Errors
+    /// - [`Error<Set>::PieceError`] - An error from a piece
+    pub fn view_for(&self, player: u8) -> Result<Self, Error<Set>> {
+        let mut view = self.clone();
+        if !self.fog_of_war {
+            return Ok(view);
+        }
+
+        let width = self.board.width();
+        for (i, visible) in self.visible_spots(player)?.into_iter().enumerate() {
+            let position = Coordinate(i % width, i / width);
+            if !visible && view.board.get(&position)?.is_occupied() {
+                view.board.apply_delta(Delta::Delete(position))?;
+            }
+        }
+
+        view.generate_valid_moves()?;
+        view.valid_moves.retain(|r#move| self.valid_moves.contains(r#move));
+
+        Ok(view)
+    }
+
+    /// Remove a player from the game, putting them out of play
+    ///
+    /// # Errors
+    /// - [`Error<Set>::PieceError`] - An error from a piece
     pub fn remove_player(&mut self, player: u8) -> Result<Vec<PartialDelta<Set::PieceId>>, Error<Set>> {
-        // Remove the player from the board
-        let deltas = self.board.remove_player(player)?;
+        self.remove_player_at(player, Instant::now())
+    }
+
+    /// Remove a player from the game at a time, starting the next player's turn and clock
+    /// if it was their turn
+    ///
+    /// # Errors
+    /// - [`Error<Set>::PieceError`] - An error from a piece
+    pub fn remove_player_at(
+        &mut self,
+        player: u8,
+        now: Instant,
+    ) -> Result<Vec<PartialDelta<Set::PieceId>>, Error<Set>> {
+        if !self.is_in_play(player) {
+            return Ok(Vec::new());
+        }
+
+        let mut partial_deltas = self.eliminate(player)?;
 
         // If it is the player's turn, go to the next player
         if self.turn.1 == player {
-            self.increment_turn();
-            self.turn.0 -= 1;
+            if let Some(clock) = &mut self.clock {
+                clock.restart(now);
+            }
+            self.neutral_phase = false;
+            partial_deltas.extend(self.start_next_player_turn()?);
+        }
+
+        Ok(partial_deltas)
+    }
+}
+
+/// Whether a move deletes pieces, other than by moving onto them
+fn deletes_pieces<PieceId>(partial_deltas: &[PartialDelta<PieceId>]) -> bool {
+    partial_deltas
+        .iter()
+        .any(|delta| matches!(delta, PartialDelta::Delete(_)))
+}
+
+#[cfg(all(test, feature = "standard_pieces"))]
+mod test {
+    use std::time::{Duration, Instant};
+
+    use crate::{
+        clock::TimeControl,
+        coordinate::Coordinate,
+        delta::PartialDelta,
+        r#move::Move,
+        standard_pieces::{King, Rook},
+    };
+
+    use super::{Game, InvalidPosition};
+
+    #[test]
+    fn edit() {
+        const ROOK_POSITION: Coordinate = Coordinate(0, 0);
+        const KING_POSITION: Coordinate = Coordinate(0, 4);
+
+        let mut game = Game::new(2, 8, 8);
+        game.add_piece(Rook::new(0), &ROOK_POSITION)
+            .expect("failed to add rook to board");
+        game.add_piece(King::new(1), &KING_POSITION)
+            .expect("failed to add king to board");
+        assert!(
+            game.board()
+                .get(&KING_POSITION)
+                .expect("king not on board")
+                .is_attacked_by(0),
+            "rook does not attack king"
+        );
+
+        // Moving the rook off the file stops the attack
+        game.move_piece(&ROOK_POSITION, &Coordinate(1, 0))
+            .expect("failed to move rook");
+        assert!(
+            !game
+                .board()
+                .get(&KING_POSITION)
+                .expect("king not on board")
+                .is_attacked_by(0),
+            "moved rook still attacks king"
+        );
+        assert!(
+            game.move_piece(&ROOK_POSITION, &Coordinate(2, 0)).is_err(),
+            "moved a piece from an empty spot"
+        );
+
+        // Replacing the rook with a king's rook on the king's file restores the attack
+        game.remove_piece(&Coordinate(1, 0))
+            .expect("failed to remove rook");
+        assert!(
+            game.valid_moves().is_empty(),
+            "player with no pieces has moves"
+        );
+        game.replace_piece(Rook::new(0), &Coordinate(0, 7))
+            .expect("failed to replace spot");
+        assert!(
+            game.board()
+                .get(&KING_POSITION)
+                .expect("king not on board")
+                .is_attacked_by(0),
+            "replaced rook does not attack king"
+        );
+
+        game.set_current_player(1)
+            .expect("failed to set current player");
+        assert_eq!(game.current_player(), 1, "current player not set");
+        assert!(
+            game.valid_moves().iter().all(|r#move| r#move.0 == KING_POSITION),
+            "moves generated for wrong player"
+        );
+        assert!(
+            game.set_current_player(2).is_err(),
+            "set current player to a player not in the game"
+        );
+
+        game.set_score(0, 5).expect("failed to set score");
+        assert_eq!(game.scores(), vec![5, 0], "score not set");
+
+        game.set_in_play(1, false).expect("failed to set in play");
+        assert!(!game.is_in_play(1), "player still in play");
+    }
+
+    #[test]
+    fn validate() {
+        let mut game = Game::new(2, 8, 8);
+        game.add_piece(King::new(0), &Coordinate(4, 0))
+            .expect("failed to add king to board");
+        assert_eq!(
+            game.validate().expect("failed to validate"),
+            vec![InvalidPosition::NoRoyalPiece(1)],
+            "player without a king not reported"
+        );
+
+        game.add_piece(King::new(1), &Coordinate(4, 5))
+            .expect("failed to add king to board");
+        game.add_piece(Rook::new(0), &Coordinate(0, 5))
+            .expect("failed to add rook to board");
+        assert_eq!(
+            game.validate().expect("failed to validate"),
+            vec![InvalidPosition::InCheckOutOfTurn(1)],
+            "player in check out of turn not reported"
+        );
+
+        game.set_current_player(1)
+            .expect("failed to set current player");
+        game.add_piece(Rook::new(2), &Coordinate(7, 3))
+            .expect("failed to add rook to board");
+        assert_eq!(
+            game.validate().expect("failed to validate"),
+            vec![InvalidPosition::UnknownPlayer(Coordinate(7, 3), 2)],
+            "piece of unknown player not reported"
+        );
+
+        game.remove_piece(&Coordinate(7, 3))
+            .expect("failed to remove rook");
+        assert!(
+            game.validate().expect("failed to validate").is_empty(),
+            "valid position reported as invalid"
+        );
+
+        // With more players, a player can stay in check while others move
+        let mut game = Game::new(3, 8, 24);
+        for player in 0..3 {
+            game.add_piece(King::new(player), &Coordinate(4, usize::from(player) * 8))
+                .expect("failed to add king to board");
+        }
+        game.add_piece(Rook::new(0), &Coordinate(0, 16))
+            .expect("failed to add rook to board");
+        game.set_current_player(1)
+            .expect("failed to set current player");
+        assert!(
+            game.validate().expect("failed to validate").is_empty(),
+            "player checked before the last move reported"
+        );
+    }
+
+    #[test]
+    fn view_for() {
+        let mut game = Game::new(2, 8, 16);
+        game.add_pieces([
+            (King::new(0), Coordinate(0, 0)),
+            (Rook::new(0), Coordinate(2, 4)),
+            (King::new(1), Coordinate(4, 8)),
+            (Rook::new(1), Coordinate(7, 8)),
+        ])
+        .expect("failed to add pieces to board");
+
+        let view = game.view_for(0).expect("failed to get view");
+        assert!(
+            view.board().get(&Coordinate(7, 8)).expect("coordinate not on board").is_occupied(),
+            "piece hidden without fog of war"
+        );
+
+        game.set_fog_of_war(true);
+        let view = game.view_for(0).expect("failed to get view");
+        let (_, _, spots) = view.board().export();
+        assert_eq!(
+            spots.iter().filter(|&&(_, type_id)| type_id != 0).count(),
+            2,
+            "hidden pieces left in view"
+        );
+        assert!(
+            !view.valid_moves().is_empty()
+                && view.valid_moves().iter().all(|r#move| game.valid_moves().contains(r#move)),
+            "view has moves not valid in the game"
+        );
+    }
+
+    #[test]
+    fn timeout() {
+        let second = Duration::from_secs(1);
+        let start = Instant::now();
+
+        let mut game = Game::new(3, 8, 24);
+        for player in 0..3 {
+            game.add_piece(King::new(player), &Coordinate(4, usize::from(player) * 8))
+                .expect("failed to add king to board");
+        }
+        game.set_time_control(Some(TimeControl::PerMove(second * 2)));
+        game.set_current_player(0)
+            .expect("failed to set current player");
+        game.start_clock(start);
+
+        game.make_move_at(
+            &Move {
+                from: Coordinate(4, 0),
+                to: Coordinate(4, 1),
+                data: 0,
+                player: 0,
+            },
+            start + second,
+        )
+        .expect("failed to make move");
+        game.start_turn().expect("failed to start turn");
+        assert_eq!(
+            game.remaining_time(1, start + second * 2),
+            Some(second),
+            "wrong time remaining"
+        );
+        assert!(
+            game.check_timeout(start + second * 2)
+                .expect("failed to check timeout")
+                .is_empty(),
+            "player timed out early"
+        );
+
+        // Player 1 runs out of time, and is out of play like checkmate
+        let deltas = game
+            .check_timeout(start + second * 3)
+            .expect("failed to check timeout");
+        assert!(
+            deltas.iter().any(|delta| matches!(delta, PartialDelta::Timeout(1))),
+            "no timeout delta"
+        );
+        assert!(!game.is_in_play(1), "timed out player still in play");
+        assert_eq!(game.current_player(), 2, "turn did not pass to the next player");
+        assert!(
+            game.board().get(&Coordinate(4, 8)).expect("spot not on board").get().is_none(),
+            "timed out player's pieces not removed"
+        );
+        assert_eq!(
+            game.remaining_time(2, start + second * 4),
+            Some(second),
+            "next player's clock did not start at the timeout"
+        );
+    }
+
+    #[test]
+    fn remove_player() {
+        let mut game = Game::new(3, 8, 24);
+        for player in 0..3 {
+            game.add_piece(King::new(player), &Coordinate(4, usize::from(player) * 8))
+                .expect("failed to add king to board");
         }
+        game.set_current_player(1)
+            .expect("failed to set current player");
+
+        // Removing a player whose turn it is not keeps the turn
+        let deltas = game.remove_player(0).expect("failed to remove player");
+        assert!(
+            deltas.iter().any(|delta| matches!(delta, PartialDelta::Eliminated(0))),
+            "no elimination delta"
+        );
+        assert!(!game.is_in_play(0), "removed player still in play");
+        assert_eq!(game.current_player(), 1, "turn changed");
+        assert!(
+            game.board().get(&Coordinate(4, 0)).expect("spot not on board").get().is_none(),
+            "removed player's pieces not removed"
+        );
+        assert!(
+            game.remove_player(0).expect("failed to remove player").is_empty(),
+            "removed player removed again"
+        );
 
-        Ok(deltas)
+        // Removing the current player passes the turn on, skipping players out of play
+        game.remove_player(1).expect("failed to remove player");
+        assert_eq!(game.current_player(), 2, "turn did not pass to the next player");
+        assert!(!game.valid_moves().is_empty(), "next player has no valid moves");
+        assert!(game.is_over(), "game not over with one player left");
     }
 }
@@ -6,14 +6,26 @@
     clippy::cargo
 )]
 
+pub mod attack;
 pub mod board;
+pub mod clock;
+pub mod condition;
 pub mod coordinate;
 pub mod delta;
+#[cfg(feature = "standard_pieces")]
+pub mod engine;
 pub mod error;
 pub mod game;
+pub mod mcts;
 pub mod r#move;
 pub mod piece_set;
+pub mod rng;
+#[cfg(feature = "standard_pieces")]
+pub mod simulation;
 pub mod spot;
+pub mod tactics;
+#[cfg(feature = "standard_pieces")]
+pub mod tablebase;
 
 #[cfg(feature = "standard_pieces")]
 pub mod standard_pieces;
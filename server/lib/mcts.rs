@@ -0,0 +1,418 @@
+use std::time::{Duration, Instant};
+
+use crate::{
+    error::Error,
+    game::Game,
+    piece_set::{PieceSet, NEUTRAL},
+    r#move::Move,
+    rng::Rng,
+};
+
+/// The fraction of a player's remaining clock to search for, so they do not run out of time
+pub const REMAINING_FRACTION: u32 = 20;
+
+/// How long a search runs for, which is always at least one iteration
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Budget {
+    /// A fixed number of iterations, for reproducible searches
+    Iterations(u32),
+    /// A length of time
+    Time(Duration),
+}
+
+/// How moves are chosen during a playout
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Playout {
+    /// Any valid move
+    Random,
+    /// Captures of the most valuable piece when possible, otherwise any valid move
+    Captures,
+}
+
+/// A function scoring a game for each player, between 0 and 1
+pub type Reward<Set> = fn(&Game<Set>) -> Vec<f64>;
+
+/// The search statistics for a move from the root position
+#[derive(Clone, Copy, Debug)]
+pub struct MoveStatistics {
+    pub r#move: Move,
+    pub visits: u32,
+    /// The mean reward of the player making the move
+    pub mean_reward: f64,
+}
+
+/// The result of a search
+#[derive(Clone, Debug)]
+pub struct Search {
+    /// The most visited move
+    pub r#move: Move,
+    pub iterations: u32,
+    pub statistics: Vec<MoveStatistics>,
+}
+
+/// A node in the search tree
+struct Node {
+    /// The move made to reach the node, and the player who made it
+    r#move: Option<Move>,
+    children: Vec<usize>,
+    untried: Vec<Move>,
+    visits: u32,
+    rewards: Vec<f64>,
+}
+
+impl Node {
+    fn new<Set: PieceSet + 'static>(r#move: Option<Move>, game: &Game<Set>) -> Self {
+        Self {
+            r#move,
+            children: Vec::new(),
+            untried: if game.is_over() {
+                Vec::new()
+            } else {
+                moves(game)
+            },
+            visits: 0,
+            rewards: vec![0.; usize::from(game.n_players())],
+        }
+    }
+
+    /// Get the mean reward of a player
+    fn mean_reward(&self, player: u8) -> f64 {
+        if self.visits == 0 {
+            return 0.;
+        }
+
+        self.rewards.get(usize::from(player)).copied().unwrap_or_default() / f64::from(self.visits)
+    }
+}
+
+/// A Monte Carlo tree search player, for any number of players
+///
+/// Each node keeps a reward for every player, and a move is selected (UCT)
+/// by the reward of the player making it.
+pub struct Mcts<Set: PieceSet> {
+    rng: Rng,
+    budget: Budget,
+    exploration: f64,
+    playout: Playout,
+    playout_depth: u16,
+    reward: Reward<Set>,
+}
+
+impl<Set: PieceSet + 'static> Mcts<Set> {
+    #[must_use]
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: Rng::new(seed),
+            budget: Budget::Iterations(1000),
+            exploration: std::f64::consts::SQRT_2,
+            playout: Playout::Captures,
+            playout_depth: 64,
+            reward: in_play_reward,
+        }
+    }
+
+    /// Set how long each search runs for
+    pub const fn set_budget(&mut self, budget: Budget) {
+        self.budget = budget;
+    }
+
+    /// Set the UCT exploration constant
+    pub const fn set_exploration(&mut self, exploration: f64) {
+        self.exploration = exploration;
+    }
+
+    /// Set how moves are chosen during a playout
+    pub const fn set_playout(&mut self, playout: Playout) {
+        self.playout = playout;
+    }
+
+    /// Set the maximum number of moves in a playout, before the game is scored
+    pub const fn set_playout_depth(&mut self, playout_depth: u16) {
+        self.playout_depth = playout_depth;
+    }
+
+    /// Set how games are scored at the end of a playout
+    pub fn set_reward(&mut self, reward: Reward<Set>) {
+        self.reward = reward;
+    }
+
+    /// Search for the best move for the current player
+    ///
+    /// Returns `None` if the current player has no valid moves
+    ///
+    /// # Errors
+    /// - [`Error<Set>::PieceError`] - Error from a piece
+    pub fn search(&mut self, game: &Game<Set>) -> Result<Option<Search>, Error<Set>> {
+        // The search's moves do not use the game's clock
+        let mut root = game.clone();
+        root.set_time_control(None);
+        let game = &root;
+
+        let mut tree = vec![Node::new(None, game)];
+        if tree[0].untried.is_empty() {
+            return Ok(None);
+        }
+
+        let start = Instant::now();
+        let mut iterations = 0;
+        while iterations == 0
+            || match self.budget {
+                Budget::Iterations(budget) => iterations < budget,
+                Budget::Time(budget) => start.elapsed() < budget,
+            }
+        {
+            self.iterate(&mut tree, game)?;
+            iterations += 1;
+        }
+
+        // Choose the most visited move
+        let player = game.current_player();
+        let statistics: Vec<MoveStatistics> = tree[0]
+            .children
+            .iter()
+            .filter_map(|&child| {
+                let node = &tree[child];
+                Some(MoveStatistics {
+                    r#move: node.r#move?,
+                    visits: node.visits,
+                    mean_reward: node.mean_reward(player),
+                })
+            })
+            .collect();
+        let Some(best) = statistics.iter().max_by_key(|statistic| statistic.visits) else {
+            return Ok(None);
+        };
+
+        Ok(Some(Search {
+            r#move: best.r#move,
+            iterations,
+            statistics,
+        }))
+    }
+
+    /// Run one iteration of selection, expansion, playout and backpropagation
+    fn iterate(&mut self, tree: &mut Vec<Node>, root: &Game<Set>) -> Result<(), Error<Set>> {
+        let mut game = root.clone();
+        let mut path = vec![0];
+        let mut node = 0;
+
+        // Selection
+        while tree[node].untried.is_empty() && !tree[node].children.is_empty() {
+            let parent_visits = f64::from(tree[node].visits.max(1)).ln();
+            let mut best = (f64::NEG_INFINITY, node);
+
+            for &child in &tree[node].children {
+                let child_node = &tree[child];
+                let player = child_node.r#move.map_or(NEUTRAL, |r#move| r#move.player);
+                let exploration = (parent_visits / f64::from(child_node.visits.max(1))).sqrt();
+                let uct = self
+                    .exploration
+                    .mul_add(exploration, child_node.mean_reward(player));
+
+                if uct > best.0 {
+                    best = (uct, child);
+                }
+            }
+
+            node = best.1;
+            if let Some(r#move) = tree[node].r#move {
+                play(&mut game, &r#move)?;
+            }
+            path.push(node);
+        }
+
+        // Expansion
+        if !tree[node].untried.is_empty() {
+            let index = self.rng.below(tree[node].untried.len());
+            let r#move = tree[node].untried.swap_remove(index);
+            play(&mut game, &r#move)?;
+
+            tree.push(Node::new(Some(r#move), &game));
+            let child = tree.len() - 1;
+            tree[node].children.push(child);
+            path.push(child);
+        }
+
+        // Playout
+        for _ in 0..self.playout_depth {
+            if game.is_over() {
+                break;
+            }
+
+            let Some(r#move) = self.playout_move(&game) else { break };
+            play(&mut game, &r#move)?;
+        }
+
+        // Backpropagation
+        let rewards = (self.reward)(&game);
+        for node in path {
+            let node = &mut tree[node];
+            node.visits += 1;
+            for (total, reward) in node.rewards.iter_mut().zip(&rewards) {
+                *total += reward;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Choose a move for the current player during a playout
+    fn playout_move(&mut self, game: &Game<Set>) -> Option<Move> {
+        let moves = moves(game);
+        if moves.is_empty() {
+            return None;
+        }
+
+        if self.playout == Playout::Captures {
+            let capture = moves
+                .iter()
+                .filter_map(|r#move| {
+                    let piece = game.board().get(&r#move.to).ok()?.get().as_ref()?;
+                    if piece.player() == r#move.player {
+                        return None;
+                    }
+                    Some((piece.capture_points()?, r#move))
+                })
+                .max_by_key(|&(points, _)| points);
+
+            if let Some((_, r#move)) = capture {
+                return Some(*r#move);
+            }
+        }
+
+        Some(moves[self.rng.below(moves.len())])
+    }
+}
+
+/// Get the valid moves of the current player
+fn moves<Set: PieceSet + 'static>(game: &Game<Set>) -> Vec<Move> {
+    let player = game.current_player();
+
+    game.valid_moves()
+        .iter()
+        .map(|&(from, to, data)| Move {
+            from,
+            to,
+            data,
+            player,
+        })
+        .collect()
+}
+
+/// Make a move and start the next turn
+fn play<Set: PieceSet + 'static>(game: &mut Game<Set>, r#move: &Move) -> Result<(), Error<Set>> {
+    game.make_move(r#move)?;
+    game.start_turn()?;
+
+    Ok(())
+}
+
+/// Score a game by sharing a reward of 1 between the players still in play
+#[must_use]
+pub fn in_play_reward<Set: PieceSet + 'static>(game: &Game<Set>) -> Vec<f64> {
+    let in_play: Vec<bool> = (0..game.n_players())
+        .map(|player| game.is_in_play(player))
+        .collect();
+    let n_in_play = in_play.iter().filter(|&&is_in_play| is_in_play).count();
+
+    in_play
+        .into_iter()
+        .map(|is_in_play| {
+            if is_in_play {
+                1. / f64::from(u32::try_from(n_in_play).unwrap_or(1))
+            } else {
+                0.
+            }
+        })
+        .collect()
+}
+
+#[cfg(all(test, feature = "standard_pieces"))]
+mod test {
+    use crate::{
+        coordinate::Coordinate,
+        game::Game,
+        standard_pieces::{setup, BackRank, King, Rook, StandardCompatiblePieceSet},
+    };
+
+    use super::{Budget, Mcts};
+
+    #[test]
+    fn reproducible() {
+        let mut game = Game::<StandardCompatiblePieceSet>::new(3, 8, 24);
+        setup(&mut game, BackRank::Standard).expect("failed to set up board");
+        game.generate_valid_moves()
+            .expect("failed to generate moves");
+
+        let searches = [1, 2].map(|_| {
+            let mut mcts = Mcts::new(7);
+            mcts.set_budget(Budget::Iterations(50));
+            mcts.set_playout_depth(8);
+            mcts.search(&game)
+                .expect("failed to search")
+                .expect("no move found")
+        });
+
+        let [a, b] = &searches;
+        assert_eq!(a.iterations, 50, "wrong number of iterations");
+        assert!(
+            game.valid_moves().iter().any(|&(from, to, data)| {
+                from == a.r#move.from && to == a.r#move.to && data == a.r#move.data
+            }),
+            "chosen move not valid"
+        );
+        assert!(
+            a.statistics
+                .iter()
+                .zip(&b.statistics)
+                .all(|(a, b)| a.r#move.to == b.r#move.to && a.visits == b.visits),
+            "same seeds gave different searches"
+        );
+        assert_eq!(
+            a.statistics.iter().map(|statistic| statistic.visits).sum::<u32>(),
+            50,
+            "visits do not add up to iterations"
+        );
+    }
+
+    #[test]
+    fn capture_king() {
+        // A king without royalty can be captured like any other piece
+        let mut game = Game::<StandardCompatiblePieceSet>::new(2, 8, 8);
+        game.add_pieces([
+            (Rook::new(0), Coordinate(0, 0)),
+            (King::new_non_royal(0), Coordinate(7, 0)),
+            (King::new_non_royal(1), Coordinate(0, 4)),
+        ])
+        .expect("failed to add pieces to board");
+
+        let mut mcts = Mcts::new(0);
+        mcts.set_budget(Budget::Iterations(200));
+        let search = mcts
+            .search(&game)
+            .expect("failed to search")
+            .expect("no move found");
+
+        assert_eq!(search.r#move.to, Coordinate(0, 4), "capture not chosen");
+    }
+
+    #[test]
+    fn empty_budget() {
+        let mut game = Game::<StandardCompatiblePieceSet>::new(2, 8, 8);
+        game.add_pieces([
+            (King::new(0), Coordinate(4, 0)),
+            (King::new(1), Coordinate(4, 7)),
+        ])
+        .expect("failed to add kings to board");
+
+        // One iteration is run even when the budget allows none
+        let mut mcts = Mcts::new(0);
+        mcts.set_budget(Budget::Iterations(0));
+        let search = mcts
+            .search(&game)
+            .expect("failed to search")
+            .expect("no move found");
+
+        assert_eq!(search.iterations, 1, "search did not run one iteration");
+    }
+}
@@ -1,6 +1,11 @@
 use crate::{board::Board, coordinate::Coordinate, delta::Delta, r#move::Move};
 use std::{error, fmt::Debug};
 
+/// The player index of neutral pieces, which belong to no player
+///
+/// Neutral pieces never attack and cannot be captured
+pub const NEUTRAL: u8 = u8::MAX;
+
 pub trait PieceSet
 where
     Self: Clone + Debug,
@@ -0,0 +1,52 @@
+/// A small deterministic random number generator (`SplitMix64`)
+///
+/// Used where a game must be reproducible from a seed
+#[derive(Clone, Debug)]
+pub struct Rng(u64);
+
+impl Rng {
+    #[must_use]
+    pub const fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    /// Get the next random number
+    pub const fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Get a random number in `0..bound`
+    ///
+    /// # Panics
+    /// Will panic if `bound` is 0
+    pub fn below(&mut self, bound: usize) -> usize {
+        assert!(bound > 0, "bound must be greater than 0");
+
+        // The modulo bias is negligible for the small bounds used
+        usize::try_from(self.next_u64() % bound as u64).expect("value below bound exceeded usize")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Rng;
+
+    #[test]
+    fn reproducible() {
+        let mut a = Rng::new(42);
+        let mut b = Rng::new(42);
+
+        for _ in 0..16 {
+            assert_eq!(a.next_u64(), b.next_u64(), "test failed: same seeds diverged");
+        }
+
+        for bound in 1..16 {
+            assert!(a.below(bound) < bound, "test failed: value not below {bound}");
+        }
+    }
+}
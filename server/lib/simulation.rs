@@ -0,0 +1,176 @@
+//! Play random games, checking the rules' invariants after every move
+
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::{
+    engine::{record::GameRecord, Position, Setup},
+    game::Game,
+    r#move::PartialMove,
+    rng::Rng,
+    standard_pieces::{setup, BackRank, StandardCompatiblePieceSet},
+};
+
+/// A random game which broke an invariant, and how to reproduce it
+#[derive(Clone, Debug, PartialEq)]
+pub struct Failure {
+    pub n_players: u8,
+    pub seed: u64,
+    /// The moves made before the failure, ending with the move which caused it
+    pub moves: Vec<PartialMove>,
+    pub reason: String,
+}
+
+impl Failure {
+    #[must_use]
+    /// Get a record of the failing game, which can be replayed
+    pub fn record(&self) -> GameRecord {
+        GameRecord {
+            tags: vec![
+                ("Event".to_owned(), "simulation".to_owned()),
+                ("Seed".to_owned(), self.seed.to_string()),
+                ("Failure".to_owned(), self.reason.replace('"', "'")),
+            ],
+            position: Position {
+                setup: Setup::Start {
+                    players: self.n_players,
+                    width: 8,
+                    rows: 8,
+                    back_rank: BackRank::Individual,
+                    seed: self.seed,
+                },
+                moves: self.moves.clone(),
+            },
+        }
+    }
+}
+
+/// A random game which kept every invariant
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Simulation {
+    pub moves: usize,
+    pub scores: Vec<u16>,
+    /// Whether the game ended before the move limit
+    pub is_over: bool,
+}
+
+/// Play a random game from the starting position, with individually shuffled back ranks,
+/// checking after every move that:
+/// - the attacks on every spot match those found by placing the pieces on an empty board
+/// - the player who moved is not in check
+/// - the position is valid, from [`Game::validate`]
+/// - no score decreased
+/// - the next turn started with a player in play, who has a move
+///
+/// # Errors
+/// - [`Failure`] - An invariant was broken, or the game returned an error or panicked
+pub fn simulate(n_players: u8, seed: u64, max_moves: usize) -> Result<Simulation, Failure> {
+    let mut moves = Vec::new();
+    let fail = |moves: &Vec<PartialMove>, reason: String| Failure {
+        n_players,
+        seed,
+        moves: moves.clone(),
+        reason,
+    };
+
+    let mut game = Game::<StandardCompatiblePieceSet>::new(n_players, 8, 8 * u16::from(n_players));
+    game.set_seed(seed);
+    setup(&mut game, BackRank::Individual).map_err(|err| fail(&moves, err.to_string()))?;
+    let mut rng = Rng::new(seed);
+
+    while moves.len() < max_moves && !game.is_over() {
+        let Some(r#move) = game.random_move(&mut rng) else { break };
+        moves.push((r#move.from, r#move.to, r#move.data));
+        let scores = game.scores();
+
+        // Make the move, catching panics from the rules
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            game.make_move(&r#move)?;
+            game.start_turn()
+        }));
+        match result {
+            Ok(Ok(_)) => {}
+            Ok(Err(err)) => return Err(fail(&moves, err.to_string())),
+            Err(payload) => {
+                let message = payload
+                    .downcast_ref::<&str>()
+                    .map(ToString::to_string)
+                    .or_else(|| payload.downcast_ref::<String>().cloned())
+                    .unwrap_or_default();
+                return Err(fail(&moves, format!("panicked: {message}")));
+            }
+        }
+
+        let attacks = game
+            .board()
+            .verify_attacks()
+            .map_err(|err| fail(&moves, err.to_string()))?;
+        if !attacks.is_empty() {
+            return Err(fail(&moves, format!("attacks differ on {attacks:?}")));
+        }
+
+        if game
+            .board()
+            .is_player_in_check(r#move.player)
+            .map_err(|err| fail(&moves, err.to_string()))?
+        {
+            return Err(fail(&moves, format!("player {} left in check", r#move.player)));
+        }
+
+        let invalid = game
+            .validate()
+            .map_err(|err| fail(&moves, err.to_string()))?;
+        if !invalid.is_empty() {
+            return Err(fail(&moves, format!("invalid position: {invalid:?}")));
+        }
+
+        if game.scores().iter().zip(&scores).any(|(new, old)| new < old) {
+            return Err(fail(&moves, format!("score decreased from {scores:?} to {:?}", game.scores())));
+        }
+
+        if !game.is_over() && !game.is_in_play(game.current_player()) {
+            return Err(fail(
+                &moves,
+                format!("turn started for player {} out of play", game.current_player()),
+            ));
+        }
+
+        // Otherwise the next move would never come, so the game would not end
+        if !game.is_over() && game.valid_moves().is_empty() {
+            return Err(fail(
+                &moves,
+                format!("turn started for player {} without moves", game.current_player()),
+            ));
+        }
+    }
+
+    Ok(Simulation {
+        moves: moves.len(),
+        scores: game.scores(),
+        is_over: game.is_over(),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::simulate;
+
+    #[test]
+    fn random_games() {
+        for n_players in [2, 3, 5] {
+            for seed in 0..2 {
+                if let Err(failure) = simulate(n_players, seed, 40) {
+                    panic!("test failed:\n{}", failure.record());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn reproducible() {
+        assert_eq!(
+            simulate(2, 7, 20),
+            simulate(2, 7, 20),
+            "same seeds gave different games"
+        );
+    }
+}
@@ -42,6 +42,19 @@ impl<Set> Spot<Set> {
         false
     }
 
+    #[must_use]
+    pub fn is_attacked_by(&self, player: u8) -> bool {
+        self.attackers
+            .iter()
+            .any(|(attacker, _, _)| attacker == &player)
+    }
+
+    #[must_use]
+    /// Get the player, position and whether the attack can be blocked, of each attack on the spot
+    pub const fn attackers(&self) -> &Vec<(u8, Coordinate, bool)> {
+        &self.attackers
+    }
+
     pub fn take(&mut self) -> Option<Set> {
         self.piece.take()
     }
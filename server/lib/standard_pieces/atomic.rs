@@ -0,0 +1,371 @@
+use crate::{
+    board::Board,
+    coordinate::{Coordinate, CoordinateDelta},
+    delta::Delta,
+    piece_set::NEUTRAL,
+    r#move::Move,
+    spot::Spot,
+};
+
+use super::{Error, StandardCompatiblePiece, StandardCompatiblePieceSet, PAWN_ID};
+
+/// A piece in an atomic game
+///
+/// Captures explode every non-pawn piece in the surrounding spots,
+/// along with the capturing piece, and royal pieces cannot capture.
+#[derive(Clone, Debug)]
+pub struct Atomic(StandardCompatiblePieceSet);
+
+impl Atomic {
+    #[must_use]
+    pub fn new(piece: StandardCompatiblePieceSet) -> StandardCompatiblePieceSet {
+        Box::new(Self(piece))
+    }
+}
+
+impl StandardCompatiblePiece for Atomic {
+    fn type_id(&self) -> u8 {
+        self.0.type_id()
+    }
+
+    fn capture_points(&self) -> Option<u16> {
+        self.0.capture_points()
+    }
+
+    fn blockable(&self) -> bool {
+        self.0.blockable()
+    }
+
+    fn player(&self) -> u8 {
+        self.0.player()
+    }
+
+    fn is_in_check(
+        &self,
+        board: &Board<StandardCompatiblePieceSet>,
+        position: &Coordinate,
+    ) -> Result<Option<bool>, Error> {
+        self.0.is_in_check(board, position)
+    }
+
+    fn attacking(
+        &self,
+        board: &Board<StandardCompatiblePieceSet>,
+        from: &Coordinate,
+    ) -> Result<Vec<Coordinate>, Error> {
+        self.0.attacking(board, from)
+    }
+
+    fn valid_moves(
+        &self,
+        board: &Board<StandardCompatiblePieceSet>,
+        from: &Coordinate,
+        turn: u16,
+        n_players: u8,
+    ) -> Result<Vec<(Coordinate, u8)>, Error> {
+        let moves = self.0.valid_moves(board, from, turn, n_players)?;
+
+        // Unless the piece is royal, it can capture
+        if self.0.is_in_check(board, from)?.is_none() {
+            return Ok(moves);
+        }
+
+        // Remove the captures
+        let mut non_captures = Vec::with_capacity(moves.len());
+        for (to, data) in moves {
+            // Castling moves onto the piece's own spot
+            if board
+                .get(&to)
+                .map_err(|err| Error::BoardError(Box::new(err)))?
+                .get()
+                .as_ref()
+                .is_none_or(|piece| piece.player() == self.player())
+            {
+                non_captures.push((to, data));
+            }
+        }
+
+        Ok(non_captures)
+    }
+
+    fn mid_move(
+        &mut self,
+        board: &Board<StandardCompatiblePieceSet>,
+        r#move: &Move,
+        turn: u16,
+        n_players: u8,
+    ) -> Result<(Vec<Delta<StandardCompatiblePieceSet>>, u16), Error> {
+        let captures_target = board
+            .get(&r#move.to)
+            .map_err(|err| Error::BoardError(Box::new(err)))?
+            .is_occupied();
+
+        let (inner_deltas, mut points) = self.0.mid_move(board, r#move, turn, n_players)?;
+
+        // Keep any new pieces atomic
+        let mut deltas: Vec<Delta<StandardCompatiblePieceSet>> = inner_deltas
+            .into_iter()
+            .map(|delta| match delta {
+                Delta::Replace(position, piece) => Delta::Replace(position, Self::new(piece)),
+                delta => delta,
+            })
+            .collect();
+
+        // Get the pieces already removed by the move (e.g. en passant)
+        let mut exploded: Vec<Coordinate> = deltas
+            .iter()
+            .filter_map(|delta| match delta {
+                Delta::Delete(position) => Some(*position),
+                _ => None,
+            })
+            .collect();
+
+        // Unless the move captures, nothing explodes
+        if !captures_target && exploded.is_empty() {
+            return Ok((deltas, points));
+        }
+
+        // Explode the surrounding pieces, the spots wrap around the tube
+        for y in -1..=1 {
+            for x in -1..=1 {
+                let Some(position) = &r#move.to + (&CoordinateDelta(x, y), board) else { continue };
+                if position == r#move.to || exploded.contains(&position) {
+                    continue;
+                }
+
+                let Ok(Some(piece)) = board.get(&position).map(Spot::get) else { continue };
+
+                // Pawns and neutral pieces survive explosions
+                if piece.type_id() == PAWN_ID || piece.player() == NEUTRAL {
+                    continue;
+                }
+
+                if piece.player() != self.player() {
+                    points += piece.capture_points().unwrap_or(0);
+                }
+
+                deltas.push(Delta::Delete(position));
+                exploded.push(position);
+            }
+        }
+
+        // The capturing piece explodes with its target, unless an
+        // inner atomic piece (e.g. a castled piece) already exploded it
+        if !exploded.contains(&r#move.to) {
+            deltas.push(Delta::Delete(r#move.to));
+        }
+
+        Ok((deltas, points))
+    }
+
+    fn clone(&self) -> StandardCompatiblePieceSet {
+        Box::new(Clone::clone(self))
+    }
+
+    fn can_en_passant(&self, intermediate: &Coordinate, turn: u16, n_players_in_play: u8) -> bool {
+        self.0.can_en_passant(intermediate, turn, n_players_in_play)
+    }
+
+    fn can_castle(&self) -> bool {
+        self.0.can_castle()
+    }
+
+    fn mid_castle(&mut self) {
+        self.0.mid_castle();
+    }
+
+    fn upgrade(&self) -> Option<(i8, usize)> {
+        self.0.upgrade()
+    }
+
+    // The wrapper is not part of the type id
+    fn is_initial(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        coordinate::Coordinate,
+        delta::PartialDelta,
+        game::Game,
+        piece_set::PieceSet,
+        r#move::Move,
+        standard_pieces::{King, Knight, Pawn, Rook, StandardCompatiblePieceSet},
+    };
+
+    use super::Atomic;
+
+    fn piece_at(game: &Game<StandardCompatiblePieceSet>, position: Coordinate) -> Option<u8> {
+        game.board()
+            .get(&position)
+            .unwrap()
+            .get()
+            .as_ref()
+            .map(PieceSet::type_id)
+    }
+
+    #[test]
+    fn explosion() {
+        const ROOK_POSITION: Coordinate = Coordinate(0, 2);
+        const TARGET_POSITION: Coordinate = Coordinate(2, 2);
+        const KNIGHT_POSITION: Coordinate = Coordinate(3, 3);
+        const PAWN_POSITION: Coordinate = Coordinate(1, 3);
+
+        let mut game = Game::new(2, 5, 5);
+        game.add_piece(Atomic::new(Rook::new(0)), &ROOK_POSITION)
+            .expect("failed to add rook to board");
+        game.add_piece(Atomic::new(Knight::new(1)), &TARGET_POSITION)
+            .expect("failed to add target to board");
+        game.add_piece(Atomic::new(Knight::new(1)), &KNIGHT_POSITION)
+            .expect("failed to add knight to board");
+        game.add_piece(Atomic::new(Pawn::new(1, 1, 20)), &PAWN_POSITION)
+            .expect("failed to add pawn to board");
+
+        let partial_deltas = game
+            .make_move(&Move {
+                from: ROOK_POSITION,
+                to: TARGET_POSITION,
+                data: 0,
+                player: 0,
+            })
+            .expect("failed to make capture");
+
+        for position in [ROOK_POSITION, TARGET_POSITION, KNIGHT_POSITION] {
+            assert!(
+                piece_at(&game, position).is_none(),
+                "test failed: {position} was not exploded"
+            );
+        }
+        assert!(
+            piece_at(&game, PAWN_POSITION) == Some(1),
+            "test failed: pawn at {PAWN_POSITION} was exploded"
+        );
+
+        for position in [KNIGHT_POSITION, TARGET_POSITION] {
+            assert!(
+                partial_deltas
+                    .iter()
+                    .any(|delta| matches!(delta, PartialDelta::Delete(p) if p == &position)),
+                "test failed: no delete delta for {position}"
+            );
+        }
+    }
+
+    #[test]
+    fn explosion_wraps() {
+        const ROOK_POSITION: Coordinate = Coordinate(0, 0);
+        const TARGET_POSITION: Coordinate = Coordinate(2, 0);
+        const KNIGHT_POSITION: Coordinate = Coordinate(1, 4);
+
+        let mut game = Game::new(2, 5, 5);
+        game.add_piece(Atomic::new(Rook::new(0)), &ROOK_POSITION)
+            .expect("failed to add rook to board");
+        game.add_piece(Atomic::new(Rook::new(1)), &TARGET_POSITION)
+            .expect("failed to add target to board");
+        game.add_piece(Atomic::new(Knight::new(1)), &KNIGHT_POSITION)
+            .expect("failed to add knight to board");
+
+        game.make_move(&Move {
+            from: ROOK_POSITION,
+            to: TARGET_POSITION,
+            data: 0,
+            player: 0,
+        })
+        .expect("failed to make capture");
+
+        assert!(
+            piece_at(&game, KNIGHT_POSITION).is_none(),
+            "test failed: {KNIGHT_POSITION} was not exploded across the seam"
+        );
+    }
+
+    #[test]
+    fn king_cannot_capture() {
+        const KING_POSITION: Coordinate = Coordinate(2, 2);
+        const KNIGHT_POSITION: Coordinate = Coordinate(3, 2);
+
+        let mut game = Game::new(2, 5, 5);
+        game.add_piece(Atomic::new(King::new(0)), &KING_POSITION)
+            .expect("failed to add king to board");
+        game.add_piece(Atomic::new(Knight::new(1)), &KNIGHT_POSITION)
+            .expect("failed to add knight to board");
+
+        game.generate_valid_moves()
+            .expect("failed to generate moves");
+        assert!(
+            !game
+                .valid_moves()
+                .iter()
+                .any(|(_, to, _)| to == &KNIGHT_POSITION),
+            "test failed: king can capture {KNIGHT_POSITION}"
+        );
+    }
+
+    #[test]
+    fn king_explodes() {
+        const ROOK_POSITION: Coordinate = Coordinate(0, 1);
+        const TARGET_POSITION: Coordinate = Coordinate(2, 1);
+        const KING_0_POSITION: Coordinate = Coordinate(0, 4);
+        const KING_1_POSITION: Coordinate = Coordinate(3, 2);
+        const KNIGHT_POSITION: Coordinate = Coordinate(4, 4);
+
+        let mut game = Game::new(2, 5, 6);
+        game.add_piece(Atomic::new(Rook::new(0)), &ROOK_POSITION)
+            .expect("failed to add rook to board");
+        game.add_piece(Atomic::new(King::new(0)), &KING_0_POSITION)
+            .expect("failed to add first king to board");
+        game.add_piece(Atomic::new(Knight::new(1)), &TARGET_POSITION)
+            .expect("failed to add target to board");
+        game.add_piece(Atomic::new(King::new(1)), &KING_1_POSITION)
+            .expect("failed to add second king to board");
+        game.add_piece(Atomic::new(Knight::new(1)), &KNIGHT_POSITION)
+            .expect("failed to add knight to board");
+
+        let partial_deltas = game
+            .make_move(&Move {
+                from: ROOK_POSITION,
+                to: TARGET_POSITION,
+                data: 0,
+                player: 0,
+            })
+            .expect("failed to make capture");
+
+        assert!(
+            partial_deltas
+                .iter()
+                .any(|delta| matches!(delta, PartialDelta::Eliminated(1))),
+            "test failed: player 1 was not eliminated"
+        );
+        assert!(
+            piece_at(&game, KNIGHT_POSITION).is_none(),
+            "test failed: eliminated player's piece at {KNIGHT_POSITION} remains"
+        );
+    }
+
+    #[test]
+    fn cannot_explode_own_king() {
+        const ROOK_POSITION: Coordinate = Coordinate(0, 2);
+        const TARGET_POSITION: Coordinate = Coordinate(2, 2);
+        const KING_POSITION: Coordinate = Coordinate(3, 3);
+
+        let mut game = Game::new(2, 5, 6);
+        game.add_piece(Atomic::new(Rook::new(0)), &ROOK_POSITION)
+            .expect("failed to add rook to board");
+        game.add_piece(Atomic::new(King::new(0)), &KING_POSITION)
+            .expect("failed to add king to board");
+        game.add_piece(Atomic::new(Knight::new(1)), &TARGET_POSITION)
+            .expect("failed to add target to board");
+
+        game.generate_valid_moves()
+            .expect("failed to generate moves");
+        assert!(
+            !game
+                .valid_moves()
+                .iter()
+                .any(|(from, to, _)| from == &ROOK_POSITION && to == &TARGET_POSITION),
+            "test failed: {ROOK_POSITION} -> {TARGET_POSITION} explodes own king"
+        );
+    }
+}
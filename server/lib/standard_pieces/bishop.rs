@@ -5,7 +5,7 @@ use crate::{
     r#move::Move,
 };
 
-use super::{StandardCompatiblePiece, StandardCompatiblePieceSet};
+use super::{BISHOP_ID, StandardCompatiblePiece, StandardCompatiblePieceSet};
 
 #[derive(Clone, Debug)]
 pub struct Bishop(u8, bool);
@@ -19,7 +19,7 @@ impl Bishop {
 
 impl StandardCompatiblePiece for Bishop {
     fn type_id(&self) -> u8 {
-        2
+        BISHOP_ID
     }
 
     fn capture_points(&self) -> Option<u16> {
@@ -0,0 +1,77 @@
+use crate::{
+    board::Board, coordinate::Coordinate, delta::Delta, piece_set::NEUTRAL, r#move::Move,
+};
+
+use super::{DUCK_ID, Error, StandardCompatiblePiece, StandardCompatiblePieceSet};
+
+/// A neutral piece which every player must move to an empty spot after their own move
+#[derive(Clone, Debug)]
+pub struct Duck;
+
+impl Duck {
+    #[must_use]
+    pub fn new() -> StandardCompatiblePieceSet {
+        Box::new(Self)
+    }
+}
+
+impl StandardCompatiblePiece for Duck {
+    fn type_id(&self) -> u8 {
+        DUCK_ID
+    }
+
+    fn capture_points(&self) -> Option<u16> {
+        None
+    }
+
+    fn blockable(&self) -> bool {
+        false
+    }
+
+    fn player(&self) -> u8 {
+        NEUTRAL
+    }
+
+    fn attacking(
+        &self,
+        _board: &Board<StandardCompatiblePieceSet>,
+        _from: &Coordinate,
+    ) -> Result<Vec<Coordinate>, Error> {
+        Ok(Vec::with_capacity(0))
+    }
+
+    fn valid_moves(
+        &self,
+        board: &Board<StandardCompatiblePieceSet>,
+        _from: &Coordinate,
+        _turn: u16,
+        _n_players: u8,
+    ) -> Result<Vec<(Coordinate, u8)>, Error> {
+        let mut moves = Vec::new();
+
+        // The duck can move to any empty spot
+        for (y, rank) in board.raw().iter().enumerate() {
+            for (x, spot) in rank.iter().enumerate() {
+                if !spot.is_occupied() {
+                    moves.push((Coordinate(x, y), 0));
+                }
+            }
+        }
+
+        Ok(moves)
+    }
+
+    fn mid_move(
+        &mut self,
+        _board: &Board<StandardCompatiblePieceSet>,
+        _move: &Move,
+        _turn: u16,
+        _n_players: u8,
+    ) -> Result<(Vec<Delta<StandardCompatiblePieceSet>>, u16), Error> {
+        Ok((Vec::with_capacity(0), 0))
+    }
+
+    fn clone(&self) -> StandardCompatiblePieceSet {
+        Box::new(Clone::clone(self))
+    }
+}
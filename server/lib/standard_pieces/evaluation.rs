@@ -0,0 +1,232 @@
+use crate::{
+    board::Board,
+    coordinate::{Coordinate, CoordinateDelta},
+    error::Error,
+    piece_set::PieceSet,
+};
+
+use super::StandardCompatiblePieceSet;
+
+/// Centipawns per capture point
+const MATERIAL: i32 = 100;
+/// Centipawns per valid move
+const MOBILITY: i32 = 4;
+/// Centipawns lost per spot next to a royal piece attacked by another player
+const ATTACKED_NEIGHBOUR: i32 = 15;
+/// Centipawns lost while a royal piece is in check
+const IN_CHECK: i32 = 50;
+/// Centipawns per rank a pawn has advanced towards its upgrade rank
+const PAWN_ADVANCE: i32 = 10;
+
+/// Piece-square tables, indexed by type id, in centipawns
+///
+/// Each table is laid out from the player's back rank (row 0) towards the next player's,
+/// and is scaled to the size of the player's section of the board.
+#[rustfmt::skip]
+const TABLES: [[[i8; 8]; 8]; 6] = [
+    // Pawn
+    [
+        [  0,   0,   0,   0,   0,   0,   0,   0],
+        [  5,  10,  10, -20, -20,  10,  10,   5],
+        [  5,  -5, -10,   0,   0, -10,  -5,   5],
+        [  0,   0,   0,  20,  20,   0,   0,   0],
+        [  5,   5,  10,  25,  25,  10,   5,   5],
+        [ 10,  10,  20,  30,  30,  20,  10,  10],
+        [ 50,  50,  50,  50,  50,  50,  50,  50],
+        [  0,   0,   0,   0,   0,   0,   0,   0],
+    ],
+    // Bishop
+    [
+        [-20, -10, -10, -10, -10, -10, -10, -20],
+        [-10,   5,   0,   0,   0,   0,   5, -10],
+        [-10,  10,  10,  10,  10,  10,  10, -10],
+        [-10,   0,  10,  10,  10,  10,   0, -10],
+        [-10,   5,   5,  10,  10,   5,   5, -10],
+        [-10,   0,   5,  10,  10,   5,   0, -10],
+        [-10,   0,   0,   0,   0,   0,   0, -10],
+        [-20, -10, -10, -10, -10, -10, -10, -20],
+    ],
+    // Knight
+    [
+        [-50, -40, -30, -30, -30, -30, -40, -50],
+        [-40, -20,   0,   5,   5,   0, -20, -40],
+        [-30,   5,  10,  15,  15,  10,   5, -30],
+        [-30,   0,  15,  20,  20,  15,   0, -30],
+        [-30,   5,  15,  20,  20,  15,   5, -30],
+        [-30,   0,  10,  15,  15,  10,   0, -30],
+        [-40, -20,   0,   0,   0,   0, -20, -40],
+        [-50, -40, -30, -30, -30, -30, -40, -50],
+    ],
+    // Rook
+    [
+        [  0,   0,   0,   5,   5,   0,   0,   0],
+        [ -5,   0,   0,   0,   0,   0,   0,  -5],
+        [ -5,   0,   0,   0,   0,   0,   0,  -5],
+        [ -5,   0,   0,   0,   0,   0,   0,  -5],
+        [ -5,   0,   0,   0,   0,   0,   0,  -5],
+        [ -5,   0,   0,   0,   0,   0,   0,  -5],
+        [  5,  10,  10,  10,  10,  10,  10,   5],
+        [  0,   0,   0,   0,   0,   0,   0,   0],
+    ],
+    // Queen
+    [
+        [-20, -10, -10,  -5,  -5, -10, -10, -20],
+        [-10,   0,   5,   0,   0,   0,   0, -10],
+        [-10,   5,   5,   5,   5,   5,   0, -10],
+        [  0,   0,   5,   5,   5,   5,   0,  -5],
+        [ -5,   0,   5,   5,   5,   5,   0,  -5],
+        [-10,   0,   5,   5,   5,   5,   0, -10],
+        [-10,   0,   0,   0,   0,   0,   0, -10],
+        [-20, -10, -10,  -5,  -5, -10, -10, -20],
+    ],
+    // King
+    [
+        [ 20,  30,  10,   0,   0,  10,  30,  20],
+        [ 20,  20,   0,   0,   0,   0,  20,  20],
+        [-10, -20, -20, -20, -20, -20, -20, -10],
+        [-20, -30, -30, -40, -40, -30, -30, -20],
+        [-30, -40, -40, -50, -50, -40, -40, -30],
+        [-30, -40, -40, -50, -50, -40, -40, -30],
+        [-30, -40, -40, -50, -50, -40, -40, -30],
+        [-30, -40, -40, -50, -50, -40, -40, -30],
+    ],
+];
+
+/// Evaluate a board, returning a score in centipawns for each player
+///
+/// Each player is assumed to own an equal section of the board, starting with their back rank,
+/// as placed by [`super::setup`]
+///
+/// # Errors
+/// - [`Error::CoordinateNotOnBoard`] - A piece attacks a coordinate not on the board
+/// - [`Error::PieceError`] - Error from a piece
+pub fn evaluate(
+    board: &Board<StandardCompatiblePieceSet>,
+    n_players: u8,
+) -> Result<Vec<i32>, Error<StandardCompatiblePieceSet>> {
+    let mut scores = vec![0; usize::from(n_players)];
+    let height = board.height();
+    let rows = height / usize::from(n_players.max(1));
+
+    for (y, rank) in board.raw().iter().enumerate() {
+        for (x, spot) in rank.iter().enumerate() {
+            let Some(piece) = spot.get() else { continue };
+            let player = piece.player();
+            let Some(score) = scores.get_mut(usize::from(player)) else { continue };
+            let position = Coordinate(x, y);
+
+            // Material
+            *score += piece
+                .capture_points()
+                .map_or(0, |points| i32::from(points) * MATERIAL);
+
+            // Mobility
+            let moves = piece
+                .valid_moves(board, &position, 0, n_players)
+                .map_err(Error::PieceError)?;
+            *score += i32::try_from(moves.len()).unwrap_or(i32::MAX) * MOBILITY;
+
+            // Position within the player's section
+            if let Some(table) = usize::from(PieceSet::type_id(piece))
+                .checked_sub(1)
+                .and_then(|index| TABLES.get(index))
+            {
+                let back_rank = usize::from(player) * rows;
+                let row = section_row(y, back_rank, height, rows);
+                let column = x * 8 / board.width();
+                *score += i32::from(table[row][column]);
+            }
+
+            // Pawn advancement
+            if let Some((direction, upgrade_rank)) = piece.upgrade() {
+                let remaining = if direction < 0 {
+                    (y + height - upgrade_rank) % height
+                } else {
+                    (upgrade_rank + height - y) % height
+                };
+                let advanced = rows.saturating_sub(remaining);
+                *score += i32::try_from(advanced).unwrap_or_default() * PAWN_ADVANCE;
+            }
+
+            // Royal safety
+            if let Some(is_in_check) = piece
+                .is_in_check(board, &position)
+                .map_err(Error::PieceError)?
+            {
+                if is_in_check {
+                    *score -= IN_CHECK;
+                }
+
+                for dx in -1..=1 {
+                    for dy in -1..=1 {
+                        let Some(neighbour) = &position + (&CoordinateDelta(dx, dy), board) else { continue };
+                        if neighbour != position && board.is_being_attacked(&neighbour, player)? {
+                            *score -= ATTACKED_NEIGHBOUR;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(scores)
+}
+
+/// Get the row of a piece-square table for a spot,
+/// scaling the player's section of the board onto the table
+fn section_row(y: usize, back_rank: usize, height: usize, rows: usize) -> usize {
+    // The distance forwards from the back rank, wrapping around the board
+    let distance = (y + height - back_rank) % height;
+
+    let row = if distance < rows {
+        distance
+    } else if distance < rows + (height - rows) / 2 {
+        // Spots ahead of the section count as its last row
+        rows.saturating_sub(1)
+    } else {
+        // Spots behind the section count as its back rank
+        0
+    };
+
+    row * 8 / rows.max(1)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{coordinate::Coordinate, game::Game};
+
+    use super::{
+        super::{setup, BackRank, Pawn},
+        evaluate,
+    };
+
+    #[test]
+    fn symmetric() {
+        for n_players in [2, 3, 4] {
+            let mut game = Game::new(n_players, 8, 8 * u16::from(n_players));
+            setup(&mut game, BackRank::Standard).expect("failed to set up board");
+
+            let scores = evaluate(game.board(), n_players).expect("failed to evaluate board");
+            assert!(
+                scores.iter().all(|score| score == &scores[0]),
+                "start position not symmetric for {n_players} players: {scores:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn advanced_pawn() {
+        let mut game = Game::new(2, 8, 16);
+        game.add_pieces([
+            (Pawn::new(0, 1, 8), Coordinate(0, 6)),
+            (Pawn::new(1, 1, 0), Coordinate(0, 10)),
+        ])
+        .expect("failed to add pieces to board");
+
+        let scores = evaluate(game.board(), 2).expect("failed to evaluate board");
+        assert!(
+            scores[0] > scores[1],
+            "advanced pawn not preferred: {scores:?}"
+        );
+    }
+}
@@ -6,25 +6,114 @@ use crate::{
     r#move::Move,
 };
 
-use super::{Error, StandardCompatiblePiece, StandardCompatiblePieceSet};
+use super::{Error, KING_ID, StandardCompatiblePiece, StandardCompatiblePieceSet};
+
+/// Move data for castling with the piece towards `x = 0`
+pub const CASTLE_LEFT: u8 = 1;
+/// Move data for castling with the piece towards `x = width - 1`
+pub const CASTLE_RIGHT: u8 = 2;
 
 #[derive(Clone, Debug)]
-pub struct King(u8, bool);
+pub struct King {
+    player: u8,
+    has_moved: bool,
+    royal: bool,
+}
 
 impl King {
     #[must_use]
     pub fn new(player: u8) -> StandardCompatiblePieceSet {
-        Box::new(Self(player, false))
+        Box::new(Self {
+            player,
+            has_moved: false,
+            royal: true,
+        })
+    }
+
+    /// Create a king which cannot be in check, and can be captured
+    #[must_use]
+    pub fn new_non_royal(player: u8) -> StandardCompatiblePieceSet {
+        Box::new(Self {
+            player,
+            has_moved: false,
+            royal: false,
+        })
+    }
+
+    /// Get the castling piece, the king's destination and the castling piece's destination
+    ///
+    /// The king ends on the third spot from its side and the castling piece next to it,
+    /// wherever they started, as long as every spot between is empty
+    fn castle_positions(
+        &self,
+        board: &Board<StandardCompatiblePieceSet>,
+        from: &Coordinate,
+        data: u8,
+    ) -> Result<Option<(Coordinate, Coordinate, Coordinate)>, Error> {
+        let width = board.width();
+        let (dir, king_x, rook_x) = match data {
+            CASTLE_LEFT => (-1, Some(2), Some(3)),
+            CASTLE_RIGHT => (1, width.checked_sub(2), width.checked_sub(3)),
+            _ => return Ok(None),
+        };
+        let (Some(king_x), Some(rook_x)) = (king_x, rook_x) else { return Ok(None) };
+        if king_x >= width || rook_x >= width {
+            return Ok(None);
+        }
+
+        // Get the first piece in that direction
+        let mut rook = None;
+        for d in 1..isize::try_from(width).map_err(|err| Error::PositionOrDeltaTooLarge(width, err))? {
+            let Some(position) = from + (&CoordinateDelta(dir * d, 0), board) else { break };
+            let Some(piece) = board
+                .get(&position)
+                .map_err(|err| Error::BoardError(Box::new(err)))?
+                .get() else { continue };
+
+            if piece.player() == self.player && piece.can_castle() {
+                rook = Some(position);
+            }
+            break;
+        }
+        let Some(rook) = rook else { return Ok(None) };
+
+        // Every spot the king and castling piece cross must be empty
+        let start = from.0.min(rook.0).min(king_x).min(rook_x);
+        let end = from.0.max(rook.0).max(king_x).max(rook_x);
+        for x in start..=end {
+            let position = Coordinate(x, from.1);
+            if position == *from || position == rook {
+                continue;
+            }
+
+            if board
+                .get(&position)
+                .map_err(|err| Error::BoardError(Box::new(err)))?
+                .is_occupied()
+            {
+                return Ok(None);
+            }
+        }
+
+        Ok(Some((
+            rook,
+            Coordinate(king_x, from.1),
+            Coordinate(rook_x, from.1),
+        )))
     }
 }
 
 impl StandardCompatiblePiece for King {
     fn type_id(&self) -> u8 {
-        6
+        KING_ID
     }
 
     fn capture_points(&self) -> Option<u16> {
-        None
+        if self.royal {
+            None
+        } else {
+            Some(0)
+        }
     }
 
     fn blockable(&self) -> bool {
@@ -32,7 +121,7 @@ impl StandardCompatiblePiece for King {
     }
 
     fn player(&self) -> u8 {
-        self.0
+        self.player
     }
 
     fn is_in_check(
@@ -40,6 +129,10 @@ impl StandardCompatiblePiece for King {
         board: &Board<StandardCompatiblePieceSet>,
         position: &Coordinate,
     ) -> Result<Option<bool>, Error> {
+        if !self.royal {
+            return Ok(None);
+        }
+
         board
             .is_being_attacked(position, self.player())
             .map(Some)
@@ -83,11 +176,19 @@ impl StandardCompatiblePiece for King {
                 }
 
                 let Some(coordinate) = from + (&CoordinateDelta(x, y), board) else { continue };
+                let spot = board
+                    .get(&coordinate)
+                    .map_err(|err| Error::BoardError(Box::new(err)))?;
+
+                // Stop taking own or non-capturable pieces
+                if let Some(piece) = spot.get() {
+                    if piece.player() == self.player || piece.capture_points().is_none() {
+                        continue;
+                    }
+                }
 
-                if board
-                    .is_being_attacked(&coordinate, self.0)
-                    .map_err(|err| Error::BoardError(Box::new(err)))?
-                {
+                // A royal king cannot move into check
+                if self.royal && spot.is_being_attacked(self.player) {
                     continue;
                 }
 
@@ -96,29 +197,27 @@ impl StandardCompatiblePiece for King {
         }
 
         // Castling
-        for dir in [-1, 1] {
-            let mut castle_distance = None;
-
-            for d in 1..isize::try_from(board.height()).expect("board height exceeded isize") {
-                let Some(position) = from + (&CoordinateDelta(dir * d, 0), board) else { break };
-                let Ok(spot) = board.get(&position) else { break };
-
-                let Some(piece) = spot.get() else { continue };
+        if self.has_moved {
+            return Ok(moves);
+        }
+        if self.royal && board.is_being_attacked(from, self.player).map_err(|err| Error::BoardError(Box::new(err)))? {
+            return Ok(moves);
+        }
 
-                if piece.player() != self.0 {
-                    break;
-                }
-                if !piece.can_castle() {
-                    break;
-                }
+        for data in [CASTLE_LEFT, CASTLE_RIGHT] {
+            let Some((_, king_to, _)) = self.castle_positions(board, from, data)? else { continue };
 
-                castle_distance = Some(d);
+            // A royal king cannot pass through or land on an attacked spot
+            let mut attacked = false;
+            for x in from.0.min(king_to.0)..=from.0.max(king_to.0) {
+                attacked |= self.royal
+                    && board
+                        .is_being_attacked(&Coordinate(x, from.1), self.player)
+                        .map_err(|err| Error::BoardError(Box::new(err)))?;
             }
 
-            let Some(castle_distance) = castle_distance else { continue };
-            if castle_distance > 2 {
-                let Some(position) = from + (&CoordinateDelta(dir * 2, 0), board) else { continue };
-                moves.push((position, 0));
+            if !attacked {
+                moves.push((*from, data));
             }
         }
 
@@ -132,61 +231,42 @@ impl StandardCompatiblePiece for King {
         _turn: u16,
         _n_players: u8,
     ) -> Result<(Vec<Delta<StandardCompatiblePieceSet>>, u16), Error> {
-        let delta = CoordinateDelta(
-            isize::try_from(r#move.to.0).expect("move too large")
-                - isize::try_from(r#move.from.0).expect("move too large"),
-            {
-                let mut dy = isize::try_from(r#move.to.1)
-                    .map_err(|err| Error::PositionOrDeltaTooLarge(r#move.to.1, err))?
-                    - isize::try_from(r#move.from.1)
-                        .map_err(|err| Error::PositionOrDeltaTooLarge(r#move.from.1, err))?;
-                if dy.abs() > 1 {
-                    dy += -dy.signum()
-                        * isize::try_from(board.height())
-                            .map_err(|err| Error::PositionOrDeltaTooLarge(r#move.from.1, err))?;
-                }
-                dy
-            },
-        );
-
         let mut deltas = Vec::new();
 
         // Castling
-        if !self.1 && delta.1 == 0 && delta.0.abs() == 2 {
-            // Get the first piece in that direction
-            // Lookahead dx
-            for la_dx in 1..isize::try_from(board.height()).expect("board height exceeded isize") {
-                let Some(position) =
-                    &r#move.from + (&CoordinateDelta(la_dx * delta.0.signum(), 0), board) else { break };
-                let Ok(c_spot) = board.get(&position) else { break };
-
-                let Some(piece) = c_spot.get() else { continue };
-
-                if piece.player() != self.0 {
-                    break;
-                }
-                if !piece.can_castle() {
-                    break;
+        if !self.has_moved && r#move.from == r#move.to {
+            if let Some((rook, king_to, rook_to)) =
+                self.castle_positions(board, &r#move.from, r#move.data)?
+            {
+                let mut castle_piece = board
+                    .get(&rook)
+                    .map_err(|err| Error::BoardError(Box::new(err)))?
+                    .get()
+                    .clone()
+                    .ok_or_else(|| Error::BoardError(Box::new(crate::error::Error::NoPieceAtSpot(rook))))?;
+                castle_piece.mid_castle();
+
+                // Lift the castling piece, as the king may move into its spot
+                deltas.push(Delta::Delete(rook));
+                if king_to != r#move.from {
+                    deltas.push(Delta::Move(r#move.from, king_to));
                 }
-
-                deltas.push(Delta::Move(
-                    position,
-                    (&r#move.to + (&CoordinateDelta(-delta.0.signum(), 0), board)).ok_or(
-                        Error::IntermediatePositionNotOnBoard(
-                            position,
-                            CoordinateDelta(-delta.0.signum(), 0),
-                        ),
-                    )?,
-                ));
+                deltas.push(Delta::Replace(rook_to, castle_piece));
             }
         }
 
+        self.has_moved = true;
+
         Ok((deltas, 0))
     }
 
     fn clone(&self) -> StandardCompatiblePieceSet {
         Box::new(Clone::clone(self))
     }
+
+    fn is_initial(&self) -> bool {
+        !self.has_moved && self.royal
+    }
 }
 
 #[cfg(test)]
@@ -199,6 +279,8 @@ mod test {
         standard_pieces::{king::King, Pawn, Rook},
     };
 
+    use super::{CASTLE_LEFT, CASTLE_RIGHT};
+
     #[test]
     fn attacking() {
         const KING_POSITION: Coordinate = Coordinate(2, 2);
@@ -276,7 +358,7 @@ mod test {
         const ROOK_POSITION: Coordinate = Coordinate(5, 2);
 
         let is_castle_move = |(from, to, data): &(Coordinate, Coordinate, u8)| {
-            from == &KING_POSITION && to == &PAWN_POSITION && *data == 0
+            from == &KING_POSITION && to == &KING_POSITION && *data == CASTLE_RIGHT
         };
 
         let mut game = Game::new(1, 6, 5);
@@ -312,8 +394,8 @@ mod test {
 
         game.make_move(&Move {
             from: KING_POSITION,
-            to: PAWN_POSITION,
-            data: 0,
+            to: KING_POSITION,
+            data: CASTLE_RIGHT,
             player: 0,
         })
         .expect("failed to make castle move");
@@ -343,4 +425,46 @@ mod test {
             }
         }
     }
+
+    #[test]
+    fn castle_swap() {
+        const KING_POSITION: Coordinate = Coordinate(3, 2);
+        const ROOK_POSITION: Coordinate = Coordinate(2, 2);
+
+        let mut game = Game::new(1, 8, 5);
+        game.add_piece(King::new(0), &KING_POSITION)
+            .expect("failed to place king on board");
+        game.add_piece(Rook::new(0), &ROOK_POSITION)
+            .expect("failed to place rook on board");
+
+        assert!(
+            game.valid_moves()
+                .iter()
+                .any(|&(from, to, data)| from == KING_POSITION && to == KING_POSITION && data == CASTLE_LEFT),
+            "test failed: {KING_POSITION} cannot castle with {ROOK_POSITION}"
+        );
+
+        game.make_move(&Move {
+            from: KING_POSITION,
+            to: KING_POSITION,
+            data: CASTLE_LEFT,
+            player: 0,
+        })
+        .expect("failed to make castle move");
+
+        for (position, expected) in [(ROOK_POSITION, 6), (KING_POSITION, 4)] {
+            let result = game
+                .board()
+                .get(&position)
+                .unwrap()
+                .get()
+                .as_ref()
+                .map(PieceSet::type_id);
+
+            assert!(
+                result == Some(expected),
+                "test failed: {position}: {result:?} ({expected})"
+            );
+        }
+    }
 }
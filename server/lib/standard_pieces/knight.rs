@@ -5,7 +5,7 @@ use crate::{
     r#move::Move,
 };
 
-use super::{Error, StandardCompatiblePiece, StandardCompatiblePieceSet};
+use super::{Error, KNIGHT_ID, StandardCompatiblePiece, StandardCompatiblePieceSet};
 
 #[derive(Clone, Debug)]
 pub struct Knight(u8);
@@ -19,7 +19,7 @@ impl Knight {
 
 impl StandardCompatiblePiece for Knight {
     fn type_id(&self) -> u8 {
-        3
+        KNIGHT_ID
     }
 
     fn capture_points(&self) -> Option<u16> {
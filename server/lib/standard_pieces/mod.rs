@@ -20,6 +20,16 @@ pub type StandardCompatiblePieceSet = Box<dyn StandardCompatiblePiece>;
 pub type StandardCompatiblePieceSet = Box<dyn StandardCompatiblePiece + Send>;
 pub type Delta = delta::Delta<StandardCompatiblePieceSet>;
 
+// The type id of each standard piece
+pub const PAWN_ID: u8 = 1;
+pub const BISHOP_ID: u8 = 2;
+pub const KNIGHT_ID: u8 = 3;
+pub const ROOK_ID: u8 = 4;
+pub const QUEEN_ID: u8 = 5;
+pub const KING_ID: u8 = 6;
+pub const WALL_ID: u8 = 7;
+pub const DUCK_ID: u8 = 8;
+
 pub trait StandardCompatiblePiece
 where
     Self: Debug,
@@ -96,6 +106,16 @@ where
     }
 
     fn mid_castle(&mut self) {}
+
+    /// If the piece upgrades, return the direction it moves in and the rank it upgrades on
+    fn upgrade(&self) -> Option<(i8, usize)> {
+        None
+    }
+
+    /// If the piece is the same as a new piece of its type, so it can be recreated from its type id
+    fn is_initial(&self) -> bool {
+        true
+    }
 }
 
 impl Clone for StandardCompatiblePieceSet {
@@ -199,15 +219,25 @@ impl error::Error for Error {
 }
 
 // Re-export the pieces
+mod atomic;
 mod bishop;
+mod duck;
+mod evaluation;
 mod king;
 mod knight;
 mod pawn;
 mod queen;
 mod rook;
+mod setup;
+mod wall;
+pub use atomic::*;
 pub use bishop::*;
+pub use duck::*;
+pub use evaluation::*;
 pub use king::*;
 pub use knight::*;
 pub use pawn::*;
 pub use queen::*;
 pub use rook::*;
+pub use setup::*;
+pub use wall::*;
@@ -8,6 +8,7 @@ use crate::{
 
 use super::{
     Bishop, Error, Knight, Queen, Rook, StandardCompatiblePiece, StandardCompatiblePieceSet,
+    PAWN_ID,
 };
 
 #[derive(Clone, Debug)]
@@ -37,7 +38,7 @@ const UPGRADE_NUMBERS: [u8; 4] = [2, 3, 4, 5];
 
 impl StandardCompatiblePiece for Pawn {
     fn type_id(&self) -> u8 {
-        1
+        PAWN_ID
     }
 
     fn capture_points(&self) -> Option<u16> {
@@ -151,8 +152,10 @@ impl StandardCompatiblePiece for Pawn {
     ) -> Result<(Vec<Delta<StandardCompatiblePieceSet>>, u16), Error> {
         // Determine how the pawn was moved
         let delta = CoordinateDelta(
-            isize::try_from(r#move.to.0).expect("move too large")
-                - isize::try_from(r#move.from.0).expect("move too large"),
+            isize::try_from(r#move.to.0)
+                .map_err(|err| Error::PositionOrDeltaTooLarge(r#move.to.0, err))?
+                - isize::try_from(r#move.from.0)
+                    .map_err(|err| Error::PositionOrDeltaTooLarge(r#move.from.0, err))?,
             {
                 let mut dy = isize::try_from(r#move.to.1)
                     .map_err(|err| Error::PositionOrDeltaTooLarge(r#move.to.1, err))?
@@ -170,11 +173,11 @@ impl StandardCompatiblePiece for Pawn {
         // First, double move
         if delta.1 == 2 && !self.has_moved {
             // Get the intermediate step and record it
-            self.first_double_move = Some((
-                (&r#move.from + (&CoordinateDelta(0, self.direction.into()), board))
-                    .expect("failed to add 1 to y in coordinate"),
-                turn,
-            ));
+            let step = CoordinateDelta(0, self.direction.into());
+            let Some(intermediate) = &r#move.from + (&step, board) else {
+                return Err(Error::IntermediatePositionNotOnBoard(r#move.from, step));
+            };
+            self.first_double_move = Some((intermediate, turn));
         }
 
         let mut deltas = Vec::new();
@@ -225,6 +228,14 @@ impl StandardCompatiblePiece for Pawn {
             false
         }
     }
+
+    fn upgrade(&self) -> Option<(i8, usize)> {
+        Some((self.direction, self.upgrade_rank))
+    }
+
+    fn is_initial(&self) -> bool {
+        !self.has_moved
+    }
 }
 
 #[cfg(test)]
@@ -5,7 +5,7 @@ use crate::{
     r#move::Move,
 };
 
-use super::{StandardCompatiblePiece, StandardCompatiblePieceSet};
+use super::{QUEEN_ID, StandardCompatiblePiece, StandardCompatiblePieceSet};
 
 #[derive(Clone, Debug)]
 pub struct Queen(u8, bool);
@@ -19,7 +19,7 @@ impl Queen {
 
 impl StandardCompatiblePiece for Queen {
     fn type_id(&self) -> u8 {
-        5
+        QUEEN_ID
     }
 
     fn capture_points(&self) -> Option<u16> {
@@ -162,7 +162,7 @@ mod test {
             [false, true, true, true, false],
             [true, true, false, true, true],
             [false, true, true, true, false],
-            [true, false, false, false, true],
+            [true, false, true, false, true],
         ];
 
         for (y, rank) in tests.iter().enumerate() {
@@ -207,7 +207,7 @@ mod test {
             [false, true, true, true, false],
             [true, true, false, true, true],
             [false, true, true, true, false],
-            [true, false, false, false, true],
+            [true, false, true, false, true],
         ];
 
         game.generate_valid_moves()
@@ -5,7 +5,7 @@ use crate::{
     r#move::Move,
 };
 
-use super::{StandardCompatiblePiece, StandardCompatiblePieceSet};
+use super::{ROOK_ID, StandardCompatiblePiece, StandardCompatiblePieceSet};
 
 #[derive(Clone, Debug)]
 pub struct Rook(u8, bool);
@@ -19,7 +19,7 @@ impl Rook {
 
 impl StandardCompatiblePiece for Rook {
     fn type_id(&self) -> u8 {
-        4
+        ROOK_ID
     }
 
     fn capture_points(&self) -> Option<u16> {
@@ -109,6 +109,10 @@ impl StandardCompatiblePiece for Rook {
     fn mid_castle(&mut self) {
         self.1 = true;
     }
+
+    fn is_initial(&self) -> bool {
+        !self.1
+    }
 }
 
 #[cfg(test)]
@@ -138,7 +142,7 @@ mod test {
             [false, false, true, false, false],
             [true, true, false, true, true],
             [false, false, true, false, false],
-            [false; 5],
+            [false, false, true, false, false],
         ];
 
         for (y, rank) in tests.iter().enumerate() {
@@ -177,7 +181,7 @@ mod test {
             [false, false, true, false, false],
             [true, true, false, true, true],
             [false, false, true, false, false],
-            [false; 5],
+            [false, false, true, false, false],
         ];
 
         game.generate_valid_moves()
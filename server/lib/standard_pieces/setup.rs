@@ -0,0 +1,295 @@
+use crate::{coordinate::Coordinate, error::Error, game::Game, rng::Rng};
+
+use super::{
+    Bishop, Duck, King, Knight, Pawn, Queen, Rook, StandardCompatiblePieceSet, Wall,
+};
+
+/// How the players' back ranks are arranged
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BackRank {
+    /// Rook, knight, bishop, queen, king, bishop, knight, rook
+    Standard,
+    /// One shuffled back rank, shared by every player
+    Shared,
+    /// A differently shuffled back rank for each player
+    Individual,
+}
+
+/// Create a piece for a player
+type NewPiece = fn(u8) -> StandardCompatiblePieceSet;
+
+const STANDARD_BACK_RANK: [NewPiece; 8] = [
+    Rook::new,
+    Knight::new,
+    Bishop::new,
+    Queen::new,
+    King::new,
+    Bishop::new,
+    Knight::new,
+    Rook::new,
+];
+
+/// Shuffle a back rank, keeping the bishops on opposite colours and the king between the rooks
+fn shuffle_back_rank(rng: &mut Rng) -> [NewPiece; 8] {
+    let mut rank: [Option<NewPiece>; 8] = [None; 8];
+
+    // Place a bishop on each colour
+    rank[rng.below(4) * 2] = Some(Bishop::new);
+    rank[rng.below(4) * 2 + 1] = Some(Bishop::new);
+
+    // Place the queen and knights in random empty spots
+    for piece in [Queen::new as NewPiece, Knight::new, Knight::new] {
+        let empty: Vec<usize> = (0..rank.len()).filter(|&x| rank[x].is_none()).collect();
+        rank[empty[rng.below(empty.len())]] = Some(piece);
+    }
+
+    // Fill the remaining spots in order, so the king is between the rooks
+    for piece in [Rook::new as NewPiece, King::new, Rook::new] {
+        let x = rank
+            .iter()
+            .position(Option::is_none)
+            .expect("back rank filled too early");
+        rank[x] = Some(piece);
+    }
+
+    rank.map(|piece| piece.expect("back rank not filled"))
+}
+
+/// Place every player's pieces on the board, shuffling the back ranks using the game's seed
+///
+/// Each player gets an equal section of the board, starting with their back rank,
+/// then their pawns, which move towards the next player's back rank.
+/// On boards wider than a back rank, the pieces are centred, to the left of the centre
+/// when they cannot be exactly.
+///
+/// # Errors
+/// - [`Error::CoordinateNotOnBoard`] - The board is too small for the pieces
+/// - [`Error::SpotOccupied`] - A piece is already on the board where one would be placed
+/// - [`Error::PieceError`] - Error from a piece
+pub fn setup(
+    game: &mut Game<StandardCompatiblePieceSet>,
+    back_rank: BackRank,
+) -> Result<(), Error<StandardCompatiblePieceSet>> {
+    let n_players = game.n_players();
+    let height = game.board().height();
+    let rows = height / usize::from(n_players.max(1));
+    let offset = game.board().width().saturating_sub(STANDARD_BACK_RANK.len()) / 2;
+
+    let mut rng = Rng::new(game.seed());
+    let shared = shuffle_back_rank(&mut rng);
+
+    let mut pieces = Vec::new();
+    for player in 0..n_players {
+        let back_rank_y = usize::from(player) * rows;
+        let upgrade_rank = (back_rank_y + rows) % height;
+
+        let rank = match back_rank {
+            BackRank::Standard => STANDARD_BACK_RANK,
+            BackRank::Shared => shared,
+            BackRank::Individual => shuffle_back_rank(&mut rng),
+        };
+
+        for (x, new_piece) in rank.into_iter().enumerate() {
+            pieces.push((new_piece(player), Coordinate(offset + x, back_rank_y)));
+            pieces.push((
+                Pawn::new(player, 1, upgrade_rank),
+                Coordinate(offset + x, back_rank_y + 1),
+            ));
+        }
+    }
+
+    game.add_pieces(pieces)?;
+    Ok(())
+}
+
+/// Place neutral pieces in the empty rows of the players' sections, using the game's seed
+///
+/// Each player's section gets `walls` walls, and the first player's section gets the duck
+///
+/// # Errors
+/// - [`Error::SpotOccupied`] - A piece is already on the board where one would be placed
+/// - [`Error::PieceError`] - Error from a piece
+pub fn place_neutral(
+    game: &mut Game<StandardCompatiblePieceSet>,
+    walls: u8,
+    duck: bool,
+) -> Result<(), Error<StandardCompatiblePieceSet>> {
+    let n_players = game.n_players();
+    let width = game.board().width();
+    let rows = game.board().height() / usize::from(n_players.max(1));
+
+    // Use a different stream to the back ranks
+    let mut rng = Rng::new(!game.seed());
+
+    let mut pieces = Vec::new();
+    for player in 0..n_players {
+        // The rows between the player's pawns and the next player's back rank
+        let first_y = usize::from(player) * rows + 2;
+        let mut empty: Vec<Coordinate> = (first_y..usize::from(player) * rows + rows)
+            .flat_map(|y| (0..width).map(move |x| Coordinate(x, y)))
+            .filter(|position| {
+                game.board()
+                    .get(position)
+                    .is_ok_and(|spot| !spot.is_occupied())
+            })
+            .collect();
+
+        let n_pieces = usize::from(walls) + usize::from(duck && player == 0);
+        for i in 0..n_pieces.min(empty.len()) {
+            let position = empty.swap_remove(rng.below(empty.len()));
+            pieces.push((
+                if i < usize::from(walls) {
+                    Wall::new()
+                } else {
+                    Duck::new()
+                },
+                position,
+            ));
+        }
+    }
+
+    game.add_pieces(pieces)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{coordinate::Coordinate, game::Game, piece_set::PieceSet, r#move::Move};
+
+    use super::{place_neutral, setup, BackRank};
+
+    fn back_rank(game: &Game<crate::standard_pieces::StandardCompatiblePieceSet>, y: usize) -> Vec<u8> {
+        (0..8)
+            .map(|x| {
+                game.board()
+                    .get(&Coordinate(x, y))
+                    .unwrap()
+                    .get()
+                    .as_ref()
+                    .map_or(0, PieceSet::type_id)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn fischer_random() {
+        for seed in 0..32 {
+            let mut game = Game::new(3, 8, 21);
+            game.set_seed(seed);
+            setup(&mut game, BackRank::Individual).expect("failed to set up game");
+
+            for player in 0..3 {
+                let rank = back_rank(&game, player * 7);
+
+                let bishops: Vec<usize> = (0..8).filter(|&x| rank[x] == 2).collect();
+                assert!(
+                    bishops.len() == 2 && bishops[0] % 2 != bishops[1] % 2,
+                    "test failed: seed {seed}, bishops on the same colour: {rank:?}"
+                );
+
+                let rooks: Vec<usize> = (0..8).filter(|&x| rank[x] == 4).collect();
+                let king = rank.iter().position(|&id| id == 6).expect("no king");
+                assert!(
+                    rooks.len() == 2 && rooks[0] < king && king < rooks[1],
+                    "test failed: seed {seed}, king not between rooks: {rank:?}"
+                );
+            }
+
+            assert!(
+                !game.valid_moves().is_empty(),
+                "test failed: seed {seed}, no valid moves"
+            );
+        }
+    }
+
+    #[test]
+    fn reproducible() {
+        let set_up = |back_rank_mode| {
+            let mut game = Game::new(2, 8, 14);
+            game.set_seed(7);
+            setup(&mut game, back_rank_mode).expect("failed to set up game");
+            (back_rank(&game, 0), back_rank(&game, 7))
+        };
+
+        assert!(
+            set_up(BackRank::Individual) == set_up(BackRank::Individual),
+            "test failed: same seed gave different back ranks"
+        );
+
+        let (first, second) = set_up(BackRank::Shared);
+        assert!(
+            first == second,
+            "test failed: shared back ranks differ: {first:?} != {second:?}"
+        );
+    }
+
+    #[test]
+    fn centred() {
+        let mut game = Game::new(2, 11, 16);
+        setup(&mut game, BackRank::Standard).expect("failed to set up game");
+
+        let (_, _, spots) = game.board().export();
+        let rank: Vec<u8> = spots[..11].iter().map(|&(_, type_id)| type_id).collect();
+        assert!(
+            rank == [0, 4, 3, 2, 5, 6, 2, 3, 4, 0, 0],
+            "test failed: back rank not centred: {rank:?}"
+        );
+    }
+
+    #[test]
+    fn duck_phase() {
+        let mut game = Game::new(2, 8, 14);
+        setup(&mut game, BackRank::Standard).expect("failed to set up game");
+        place_neutral(&mut game, 2, true).expect("failed to place neutral pieces");
+
+        let walls = (0..14)
+            .flat_map(|y| (0..8).map(move |x| Coordinate(x, y)))
+            .filter(|position| {
+                game.board().get(position).unwrap().get().as_ref().map(PieceSet::type_id) == Some(7)
+            })
+            .count();
+        assert!(walls == 4, "test failed: {walls} walls (4)");
+        assert!(game.board().has_neutral_pieces(), "test failed: no neutral pieces found");
+
+        let (from, to, data) = game.valid_moves()[0];
+        game.make_move(&Move { from, to, data, player: 0 })
+            .expect("failed to make move");
+        game.start_turn().expect("failed to start duck phase");
+
+        assert!(
+            game.is_neutral_phase() && game.current_player() == 0,
+            "test failed: player 0 does not move the duck"
+        );
+        let (from, to, data) = game.valid_moves()[0];
+        assert!(
+            game.board().get(&from).unwrap().get().as_ref().map(PieceSet::type_id) == Some(8),
+            "test failed: {from} is not the duck"
+        );
+
+        game.make_move(&Move { from, to, data, player: 0 })
+            .expect("failed to move duck");
+        game.start_turn().expect("failed to start next turn");
+
+        assert!(
+            !game.is_neutral_phase() && game.current_player() == 1,
+            "test failed: turn did not pass to player 1"
+        );
+    }
+
+    #[test]
+    fn no_neutral_phase() {
+        let mut game = Game::new(2, 8, 14);
+        setup(&mut game, BackRank::Standard).expect("failed to set up game");
+        assert!(!game.board().has_neutral_pieces(), "test failed: neutral pieces found");
+
+        let (from, to, data) = game.valid_moves()[0];
+        game.make_move(&Move { from, to, data, player: 0 })
+            .expect("failed to make move");
+        game.start_turn().expect("failed to start next turn");
+
+        assert!(
+            !game.is_neutral_phase() && game.current_player() == 1,
+            "test failed: turn did not pass to player 1"
+        );
+    }
+}
@@ -0,0 +1,103 @@
+use crate::{
+    board::Board, coordinate::Coordinate, delta::Delta, piece_set::NEUTRAL, r#move::Move,
+};
+
+use super::{Error, StandardCompatiblePiece, StandardCompatiblePieceSet, WALL_ID};
+
+/// A neutral piece which never moves, blocking the spot it is placed on
+#[derive(Clone, Debug)]
+pub struct Wall;
+
+impl Wall {
+    #[must_use]
+    pub fn new() -> StandardCompatiblePieceSet {
+        Box::new(Self)
+    }
+}
+
+impl StandardCompatiblePiece for Wall {
+    fn type_id(&self) -> u8 {
+        WALL_ID
+    }
+
+    fn capture_points(&self) -> Option<u16> {
+        None
+    }
+
+    fn blockable(&self) -> bool {
+        false
+    }
+
+    fn player(&self) -> u8 {
+        NEUTRAL
+    }
+
+    fn attacking(
+        &self,
+        _board: &Board<StandardCompatiblePieceSet>,
+        _from: &Coordinate,
+    ) -> Result<Vec<Coordinate>, Error> {
+        Ok(Vec::with_capacity(0))
+    }
+
+    fn valid_moves(
+        &self,
+        _board: &Board<StandardCompatiblePieceSet>,
+        _from: &Coordinate,
+        _turn: u16,
+        _n_players: u8,
+    ) -> Result<Vec<(Coordinate, u8)>, Error> {
+        Ok(Vec::with_capacity(0))
+    }
+
+    fn mid_move(
+        &mut self,
+        _board: &Board<StandardCompatiblePieceSet>,
+        _move: &Move,
+        _turn: u16,
+        _n_players: u8,
+    ) -> Result<(Vec<Delta<StandardCompatiblePieceSet>>, u16), Error> {
+        Ok((Vec::with_capacity(0), 0))
+    }
+
+    fn clone(&self) -> StandardCompatiblePieceSet {
+        Box::new(Clone::clone(self))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        coordinate::Coordinate,
+        game::Game,
+        standard_pieces::{Rook, Wall},
+    };
+
+    #[test]
+    fn blocks() {
+        const ROOK_POSITION: Coordinate = Coordinate(0, 2);
+        const WALL_POSITION: Coordinate = Coordinate(2, 2);
+
+        let mut game = Game::new(1, 5, 5);
+        game.add_piece(Rook::new(0), &ROOK_POSITION)
+            .expect("failed to add rook to board");
+        game.add_piece(Wall::new(), &WALL_POSITION)
+            .expect("failed to add wall to board");
+
+        for x in WALL_POSITION.0..5 {
+            let position = Coordinate(x, WALL_POSITION.1);
+            assert!(
+                !game.valid_moves().iter().any(|(_, to, _)| to == &position),
+                "test failed: {ROOK_POSITION} -> {position}, true (false)"
+            );
+        }
+
+        for x in WALL_POSITION.0 + 1..5 {
+            let position = Coordinate(x, WALL_POSITION.1);
+            assert!(
+                !game.board().is_being_attacked(&position, 1).unwrap(),
+                "test failed: {ROOK_POSITION} -x {position} through the wall"
+            );
+        }
+    }
+}
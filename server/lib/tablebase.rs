@@ -0,0 +1,1032 @@
+//! Endgame tablebases for two players, built by retrograde analysis
+//!
+//! A player with no valid moves is out of play, whether in check or not,
+//! so every ending is won by the player who leaves the other without moves.
+//! Endings which neither player can force are draws.
+//!
+//! Tablebases hold kings, queens, rooks, bishops and knights, which can move back the way they came.
+//! Castling is not considered.
+
+use std::{
+    collections::HashMap,
+    error, fmt, fs,
+    io::{self, Read, Write},
+    path::Path,
+    str::FromStr,
+};
+
+use crate::{
+    coordinate::Coordinate,
+    game::Game,
+    r#move::{Move, PartialMove},
+    standard_pieces::StandardCompatiblePieceSet,
+};
+
+/// The first bytes of a tablebase file
+const MAGIC: &[u8; 4] = b"CHTB";
+/// The version of the tablebase file format
+const VERSION: u8 = 1;
+
+/// The stored result of a draw
+const DRAW: u8 = 0;
+/// The stored result of a position which cannot be reached
+const ILLEGAL: u8 = u8::MAX;
+/// The most plies a stored result can have, leaving the largest result for illegal positions
+const MAX_PLIES: u16 = 253;
+
+/// The type ids of the pieces tablebases can hold, in the order they are written
+const PIECES: [(u8, char); 5] = [(6, 'K'), (5, 'Q'), (4, 'R'), (2, 'B'), (3, 'N')];
+const KING: u8 = 6;
+const QUEEN: u8 = 5;
+const ROOK: u8 = 4;
+const BISHOP: u8 = 2;
+const KNIGHT: u8 = 3;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The material could not be parsed, or has pieces tablebases cannot hold
+    InvalidMaterial(String),
+    /// The tablebase has too many positions to build
+    TooLarge,
+    /// A result has more plies than can be stored
+    TooDeep,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidMaterial(material) => write!(f, "{material}: Invalid tablebase material!"),
+            Self::TooLarge => write!(f, "Tablebase too large!"),
+            Self::TooDeep => write!(f, "Tablebase result longer than {MAX_PLIES} plies!"),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+/// The result of a position for the player to move, with the plies until the losing player has no moves
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Probe {
+    Win(u16),
+    Draw,
+    Loss(u16),
+}
+
+impl Probe {
+    /// Get the probe from a stored result
+    fn from_result(result: u8) -> Option<Self> {
+        match result {
+            DRAW => Some(Self::Draw),
+            ILLEGAL => None,
+            // Wins take an odd number of plies and losses an even number
+            _ if result.is_multiple_of(2) => Some(Self::Win(u16::from(result) - 1)),
+            _ => Some(Self::Loss(u16::from(result) - 1)),
+        }
+    }
+}
+
+/// The pieces of each player, written with a king first for each, such as `KQK` or `KBNK`
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Material([Vec<u8>; 2]);
+
+impl Material {
+    /// Create material from the type ids of each player's pieces
+    ///
+    /// # Errors
+    /// - [`Error::InvalidMaterial`] - A player does not have exactly one king,
+    ///   or has a piece tablebases cannot hold
+    pub fn new(mut pieces: [Vec<u8>; 2]) -> Result<Self, Error> {
+        for side in &mut pieces {
+            side.sort_by_key(|&type_id| order(type_id));
+        }
+        let material = Self(pieces);
+
+        let is_valid = material.0.iter().all(|side| {
+            side.first() == Some(&KING)
+                && side[1..]
+                    .iter()
+                    .all(|&type_id| type_id != KING && order(type_id) < PIECES.len())
+        });
+        if !is_valid {
+            return Err(Error::InvalidMaterial(material.to_string()));
+        }
+
+        Ok(material)
+    }
+
+    #[must_use]
+    /// Get the type ids of a player's pieces
+    pub fn side(&self, side: usize) -> &[u8] {
+        &self.0[side]
+    }
+
+    /// Get the type ids of every piece, with the first player's first
+    fn pieces(&self) -> impl Iterator<Item = (usize, u8)> + '_ {
+        self.0
+            .iter()
+            .enumerate()
+            .flat_map(|(side, pieces)| pieces.iter().map(move |&type_id| (side, type_id)))
+    }
+
+    /// Get the material with each player's pieces swapped
+    fn swapped(&self) -> Self {
+        Self([self.0[1].clone(), self.0[0].clone()])
+    }
+
+    /// Get the material after a piece is captured, by its index in [`Self::pieces`]
+    fn without(&self, piece: usize) -> Self {
+        let mut material = self.clone();
+        let first = material.0[0].len();
+        if piece < first {
+            material.0[0].remove(piece);
+        } else {
+            material.0[1].remove(piece - first);
+        }
+        material
+    }
+}
+
+/// Get the position of a piece in the order material is written
+fn order(type_id: u8) -> usize {
+    PIECES
+        .iter()
+        .position(|&(id, _)| id == type_id)
+        .unwrap_or(PIECES.len())
+}
+
+impl fmt::Display for Material {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for &type_id in self.0.iter().flatten() {
+            let letter = PIECES
+                .iter()
+                .find(|&&(id, _)| id == type_id)
+                .map_or('?', |&(_, letter)| letter);
+            write!(f, "{letter}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Material {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut pieces = [Vec::new(), Vec::new()];
+        let mut side = None;
+
+        for letter in s.chars() {
+            let Some(&(type_id, _)) = PIECES.iter().find(|&&(_, piece)| piece == letter) else {
+                return Err(Error::InvalidMaterial(s.to_owned()));
+            };
+
+            // Each king starts the next player's pieces
+            if type_id == KING {
+                side = Some(side.map_or(0, |side| side + 1));
+            }
+            match side {
+                Some(side @ 0..=1) => pieces[side].push(type_id),
+                _ => return Err(Error::InvalidMaterial(s.to_owned())),
+            }
+        }
+
+        Self::new(pieces)
+    }
+}
+
+/// The spots each piece can reach from each spot of a board, on an empty board
+///
+/// The board is a tube, where `x` is finite and `y` wraps
+struct Geometry {
+    n_spots: usize,
+    king: Vec<Vec<usize>>,
+    knight: Vec<Vec<usize>>,
+    /// The spots in each direction, with the orthogonal directions first
+    rays: Vec<[Vec<usize>; 8]>,
+}
+
+impl Geometry {
+    fn new(width: usize, height: usize) -> Self {
+        let offset = |spot: usize, dx: isize, dy: isize| {
+            let x = (spot % width).checked_add_signed(dx).filter(|&x| x < width)?;
+            let y = usize::try_from(
+                isize::try_from(spot / width).ok()?.checked_add(dy)?.rem_euclid(isize::try_from(height).ok()?),
+            )
+            .ok()?;
+            Some(y * width + x)
+        };
+        // Get the distinct spots reached by some offsets
+        let jumps = |spot: usize, offsets: &[(isize, isize)]| {
+            let mut spots: Vec<usize> = offsets
+                .iter()
+                .filter_map(|&(dx, dy)| offset(spot, dx, dy))
+                .filter(|&to| to != spot)
+                .collect();
+            spots.sort_unstable();
+            spots.dedup();
+            spots
+        };
+
+        let n_spots = width * height;
+        let king_offsets: Vec<(isize, isize)> = (-1..=1)
+            .flat_map(|dx| (-1..=1).map(move |dy| (dx, dy)))
+            .filter(|&offset| offset != (0, 0))
+            .collect();
+        let knight_offsets = [(-2, -1), (-2, 1), (2, -1), (2, 1), (-1, -2), (-1, 2), (1, -2), (1, 2)];
+        let directions = [(0, 1), (0, -1), (1, 0), (-1, 0), (1, 1), (1, -1), (-1, 1), (-1, -1)];
+        let max_distance = isize::try_from(height).unwrap_or(isize::MAX);
+
+        Self {
+            n_spots,
+            king: (0..n_spots).map(|spot| jumps(spot, &king_offsets)).collect(),
+            knight: (0..n_spots).map(|spot| jumps(spot, &knight_offsets)).collect(),
+            rays: (0..n_spots)
+                .map(|spot| {
+                    directions.map(|(dx, dy)| {
+                        // Like the sliding pieces, go at most the height of the board
+                        (1..max_distance)
+                            .map_while(|d| offset(spot, dx * d, dy * d))
+                            .collect()
+                    })
+                })
+                .collect(),
+        }
+    }
+
+    /// Get the directions a piece slides in
+    const fn directions(type_id: u8) -> std::ops::Range<usize> {
+        match type_id {
+            QUEEN => 0..8,
+            ROOK => 0..4,
+            BISHOP => 4..8,
+            _ => 0..0,
+        }
+    }
+
+    /// Call `f` with each spot a piece attacks, until it returns true
+    fn any_attack(
+        &self,
+        type_id: u8,
+        from: usize,
+        occupied: impl Fn(usize) -> bool,
+        mut f: impl FnMut(usize) -> bool,
+    ) -> bool {
+        match type_id {
+            KING => self.king[from].iter().any(|&to| f(to)),
+            KNIGHT => self.knight[from].iter().any(|&to| f(to)),
+            _ => Self::directions(type_id).any(|direction| {
+                for &to in &self.rays[from][direction] {
+                    if f(to) {
+                        return true;
+                    }
+                    if occupied(to) {
+                        break;
+                    }
+                }
+                false
+            }),
+        }
+    }
+}
+
+/// A position being looked at, with the spot of each piece in material order
+struct Placement<'a> {
+    geometry: &'a Geometry,
+    pieces: &'a [(usize, u8)],
+    spots: Vec<usize>,
+    /// A piece which has been captured
+    captured: Option<usize>,
+}
+
+impl Placement<'_> {
+    fn occupant(&self, spot: usize) -> Option<usize> {
+        (0..self.spots.len()).find(|&piece| Some(piece) != self.captured && self.spots[piece] == spot)
+    }
+
+    /// Get whether a side's king is attacked
+    fn is_in_check(&self, side: usize) -> bool {
+        let Some(king) = (0..self.pieces.len()).find(|&piece| self.pieces[piece] == (side, KING)) else {
+            return false;
+        };
+        let target = self.spots[king];
+
+        (0..self.pieces.len())
+            .filter(|&piece| Some(piece) != self.captured && self.pieces[piece].0 != side)
+            .any(|piece| {
+                self.geometry.any_attack(
+                    self.pieces[piece].1,
+                    self.spots[piece],
+                    |spot| self.occupant(spot).is_some(),
+                    |spot| spot == target,
+                )
+            })
+    }
+
+    /// Get the spots a piece can move to, with the piece captured by each move
+    ///
+    /// Only empty spots are returned when `captures` is false
+    fn moves(&self, piece: usize, captures: bool) -> Vec<(usize, Option<usize>)> {
+        let (side, type_id) = self.pieces[piece];
+        let mut moves = Vec::new();
+
+        self.geometry.any_attack(
+            type_id,
+            self.spots[piece],
+            |spot| self.occupant(spot).is_some(),
+            |to| {
+                match self.occupant(to) {
+                    None => moves.push((to, None)),
+                    // Kings cannot be captured
+                    Some(other) if captures && self.pieces[other].0 != side && self.pieces[other].1 != KING => {
+                        moves.push((to, Some(other)));
+                    }
+                    Some(_) => {}
+                }
+                false
+            },
+        );
+
+        // Sliding pieces can reach a spot in more than one direction on small boards
+        moves.sort_unstable_by_key(|&(to, _)| to);
+        moves.dedup_by_key(|&mut (to, _)| to);
+        moves
+    }
+}
+
+/// The results of every position with some material, on a board of some size
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Tablebase {
+    material: Material,
+    width: u16,
+    height: u16,
+    /// The result of each position by its index, see [`Self::index`]
+    results: Vec<u8>,
+}
+
+/// The numbers of results in a tablebase
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Statistics {
+    /// The wins, draws and losses with each player to move
+    pub results: [[u64; 3]; 2],
+    /// The most plies of any win
+    pub longest_win: u16,
+}
+
+impl Tablebase {
+    #[must_use]
+    pub const fn material(&self) -> &Material {
+        &self.material
+    }
+
+    #[must_use]
+    pub const fn width(&self) -> u16 {
+        self.width
+    }
+
+    #[must_use]
+    pub const fn height(&self) -> u16 {
+        self.height
+    }
+
+    /// Get the index of a position from the player to move and the spot of each piece
+    fn index(&self, side_to_move: usize, spots: &[usize]) -> usize {
+        let n_spots = usize::from(self.width) * usize::from(self.height);
+        side_to_move + 2 * spots.iter().rev().fold(0, |index, &spot| index * n_spots + spot)
+    }
+
+    #[must_use]
+    /// Get the result of a position, from the player to move and the spot of each piece in material order
+    ///
+    /// Returns `None` if the position cannot be reached or is not in the tablebase
+    pub fn probe(&self, side_to_move: usize, spots: &[Coordinate]) -> Option<Probe> {
+        if side_to_move > 1
+            || spots.len() != self.material.pieces().count()
+            || spots
+                .iter()
+                .any(|spot| spot.0 >= usize::from(self.width) || spot.1 >= usize::from(self.height))
+        {
+            return None;
+        }
+
+        let spots: Vec<usize> = spots
+            .iter()
+            .map(|spot| spot.1 * usize::from(self.width) + spot.0)
+            .collect();
+        Probe::from_result(self.results[self.index(side_to_move, &spots)])
+    }
+
+    #[must_use]
+    /// Count the results in the tablebase
+    pub fn statistics(&self) -> Statistics {
+        let mut statistics = Statistics::default();
+
+        for (index, &result) in self.results.iter().enumerate() {
+            let (category, plies) = match Probe::from_result(result) {
+                Some(Probe::Win(plies)) => (0, plies),
+                Some(Probe::Draw) => (1, 0),
+                Some(Probe::Loss(_)) => (2, 0),
+                None => continue,
+            };
+            statistics.results[index % 2][category] += 1;
+            statistics.longest_win = statistics.longest_win.max(plies);
+        }
+
+        statistics
+    }
+
+    /// Build a tablebase, using the tablebases for its material after each capture
+    fn generate(material: Material, width: u16, height: u16, tablebases: &Tablebases) -> Result<Self, Error> {
+        let geometry = Geometry::new(usize::from(width), usize::from(height));
+        let pieces: Vec<(usize, u8)> = material.pieces().collect();
+        let n_positions = u32::try_from(pieces.len())
+            .ok()
+            .and_then(|n_pieces| geometry.n_spots.checked_pow(n_pieces))
+            .and_then(|n| n.checked_mul(2))
+            .ok_or(Error::TooLarge)?;
+
+        let mut tablebase = Self {
+            material,
+            width,
+            height,
+            results: vec![ILLEGAL; n_positions],
+        };
+        // The tablebases after capturing each piece
+        let captures: Vec<Option<&Self>> = (0..pieces.len())
+            .map(|piece| {
+                (pieces[piece].1 != KING)
+                    .then(|| tablebases.get(&tablebase.material.without(piece), width, height))
+                    .flatten()
+            })
+            .collect();
+
+        let mut builder = Builder {
+            geometry: &geometry,
+            pieces: &pieces,
+            plies: vec![0; n_positions],
+            is_done: vec![false; n_positions],
+            n_moves: vec![0; n_positions],
+            is_legal: vec![false; n_positions],
+            max_plies: 0,
+        };
+        for index in 0..n_positions {
+            builder.start(index, &captures);
+        }
+
+        // Finalise the results with each number of plies in turn
+        let mut level = 1;
+        while level <= builder.max_plies {
+            for index in 0..n_positions {
+                builder.finalise(&tablebase, index, level);
+            }
+            level += 1;
+        }
+
+        for index in 0..n_positions {
+            if !builder.is_legal[index] {
+                continue;
+            }
+            tablebase.results[index] = if builder.is_done[index] {
+                if builder.plies[index] > MAX_PLIES + 1 {
+                    return Err(Error::TooDeep);
+                }
+                u8::try_from(builder.plies[index]).map_err(|_| Error::TooDeep)?
+            } else {
+                DRAW
+            };
+        }
+
+        Ok(tablebase)
+    }
+
+    /// Write the tablebase in its binary format
+    ///
+    /// All numbers are little endian:
+    /// - `CHTB`, then the version as a byte
+    /// - The width and height as u16s
+    /// - The length of the material as a byte, then the material, such as `KQK`
+    /// - A byte for each position, by its index:
+    ///   `0` for a draw, `255` for a position which cannot be reached,
+    ///   otherwise the plies + 1 until the losing player has no moves,
+    ///   which is odd for a loss and even for a win of the player to move
+    ///
+    /// The index of a position is the player to move, + 2 * the spot of each piece in material order,
+    /// as a number with a digit for each piece, the first piece least significant
+    /// and the base the number of spots on the board.
+    /// The spot of a piece at `(x, y)` is `y * width + x`
+    ///
+    /// # Errors
+    /// - [`io::Error`] - The tablebase could not be written
+    pub fn write(&self, writer: &mut impl Write) -> io::Result<()> {
+        let material = self.material.to_string();
+
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[VERSION])?;
+        writer.write_all(&self.width.to_le_bytes())?;
+        writer.write_all(&self.height.to_le_bytes())?;
+        writer.write_all(&[u8::try_from(material.len()).map_err(|_| invalid_data("material too long"))?])?;
+        writer.write_all(material.as_bytes())?;
+        writer.write_all(&self.results)
+    }
+
+    /// Read a tablebase written by [`Self::write`]
+    ///
+    /// # Errors
+    /// - [`io::Error`] - The tablebase could not be read, or is not a valid tablebase
+    pub fn read(reader: &mut impl Read) -> io::Result<Self> {
+        let mut header = [0; 10];
+        reader.read_exact(&mut header)?;
+        if &header[..4] != MAGIC {
+            return Err(invalid_data("not a tablebase"));
+        }
+        if header[4] != VERSION {
+            return Err(invalid_data("unsupported tablebase version"));
+        }
+        let width = u16::from_le_bytes([header[5], header[6]]);
+        let height = u16::from_le_bytes([header[7], header[8]]);
+
+        let mut material = vec![0; usize::from(header[9])];
+        reader.read_exact(&mut material)?;
+        let material: Material = String::from_utf8(material)
+            .map_err(|_| invalid_data("invalid tablebase material"))?
+            .parse()
+            .map_err(|_| invalid_data("invalid tablebase material"))?;
+
+        let mut results = Vec::new();
+        reader.read_to_end(&mut results)?;
+        let n_spots = usize::from(width) * usize::from(height);
+        if Some(results.len())
+            != u32::try_from(material.pieces().count())
+                .ok()
+                .and_then(|n_pieces| n_spots.checked_pow(n_pieces))
+                .and_then(|n| n.checked_mul(2))
+        {
+            return Err(invalid_data("wrong number of tablebase positions"));
+        }
+
+        Ok(Self {
+            material,
+            width,
+            height,
+            results,
+        })
+    }
+}
+
+/// The state of a tablebase while it is built
+struct Builder<'a> {
+    geometry: &'a Geometry,
+    pieces: &'a [(usize, u8)],
+    /// Each position's plies + 1, or 0 if unknown
+    ///
+    /// With an odd number of plies, it is a possible win with the fewest plies found,
+    /// otherwise a loss if every move is losing, with the most plies found
+    plies: Vec<u16>,
+    is_done: Vec<bool>,
+    /// The moves from each position which are not known to be losing
+    n_moves: Vec<u16>,
+    is_legal: Vec<bool>,
+    max_plies: u16,
+}
+
+impl Builder<'_> {
+    /// Get the player to move and the spot of each piece of a position
+    fn decode(&self, index: usize) -> Placement<'_> {
+        let mut rest = index / 2;
+        let spots = (0..self.pieces.len())
+            .map(|_| {
+                let spot = rest % self.geometry.n_spots;
+                rest /= self.geometry.n_spots;
+                spot
+            })
+            .collect();
+
+        Placement {
+            geometry: self.geometry,
+            pieces: self.pieces,
+            spots,
+            captured: None,
+        }
+    }
+
+    /// Find whether a position is legal, its moves, and the results of its captures
+    fn start(&mut self, index: usize, captures: &[Option<&Tablebase>]) {
+        let side_to_move = index % 2;
+        let mut position = self.decode(index);
+
+        // Each piece must have its own spot, and the player who moved cannot be in check
+        let mut sorted = position.spots.clone();
+        sorted.sort_unstable();
+        sorted.dedup();
+        if sorted.len() != self.pieces.len() || position.is_in_check(1 - side_to_move) {
+            return;
+        }
+
+        let mut n_moves = 0;
+        let mut plies = 0;
+        for piece in (0..self.pieces.len()).filter(|&piece| self.pieces[piece].0 == side_to_move) {
+            let from = position.spots[piece];
+            for (to, captured) in position.moves(piece, true) {
+                position.spots[piece] = to;
+                position.captured = captured;
+
+                if !position.is_in_check(side_to_move) {
+                    n_moves += 1;
+
+                    // Captures are resolved by the tablebase after the capture
+                    if let Some(captured) = captured {
+                        let mut spots = position.spots.clone();
+                        spots.remove(captured);
+                        let result = captures[captured].map_or(DRAW, |tablebase| {
+                            tablebase.results[tablebase.index(1 - side_to_move, &spots)]
+                        });
+
+                        match Probe::from_result(result) {
+                            Some(Probe::Loss(loss)) => add_win(&mut plies, loss + 1),
+                            Some(Probe::Win(win)) => {
+                                n_moves -= 1;
+                                add_loss(&mut plies, win + 1);
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+
+                position.spots[piece] = from;
+                position.captured = None;
+            }
+        }
+
+        // A player with no moves is out of play
+        if n_moves == 0 && plies == 0 {
+            plies = 1;
+        }
+
+        self.is_legal[index] = true;
+        self.n_moves[index] = n_moves;
+        self.plies[index] = plies;
+        self.max_plies = self.max_plies.max(plies);
+    }
+
+    /// Finalise a position's result if it has the plies + 1 of the level,
+    /// updating the positions which could have been before it
+    fn finalise(&mut self, tablebase: &Tablebase, index: usize, level: u16) {
+        let is_win = level.is_multiple_of(2);
+        if self.is_done[index] || self.plies[index] != level || (!is_win && self.n_moves[index] != 0) {
+            return;
+        }
+        self.is_done[index] = true;
+
+        let side_to_move = index % 2;
+        let mut position = self.decode(index);
+        let mut previous = Vec::new();
+
+        // Move back each piece of the player who moved
+        let mover = 1 - side_to_move;
+        for piece in (0..self.pieces.len()).filter(|&piece| self.pieces[piece].0 == mover) {
+            let to = position.spots[piece];
+            for (from, _) in position.moves(piece, false) {
+                position.spots[piece] = from;
+                previous.push(tablebase.index(mover, &position.spots));
+            }
+            position.spots[piece] = to;
+        }
+
+        for previous in previous {
+            if !self.is_legal[previous] || self.is_done[previous] {
+                continue;
+            }
+
+            if is_win {
+                self.n_moves[previous] -= 1;
+                add_loss(&mut self.plies[previous], level);
+            } else {
+                add_win(&mut self.plies[previous], level);
+            }
+            self.max_plies = self.max_plies.max(self.plies[previous]);
+        }
+    }
+}
+
+/// Record a move to a position lost by the opponent
+const fn add_win(plies: &mut u16, win: u16) {
+    // Keep the fewest plies, replacing a possible loss
+    if *plies % 2 == 1 || *plies == 0 || win + 1 < *plies {
+        *plies = win + 1;
+    }
+}
+
+/// Record a move to a position won by the opponent
+fn add_loss(plies: &mut u16, loss: u16) {
+    // Keep the most plies, unless there is a possible win
+    if *plies % 2 == 1 || *plies == 0 {
+        *plies = (*plies).max(loss + 1);
+    }
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// A set of tablebases, which can be probed with games
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Tablebases(HashMap<(Material, u16, u16), Tablebase>);
+
+impl Tablebases {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    /// Get the tablebase for some material and board size
+    pub fn get(&self, material: &Material, width: u16, height: u16) -> Option<&Tablebase> {
+        self.0.get(&(material.clone(), width, height))
+    }
+
+    /// Get every tablebase
+    pub fn iter(&self) -> impl Iterator<Item = &Tablebase> {
+        self.0.values()
+    }
+
+    pub fn insert(&mut self, tablebase: Tablebase) {
+        self.0.insert(
+            (tablebase.material.clone(), tablebase.width, tablebase.height),
+            tablebase,
+        );
+    }
+
+    /// Build the tablebase for some material and board size,
+    /// and the tablebases for the material after each capture, unless they are already in the set
+    ///
+    /// # Errors
+    /// - [`Error::TooLarge`] - A tablebase has too many positions to build
+    /// - [`Error::TooDeep`] - A result has more plies than can be stored
+    pub fn generate(&mut self, material: &Material, width: u16, height: u16) -> Result<&Tablebase, Error> {
+        let key = (material.clone(), width, height);
+        if !self.0.contains_key(&key) {
+            for piece in 0..material.pieces().count() {
+                let (_, type_id) = material.pieces().nth(piece).unwrap_or((0, KING));
+                if type_id != KING {
+                    self.generate(&material.without(piece), width, height)?;
+                }
+            }
+
+            let tablebase = Tablebase::generate(material.clone(), width, height, self)?;
+            self.0.insert(key.clone(), tablebase);
+        }
+
+        Ok(&self.0[&key])
+    }
+
+    #[must_use]
+    /// Get the result of a game for the current player
+    ///
+    /// Returns `None` unless exactly two players are in play,
+    /// with pieces matching a tablebase in the set, and no neutral pieces
+    pub fn probe(&self, game: &Game<StandardCompatiblePieceSet>) -> Option<Probe> {
+        let players: Vec<u8> = (0..game.n_players()).filter(|&player| game.is_in_play(player)).collect();
+        let &[first, second] = players.as_slice() else { return None };
+        if game.is_neutral_phase() {
+            return None;
+        }
+
+        let (width, height, spots) = game.board().export();
+        let mut pieces = [Vec::new(), Vec::new()];
+        for (index, (player, type_id)) in spots.into_iter().enumerate() {
+            if type_id == 0 {
+                continue;
+            }
+            let side = match player {
+                _ if player == first => 0,
+                _ if player == second => 1,
+                _ => return None,
+            };
+            pieces[side].push((type_id, Coordinate(index % width, index / width)));
+        }
+        let side_to_move = usize::from(game.current_player() == second);
+        let (width, height) = (u16::try_from(width).ok()?, u16::try_from(height).ok()?);
+
+        for side in &mut pieces {
+            side.sort_by_key(|&(type_id, _)| order(type_id));
+        }
+        let material = Material::new(pieces.clone().map(|side| side.into_iter().map(|(type_id, _)| type_id).collect())).ok()?;
+
+        // The rules are the same for both players, so either can have the first player's pieces
+        let (tablebase, side_to_move, pieces) = match self.get(&material, width, height) {
+            Some(tablebase) => (tablebase, side_to_move, [&pieces[0], &pieces[1]]),
+            None => (
+                self.get(&material.swapped(), width, height)?,
+                1 - side_to_move,
+                [&pieces[1], &pieces[0]],
+            ),
+        };
+
+        let spots: Vec<Coordinate> = pieces.into_iter().flatten().map(|&(_, spot)| spot).collect();
+        tablebase.probe(side_to_move, &spots)
+    }
+
+    #[must_use]
+    /// Get the best move for the current player of a game,
+    /// winning in the fewest plies, or losing in the most
+    ///
+    /// Returns `None` if the game cannot be probed
+    pub fn best_move(&self, game: &Game<StandardCompatiblePieceSet>) -> Option<PartialMove> {
+        self.probe(game)?;
+
+        let mut best: Option<(PartialMove, i32)> = None;
+        for &(from, to, data) in game.valid_moves() {
+            let mut next = game.clone();
+            next.make_move(&Move {
+                from,
+                to,
+                data,
+                player: game.current_player(),
+            })
+            .ok()?;
+            next.start_turn().ok()?;
+
+            // Score the move, higher is better
+            let score = if next.is_over() {
+                i32::MAX
+            } else {
+                match self.probe(&next)? {
+                    Probe::Loss(plies) => i32::MAX - 1 - i32::from(plies),
+                    Probe::Draw => 0,
+                    Probe::Win(plies) => i32::MIN + 1 + i32::from(plies),
+                }
+            };
+
+            if best.is_none_or(|(_, best_score)| score > best_score) {
+                best = Some(((from, to, data), score));
+            }
+        }
+
+        best.map(|(r#move, _)| r#move)
+    }
+
+    /// Write each tablebase to a directory, named by its material and board size
+    ///
+    /// # Errors
+    /// - [`io::Error`] - A tablebase could not be written
+    pub fn write_dir(&self, directory: &Path) -> io::Result<()> {
+        fs::create_dir_all(directory)?;
+
+        for tablebase in self.0.values() {
+            let name = format!(
+                "{}-{}x{}.tb",
+                tablebase.material, tablebase.width, tablebase.height
+            );
+            tablebase.write(&mut io::BufWriter::new(fs::File::create(directory.join(name))?))?;
+        }
+
+        Ok(())
+    }
+
+    /// Read every `.tb` tablebase in a directory
+    ///
+    /// # Errors
+    /// - [`io::Error`] - A tablebase could not be read
+    pub fn read_dir(directory: &Path) -> io::Result<Self> {
+        let mut tablebases = Self::new();
+
+        for entry in fs::read_dir(directory)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|extension| extension == "tb") {
+                tablebases.insert(Tablebase::read(&mut io::BufReader::new(fs::File::open(path)?))?);
+            }
+        }
+
+        Ok(tablebases)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        coordinate::Coordinate,
+        game::Game,
+        r#move::Move,
+        standard_pieces::{King, Knight, Queen, Rook, StandardCompatiblePieceSet},
+    };
+
+    use super::{Material, Probe, Tablebase, Tablebases};
+
+    /// Get the result of a game from the results after each of its moves
+    fn expected(game: &Game<StandardCompatiblePieceSet>, tablebases: &Tablebases) -> Probe {
+        // Castling is not in the tablebase
+        let results: Vec<Probe> = game
+            .valid_moves()
+            .iter()
+            .filter(|&&(_, _, data)| data == 0)
+            .map(|&(from, to, data)| {
+                let mut next = game.clone();
+                next.make_move(&Move {
+                    from,
+                    to,
+                    data,
+                    player: game.current_player(),
+                })
+                .expect("failed to make move");
+                next.start_turn().expect("failed to start turn");
+
+                if next.is_over() {
+                    Probe::Loss(0)
+                } else {
+                    tablebases.probe(&next).expect("failed to probe next position")
+                }
+            })
+            .collect();
+
+        let fewest_loss = results
+            .iter()
+            .filter_map(|&result| if let Probe::Loss(plies) = result { Some(plies) } else { None })
+            .min();
+        let most_win = results
+            .iter()
+            .filter_map(|&result| if let Probe::Win(plies) = result { Some(plies) } else { None })
+            .max();
+
+        match (fewest_loss, most_win) {
+            (Some(plies), _) => Probe::Win(plies + 1),
+            _ if results.contains(&Probe::Draw) => Probe::Draw,
+            (None, plies) => Probe::Loss(plies.map_or(0, |plies| plies + 1)),
+        }
+    }
+
+    #[test]
+    fn material() {
+        let material: Material = "KNBK".parse().expect("failed to parse material");
+        assert_eq!(material.to_string(), "KBNK", "material not in order");
+
+        for invalid in ["", "QK", "KQ", "KPK", "KKK"] {
+            assert!(invalid.parse::<Material>().is_err(), "test failed: parsed {invalid}");
+        }
+    }
+
+    #[test]
+    fn matches_game() {
+        let (width, height) = (4, 5);
+        let mut tablebases = Tablebases::new();
+        let spots: Vec<Coordinate> = (0..height)
+            .flat_map(|y| (0..width).map(move |x| Coordinate(usize::from(x), usize::from(y))))
+            .collect();
+
+        for (material, new_piece) in [
+            ("KQK", Queen::new as fn(u8) -> _),
+            ("KRK", Rook::new),
+            ("KNK", Knight::new),
+        ] {
+            let tablebase = tablebases
+                .generate(&material.parse().expect("failed to parse material"), width, height)
+                .expect("failed to generate tablebase")
+                .clone();
+
+            let mut bytes = Vec::new();
+            tablebase.write(&mut bytes).expect("failed to write tablebase");
+            assert_eq!(
+                Tablebase::read(&mut bytes.as_slice()).ok().as_ref(),
+                Some(&tablebase),
+                "tablebase changed when written and read"
+            );
+
+            // Check the results of the tablebase against the moves of the game
+            let mut n_checked = 0;
+            for (i, &king) in spots.iter().enumerate() {
+                for &piece in spots.iter().skip(i % 3).step_by(3) {
+                    for &other_king in spots.iter().skip(i % 2).step_by(2) {
+                        for player in 0..2 {
+                            let Some(probe) = tablebase.probe(player, &[king, piece, other_king]) else { continue };
+
+                            let mut game = Game::new(2, width, height);
+                            game.add_pieces(vec![
+                                (King::new(0), king),
+                                (new_piece(0), piece),
+                                (King::new(1), other_king),
+                            ])
+                            .expect("failed to add pieces");
+                            game.set_current_player(u8::from(player == 1))
+                                .expect("failed to set player");
+
+                            assert_eq!(tablebases.probe(&game), Some(probe), "wrong probe");
+                            assert_eq!(
+                                probe,
+                                expected(&game, &tablebases),
+                                "test failed: {material}: {king} {piece} {other_king}, player {player} to move"
+                            );
+                            n_checked += 1;
+                        }
+                    }
+                }
+            }
+
+            assert!(n_checked > 100, "too few positions checked for {material}");
+        }
+
+        // The queen can always leave the other king without moves
+        let statistics = tablebases
+            .get(&"KQK".parse().expect("failed to parse material"), width, height)
+            .expect("missing tablebase")
+            .statistics();
+        assert_eq!(statistics.results[0][1..], [0, 0], "first player could not always win");
+    }
+}
@@ -0,0 +1,321 @@
+use crate::{
+    attack::Pin,
+    board::Board,
+    coordinate::Coordinate,
+    delta::Delta,
+    error::Error,
+    piece_set::{PieceSet, NEUTRAL},
+};
+
+/// A tactical opportunity for a player
+#[derive(Clone, Debug, PartialEq)]
+pub enum Tactic {
+    /// An opponent's piece can be won by capturing it
+    Hanging {
+        /// The position of the opponent's piece
+        piece: Coordinate,
+        /// The points gained by the best exchange on the piece's spot
+        gain: i32,
+    },
+    /// A piece attacks several valuable opponent's pieces at once
+    Fork {
+        /// The position of the forking piece
+        attacker: Coordinate,
+        /// The positions of the attacked pieces
+        targets: Vec<Coordinate>,
+    },
+    /// An opponent's piece cannot move without exposing their royal piece
+    Pin(Pin),
+    /// An opponent's valuable piece is attacked in front of another of their pieces
+    Skewer {
+        /// The position of the skewering piece
+        attacker: Coordinate,
+        /// The position of the attacked piece in front
+        front: Coordinate,
+        /// The position of the piece behind, which is exposed once the front piece moves
+        behind: Coordinate,
+    },
+}
+
+/// Get the value of a piece in an exchange, with uncapturable pieces being worth the most
+fn value<Set: PieceSet>(piece: &Set) -> i32 {
+    piece
+        .capture_points()
+        .map_or(i32::MAX, i32::from)
+}
+
+impl<Set: PieceSet> Board<Set> {
+    /// Get the points a player gains from capturing on a spot,
+    /// if both sides then keep recapturing with their least valuable piece while it gains them points
+    ///
+    /// Only the capturing player and the owner of the captured piece are counted as sides.
+    /// With three or more players, recaptures by other players are ignored,
+    /// so the result is an estimate which may be wrong when a third player also attacks the spot
+    ///
+    /// # Errors
+    /// - [`Error<Set>::CoordinateNotOnBoard`] - `from` or `to` is not on the board
+    /// - [`Error<Set>::NoPieceAtSpot`] - There is no piece in `from` or `to`
+    /// - [`Error<Set>::PieceNotCapturable`] - The piece in `to` cannot be captured
+    /// - [`Error<Set>::PieceError`] - Error from a piece
+    pub fn static_exchange(&self, from: &Coordinate, to: &Coordinate) -> Result<i32, Error<Set>> {
+        let Some(attacker) = self.get(from)?.get() else {
+            return Err(Error::NoPieceAtSpot(*from));
+        };
+        let Some(target) = self.get(to)?.get() else {
+            return Err(Error::NoPieceAtSpot(*to));
+        };
+        let Some(points) = target.capture_points() else {
+            return Err(Error::PieceNotCapturable(*to));
+        };
+        let sides = [attacker.player(), target.player()];
+
+        // Make the captures on a copy of the board, so attacks revealed by each capture are included
+        let mut board = self.clone();
+        let mut gains = vec![i32::from(points)];
+        let mut capturer = *from;
+
+        loop {
+            // The value of the piece which has just captured, and can be captured back
+            let at_risk = board.get(&capturer)?.get().as_ref().map_or(0, value);
+            board.apply_delta(Delta::Delete(*to))?;
+            board.apply_delta(Delta::Move(capturer, *to))?;
+
+            // Find the least valuable piece which can recapture
+            let side = sides[gains.len() % 2];
+            let Some((next, next_value)) = board
+                .attackers(to, side)?
+                .into_iter()
+                .filter_map(|position| {
+                    board
+                        .get(&position)
+                        .ok()?
+                        .get()
+                        .as_ref()
+                        .map(|piece| (position, value(piece)))
+                })
+                .min_by_key(|&(_, value)| value)
+            else {
+                break;
+            };
+
+            // An uncapturable piece can only capture onto a spot which is not attacked
+            if at_risk == i32::MAX
+                || (next_value == i32::MAX
+                    && !board
+                        .attackers(to, sides[(gains.len() + 1) % 2])?
+                        .is_empty())
+            {
+                break;
+            }
+
+            gains.push(at_risk - gains[gains.len() - 1]);
+            capturer = next;
+        }
+
+        // Each side stops recapturing once it would lose points
+        while gains.len() > 1 {
+            let gain = gains.pop().unwrap_or_default();
+            let last = gains.len() - 1;
+            gains[last] = -(-gains[last]).max(gain);
+        }
+
+        Ok(gains[0])
+    }
+
+    /// Get the tactical opportunities for a player
+    ///
+    /// Winning captures are found with [`Board::static_exchange`], so only consider recaptures by
+    /// the owner of the captured piece
+    ///
+    /// # Errors
+    /// - [`Error<Set>::CoordinateNotOnBoard`] - A piece attacks a coordinate not on the board
+    /// - [`Error<Set>::PieceError`] - Error from a piece
+    pub fn tactics(&self, player: u8) -> Result<Vec<Tactic>, Error<Set>> {
+        let mut tactics = Vec::new();
+        let mut forks: Vec<(Coordinate, Vec<Coordinate>)> = Vec::new();
+        let mut opponents = Vec::new();
+
+        for (y, rank) in self.raw().iter().enumerate() {
+            for (x, spot) in rank.iter().enumerate() {
+                let position = Coordinate(x, y);
+                // Only opponent's pieces can be targeted
+                let Some(piece) = spot.get() else { continue };
+                let opponent = piece.player();
+                if opponent == player || opponent == NEUTRAL {
+                    continue;
+                }
+                if !opponents.contains(&opponent) {
+                    opponents.push(opponent);
+                }
+
+                let attackers = self.attackers(&position, player)?;
+                if attackers.is_empty() {
+                    continue;
+                }
+
+                // Hanging pieces
+                if piece.capture_points().is_some() {
+                    let mut best = None;
+                    for attacker in &attackers {
+                        let gain = self.static_exchange(attacker, &position)?;
+                        best = best.max(Some(gain));
+                    }
+
+                    if let Some(gain) = best.filter(|&gain| gain > 0) {
+                        tactics.push(Tactic::Hanging {
+                            piece: position,
+                            gain,
+                        });
+                    }
+                }
+
+                // Fork targets, pieces which are royal, undefended, or worth more than their attacker
+                let undefended = self.defenders(&position)?.is_empty();
+                for attacker in attackers {
+                    let Some(attacking_piece) = self.get(&attacker)?.get() else { continue };
+                    if !undefended && value(piece) <= value(attacking_piece) {
+                        continue;
+                    }
+
+                    match forks.iter_mut().find(|(from, _)| from == &attacker) {
+                        Some((_, targets)) => targets.push(position),
+                        None => forks.push((attacker, vec![position])),
+                    }
+                }
+
+                // Skewers, by attacking a more valuable piece with a piece behind it
+                for &(attacker, from, blockable) in spot.attackers() {
+                    if !blockable || attacker != player {
+                        continue;
+                    }
+
+                    let Some((_, Some(behind))) = self.x_ray(&from, &position) else { continue };
+                    let Some(behind_piece) = self.get(&behind)?.get() else { continue };
+                    if behind_piece.player() == player
+                        || behind_piece.player() == NEUTRAL
+                        || behind_piece.capture_points().is_none()
+                        || value(behind_piece) >= value(piece)
+                    {
+                        continue;
+                    }
+
+                    tactics.push(Tactic::Skewer {
+                        attacker: from,
+                        front: position,
+                        behind,
+                    });
+                }
+            }
+        }
+
+        tactics.extend(
+            forks
+                .into_iter()
+                .filter(|(_, targets)| targets.len() > 1)
+                .map(|(attacker, targets)| Tactic::Fork { attacker, targets }),
+        );
+
+        // Pins against every opponent by the player's pieces
+        for opponent in opponents {
+            for pin in self.pins(opponent)? {
+                let pinned_by_player = self
+                    .get(&pin.pinner)?
+                    .get()
+                    .as_ref()
+                    .is_some_and(|piece| piece.player() == player);
+                if pinned_by_player {
+                    tactics.push(Tactic::Pin(pin));
+                }
+            }
+        }
+
+        Ok(tactics)
+    }
+}
+
+#[cfg(all(test, feature = "standard_pieces"))]
+mod test {
+    use crate::{
+        coordinate::Coordinate,
+        game::Game,
+        standard_pieces::{Bishop, King, Knight, Queen, Rook},
+    };
+
+    use super::Tactic;
+
+    #[test]
+    fn static_exchange() {
+        const TARGET_POSITION: Coordinate = Coordinate(3, 4);
+
+        let mut game = Game::new(2, 8, 8);
+        game.add_pieces([
+            (Knight::new(1), TARGET_POSITION),
+            (Rook::new(0), Coordinate(3, 0)),
+            (Queen::new(0), Coordinate(0, 1)),
+            (Bishop::new(1), Coordinate(5, 6)),
+        ])
+        .expect("failed to add pieces to board");
+
+        // The rook wins the knight and is lost to the bishop, which is lost to the queen
+        assert_eq!(
+            game.board()
+                .static_exchange(&Coordinate(3, 0), &TARGET_POSITION)
+                .expect("failed to evaluate exchange"),
+            3 - 5 + 3,
+            "wrong defended exchange"
+        );
+
+        // Without the defender, the knight is won outright
+        game.remove_piece(&Coordinate(5, 6))
+            .expect("failed to remove bishop");
+        assert_eq!(
+            game.board()
+                .static_exchange(&Coordinate(3, 0), &TARGET_POSITION)
+                .expect("failed to evaluate exchange"),
+            3,
+            "wrong undefended exchange"
+        );
+    }
+
+    #[test]
+    fn tactics() {
+        let mut game = Game::new(2, 8, 8);
+        game.add_pieces([
+            // A knight forking a king and a rook, which is defended by the other rook
+            (Knight::new(0), Coordinate(2, 2)),
+            (King::new(1), Coordinate(1, 4)),
+            (Rook::new(1), Coordinate(3, 4)),
+            // A rook skewering a queen in front of a knight
+            (Rook::new(0), Coordinate(7, 6)),
+            (Queen::new(1), Coordinate(5, 6)),
+            (Knight::new(1), Coordinate(3, 6)),
+            (Rook::new(1), Coordinate(5, 4)),
+        ])
+        .expect("failed to add pieces to board");
+
+        let tactics = game.board().tactics(0).expect("failed to find tactics");
+
+        assert!(
+            tactics.contains(&Tactic::Fork {
+                attacker: Coordinate(2, 2),
+                targets: vec![Coordinate(1, 4), Coordinate(3, 4)],
+            }),
+            "fork not found: {tactics:?}"
+        );
+        assert!(
+            tactics.contains(&Tactic::Skewer {
+                attacker: Coordinate(7, 6),
+                front: Coordinate(5, 6),
+                behind: Coordinate(3, 6),
+            }),
+            "skewer not found: {tactics:?}"
+        );
+        assert!(
+            tactics.contains(&Tactic::Hanging {
+                piece: Coordinate(3, 4),
+                gain: 5 - 3,
+            }),
+            "hanging piece not found: {tactics:?}"
+        );
+    }
+}
@@ -0,0 +1,53 @@
+#![warn(
+    clippy::all,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::perf,
+    clippy::cargo
+)]
+
+use std::{env, error, fs, process};
+
+use chessehc::engine::{book::Book, record::GameRecord};
+
+const USAGE: &str = "\
+Usage: chessehc_book [options] <records>...
+
+Builds an opening book from files of game records
+
+Options:
+  --depth <n>          moves of each game to add (default 16)
+  --output <path>      file to write the book to (default book.bin)";
+
+fn main() -> Result<(), Box<dyn error::Error>> {
+    let mut depth = 16;
+    let mut output = "book.bin".to_owned();
+    let mut paths = Vec::new();
+
+    let mut arguments = env::args().skip(1);
+    while let Some(argument) = arguments.next() {
+        match argument.as_str() {
+            "--depth" => depth = arguments.next().and_then(|value| value.parse().ok()).unwrap_or(0),
+            "--output" => output = arguments.next().unwrap_or_default(),
+            _ => paths.push(argument),
+        }
+    }
+    if depth == 0 || output.is_empty() || paths.is_empty() {
+        eprintln!("{USAGE}");
+        process::exit(2);
+    }
+
+    let mut book = Book::new();
+    let mut n_records = 0;
+    for path in paths {
+        for record in GameRecord::parse_all(&fs::read_to_string(&path)?)? {
+            book.add_record(&record, depth)?;
+            n_records += 1;
+        }
+    }
+
+    book.write(&mut fs::File::create(&output)?)?;
+    println!("{n_records} records, {} positions written to {output}", book.len());
+
+    Ok(())
+}
@@ -8,10 +8,16 @@ pub const DATABASE_URL_ENV_VARIABLE: (&str, &str) = (
     "postgres://<user>:<password>@<address>/chessehc",
 );
 pub const BIND_URL_ENV_VARIABLE: (&str, &str) = ("SERVER_URL", "<address>:<port>");
+/// An optional opening book for searched bots
+pub const BOOK_PATH_ENV_VARIABLE: (&str, &str) = ("BOOK_PATH", "<path>");
+/// An optional file where started games are saved as game records when they end
+pub const RECORD_PATH_ENV_VARIABLE: (&str, &str) = ("RECORD_PATH", "<path>");
 
 pub const GAME_BROADCAST_CAPACITY: usize = 10;
 pub const GAME_RECEIVER_CAPACITY: usize = 20;
 pub const GAME_SENDER_CAPACITY: usize = 5;
+pub const LOBBY_BROADCAST_CAPACITY: usize = 50;
+pub const MATCHMAKING_RECEIVER_CAPACITY: usize = 50;
 
 pub const GAME_MAX_CODE_SEARCH_TRIES: u8 = 200;
 
@@ -23,6 +29,17 @@ pub const USERNAME_MAX_LENGTH: usize = 15;
 
 pub const PLAYER_LIMIT: u8 = 32;
 
+pub const CHAT_MAX_LENGTH: usize = 256;
+pub const CHAT_RATE_LIMIT: usize = 5;
+pub const CHAT_RATE_PERIOD_SECS: u64 = 10;
+
+pub const BOT_SEARCH_MILLIS: u64 = 1000;
+
+pub const BOARD_MIN_WIDTH: u16 = 8;
+pub const BOARD_MAX_WIDTH: u16 = 16;
+pub const MIN_ROWS_PER_PLAYER: u16 = 4;
+pub const MAX_ROWS_PER_PLAYER: u16 = 16;
+
 lazy_static! {
     static ref USERNAME_REGEX: Regex =
         Regex::new(r"^[!./0-9?A-Z_a-z]*$").expect("failed to compile regex");
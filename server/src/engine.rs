@@ -0,0 +1,162 @@
+#![warn(
+    clippy::all,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::perf,
+    clippy::cargo
+)]
+
+use std::{
+    cmp::Reverse,
+    env, fs,
+    io::{self, BufRead, Write},
+    path::Path,
+};
+
+use chessehc::{
+    engine::{book::{Book, STANDARD_VARIANT}, Command, Info, Limit, Position, Reply},
+    mcts::{Budget, Mcts, REMAINING_FRACTION},
+    rng::Rng,
+    tablebase::Tablebases,
+};
+
+/// The iterations searched when no limit is given
+const DEFAULT_ITERATIONS: u32 = 1000;
+
+fn main() -> io::Result<()> {
+    // An opening book can be given with `--book <path>`, and tablebases with `--tablebases <directory>`
+    let mut arguments = env::args().skip(1);
+    let mut book = None;
+    let mut tablebases = None;
+    while let Some(argument) = arguments.next() {
+        match (argument.as_str(), arguments.next()) {
+            ("--book", Some(path)) => book = Some(Book::read(&mut fs::File::open(path)?)?),
+            ("--tablebases", Some(path)) => tablebases = Some(Tablebases::read_dir(Path::new(&path))?),
+            _ => {
+                eprintln!("Usage: chessehc_engine [--book <path>] [--tablebases <directory>]");
+                std::process::exit(2);
+            }
+        }
+    }
+
+    let stdin = io::stdin();
+    let mut stdout = io::stdout().lock();
+
+    let mut position: Option<Position> = None;
+    let mut seed = 0;
+
+    for line in stdin.lock().lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let command = match line.parse::<Command>() {
+            Ok(command) => command,
+            Err(err) => {
+                writeln!(stdout, "{}", Reply::Error(err.to_string()))?;
+                stdout.flush()?;
+                continue;
+            }
+        };
+
+        let replies = match command {
+            Command::Chessehc => vec![
+                Reply::Id("name".to_owned(), format!("chessehc {}", env!("CARGO_PKG_VERSION"))),
+                Reply::Id("author".to_owned(), env!("CARGO_PKG_AUTHORS").to_owned()),
+                Reply::Ok,
+            ],
+            Command::IsReady => vec![Reply::ReadyOk],
+            Command::NewGame => {
+                position = None;
+                seed += 1;
+                Vec::new()
+            }
+            Command::Position(new_position) => {
+                position = Some(new_position);
+                Vec::new()
+            }
+            Command::Go(limit) => go(position.as_ref(), book.as_ref(), tablebases.as_ref(), limit, seed),
+            Command::Quit => break,
+        };
+
+        for reply in replies {
+            writeln!(stdout, "{reply}")?;
+        }
+        stdout.flush()?;
+    }
+
+    Ok(())
+}
+
+/// Search the position, returning the info and best move replies
+///
+/// Moves in the opening book or tablebases are played without searching
+fn go(
+    position: Option<&Position>,
+    book: Option<&Book>,
+    tablebases: Option<&Tablebases>,
+    limit: Limit,
+    seed: u64,
+) -> Vec<Reply> {
+    let Some(position) = position else {
+        return vec![Reply::Error("No position set!".to_owned())];
+    };
+    let game = match position.game() {
+        Ok(game) => game,
+        Err(err) => return vec![Reply::Error(err.to_string())],
+    };
+
+    if let Some(r#move) = book
+        .and_then(|book| book.choose(&game, STANDARD_VARIANT, &mut Rng::new(seed)))
+        .or_else(|| tablebases.and_then(|tablebases| tablebases.best_move(&game)))
+    {
+        return vec![Reply::BestMove(Some(r#move))];
+    }
+
+    let budget = match limit {
+        Limit {
+            move_time: Some(move_time),
+            ..
+        } => Budget::Time(move_time),
+        Limit {
+            remaining: Some(remaining),
+            ..
+        } => Budget::Time(remaining / REMAINING_FRACTION),
+        Limit {
+            iterations: Some(iterations),
+            ..
+        } => Budget::Iterations(iterations),
+        _ => Budget::Iterations(DEFAULT_ITERATIONS),
+    };
+
+    let mut mcts = Mcts::new(seed);
+    mcts.set_budget(budget);
+
+    let search = match mcts.search(&game) {
+        Ok(Some(search)) => search,
+        Ok(None) => return vec![Reply::BestMove(None)],
+        Err(err) => return vec![Reply::Error(err.to_string())],
+    };
+
+    let mut statistics = search.statistics;
+    statistics.sort_by_key(|statistic| Reverse(statistic.visits));
+
+    let mut replies: Vec<Reply> = statistics
+        .into_iter()
+        .map(|statistic| {
+            Reply::Info(Info {
+                r#move: (statistic.r#move.from, statistic.r#move.to, statistic.r#move.data),
+                visits: statistic.visits,
+                reward: statistic.mean_reward,
+            })
+        })
+        .collect();
+    replies.push(Reply::BestMove(Some((
+        search.r#move.from,
+        search.r#move.to,
+        search.r#move.data,
+    ))));
+
+    replies
+}
@@ -0,0 +1,128 @@
+#![warn(
+    clippy::all,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::perf,
+    clippy::cargo
+)]
+
+use std::{env, error, fs, path::PathBuf, process};
+
+use chessehc::{rng::Rng, simulation::simulate};
+
+const USAGE: &str = "\
+Usage: chessehc_fuzz [options]
+
+Options:
+  --games <n>          games to play (default 100)
+  --min-players <n>    fewest players in a game (default 2)
+  --max-players <n>    most players in a game (default 32)
+  --max-moves <n>      moves before a game is stopped (default 500)
+  --seed <n>           seed for choosing the games (default 0)
+  --failures <path>    directory to write the failing games' records to";
+
+struct Config {
+    games: u32,
+    min_players: u8,
+    max_players: u8,
+    max_moves: usize,
+    seed: u64,
+    failures: Option<PathBuf>,
+}
+
+impl Config {
+    fn parse(mut arguments: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut config = Self {
+            games: 100,
+            min_players: 2,
+            max_players: 32,
+            max_moves: 500,
+            seed: 0,
+            failures: None,
+        };
+
+        while let Some(argument) = arguments.next() {
+            let Some(option) = argument.strip_prefix("--") else {
+                return Err(format!("unexpected argument: {argument}"));
+            };
+
+            let value = arguments
+                .next()
+                .ok_or_else(|| format!("missing value for --{option}"))?;
+            let invalid = || format!("invalid value for --{option}: {value}");
+
+            match option {
+                "games" => config.games = value.parse().map_err(|_| invalid())?,
+                "min-players" => config.min_players = value.parse().map_err(|_| invalid())?,
+                "max-players" => config.max_players = value.parse().map_err(|_| invalid())?,
+                "max-moves" => config.max_moves = value.parse().map_err(|_| invalid())?,
+                "seed" => config.seed = value.parse().map_err(|_| invalid())?,
+                "failures" => config.failures = Some(value.into()),
+                _ => return Err(format!("unknown option: --{option}")),
+            }
+        }
+
+        if config.min_players == 0 || config.min_players > config.max_players {
+            return Err("the players must be at least one, and the minimum at most the maximum".to_owned());
+        }
+
+        Ok(config)
+    }
+}
+
+fn main() -> Result<(), Box<dyn error::Error>> {
+    let config = match Config::parse(env::args().skip(1)) {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("{err}\n\n{USAGE}");
+            process::exit(2);
+        }
+    };
+
+    if let Some(directory) = &config.failures {
+        fs::create_dir_all(directory)?;
+    }
+
+    // Don't print the panics caught by the simulation
+    std::panic::set_hook(Box::new(|_| {}));
+
+    let mut rng = Rng::new(config.seed);
+    let range = usize::from(config.max_players - config.min_players) + 1;
+    let mut n_failures = 0;
+    let mut n_moves = 0;
+
+    for _ in 0..config.games {
+        let n_players = config.min_players + u8::try_from(rng.below(range))?;
+        let seed = rng.next_u64();
+
+        match simulate(n_players, seed, config.max_moves) {
+            Ok(simulation) => n_moves += simulation.moves,
+            Err(failure) => {
+                n_failures += 1;
+                println!(
+                    "Failed with {n_players} players and seed {seed} after {} moves: {}",
+                    failure.moves.len(),
+                    failure.reason
+                );
+
+                if let Some(directory) = &config.failures {
+                    fs::write(
+                        directory.join(format!("failure-{n_players}-{seed}.txt")),
+                        failure.record().to_string(),
+                    )?;
+                }
+            }
+        }
+    }
+
+    println!(
+        "{} games, {n_moves} moves, {n_failures} failures",
+        config.games
+    );
+
+    if n_failures > 0 {
+        process::exit(1);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,191 @@
+use std::{
+    env, fs,
+    time::{Duration, Instant},
+};
+
+use chessehc::{
+    engine::book::Book,
+    game::Game,
+    mcts::{Budget, Mcts, REMAINING_FRACTION},
+    r#move::{Move, PartialMove},
+    rng::Rng,
+    standard_pieces::StandardCompatiblePieceSet,
+};
+use lazy_static::lazy_static;
+use tokio::{sync::mpsc, task};
+
+use crate::config::{BOOK_PATH_ENV_VARIABLE, BOT_SEARCH_MILLIS, PLAYER_LIMIT};
+
+use super::{GameMessage, PlayerMessage, Variant};
+
+lazy_static! {
+    /// The opening book used by searched bots, if one is configured
+    static ref BOOK: Option<Book> = {
+        let path = env::var(BOOK_PATH_ENV_VARIABLE.0).ok()?;
+        match fs::File::open(&path).and_then(|mut file| Book::read(&mut file)) {
+            Ok(book) => Some(book),
+            Err(err) => {
+                eprintln!("Error Reading Opening Book {path}: {err}");
+                None
+            }
+        }
+    };
+}
+
+/// How a bot chooses its moves
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strength {
+    /// Any valid move
+    Random,
+    /// Captures of the most valuable piece when possible, otherwise any valid move
+    Greedy,
+    /// A move from the opening book, otherwise a tree search limited by the game's clock
+    Searched,
+}
+
+impl Strength {
+    #[must_use]
+    pub const fn from_id(id: u8) -> Option<Self> {
+        Some(match id {
+            0 => Self::Random,
+            1 => Self::Greedy,
+            2 => Self::Searched,
+            _ => return None,
+        })
+    }
+
+    #[must_use]
+    pub const fn id(self) -> u8 {
+        match self {
+            Self::Random => 0,
+            Self::Greedy => 1,
+            Self::Searched => 2,
+        }
+    }
+
+    /// Get the reserved account of a bot, with one for each seat in a game
+    ///
+    /// Bots have negative account ids, which are never given to players
+    #[must_use]
+    pub fn account_id(self, seat: u8) -> i64 {
+        -(i64::from(self.id()) * i64::from(PLAYER_LIMIT) + i64::from(seat) + 1)
+    }
+
+    /// Choose a move for the current player, which may take a while for searched bots
+    fn choose(
+        self,
+        game: &Game<StandardCompatiblePieceSet>,
+        variant: Variant,
+        seed: u64,
+    ) -> Option<PartialMove> {
+        let player = game.current_player();
+        let moves = game.valid_moves();
+        if moves.is_empty() {
+            return None;
+        }
+
+        let mut rng = Rng::new(seed);
+        match self {
+            Self::Random => Some(moves[rng.below(moves.len())]),
+            Self::Greedy => {
+                let capture = moves
+                    .iter()
+                    .filter_map(|r#move| {
+                        let piece = game.board().get(&r#move.1).ok()?.get().as_ref()?;
+                        if piece.player() == player {
+                            return None;
+                        }
+                        Some((piece.capture_points()?, r#move))
+                    })
+                    .max_by_key(|&(points, _)| points);
+
+                Some(capture.map_or_else(|| moves[rng.below(moves.len())], |(_, r#move)| *r#move))
+            }
+            Self::Searched => {
+                if let Some(r#move) = BOOK.as_ref().and_then(|book| book.choose(game, variant.id(), &mut rng)) {
+                    return Some(r#move);
+                }
+
+                // Use a share of the clock in timed games, so the bot does not run out of time
+                let mut budget = Duration::from_millis(BOT_SEARCH_MILLIS);
+                if let Some(remaining) = game.remaining_time(player, Instant::now()) {
+                    budget = budget.min(remaining / REMAINING_FRACTION);
+                }
+
+                let mut mcts = Mcts::new(seed);
+                mcts.set_budget(Budget::Time(budget));
+
+                // Fall back to a greedy move if the search fails
+                let Ok(Some(search)) = mcts.search(game) else {
+                    return Self::Greedy.choose(game, variant, seed);
+                };
+                let Move { from, to, data, .. } = search.r#move;
+                Some((from, to, data))
+            }
+        }
+    }
+}
+
+/// Play as a bot in a game, until it is removed or the game ends
+///
+/// The game sends the bot the board as the bot sees it whenever it is the bot's turn,
+/// and the bot makes its move the same way as players do.
+/// A bot which sees no valid moves leaves, and the game removes its pieces as for a player
+pub async fn bot(
+    account_id: i64,
+    strength: Strength,
+    variant: Variant,
+    mut receiver: mpsc::Receiver<GameMessage>,
+    game_sender: mpsc::Sender<PlayerMessage>,
+) {
+    while let Some(message) = receiver.recv().await {
+        let GameMessage::Position(game) = message else {
+            continue;
+        };
+
+        let seed = rand::random();
+        let r#move = match task::spawn_blocking(move || strength.choose(&game, variant, seed)).await {
+            Ok(Some(r#move)) => r#move,
+            Ok(None) => {
+                game_sender.send(PlayerMessage::Leave(account_id)).await.ok();
+                break;
+            }
+            Err(err) => {
+                eprintln!("Error Choosing Bot Move: {err}");
+                continue;
+            }
+        };
+
+        if game_sender
+            .send(PlayerMessage::Move(account_id, r#move))
+            .await
+            .is_err()
+        {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Strength;
+
+    #[test]
+    fn test_account_ids() {
+        let mut ids = Vec::new();
+        for id in 0..=u8::MAX {
+            let Some(strength) = Strength::from_id(id) else { continue };
+            assert_eq!(strength.id(), id, "strength {strength:?} has the wrong id");
+
+            for seat in 0..crate::config::PLAYER_LIMIT {
+                ids.push(strength.account_id(seat));
+            }
+        }
+
+        assert_eq!(ids.len(), 3 * usize::from(crate::config::PLAYER_LIMIT), "wrong number of strengths");
+        assert!(ids.iter().all(|id| *id < 0), "bot account id is not reserved");
+        ids.sort_unstable();
+        ids.dedup();
+        assert_eq!(ids.len(), 3 * usize::from(crate::config::PLAYER_LIMIT), "bot account ids overlap");
+    }
+}
@@ -0,0 +1,90 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use nohash_hasher::IntMap;
+
+use crate::config::{CHAT_RATE_LIMIT, CHAT_RATE_PERIOD_SECS};
+
+/// A chat message, stamped by the game
+#[derive(Debug, Clone)]
+pub struct ChatMessage {
+    pub player: i64,
+    /// Milliseconds since the Unix epoch
+    pub timestamp: u64,
+    /// Whether the message is only for spectators
+    pub spectators: bool,
+    pub text: String,
+}
+
+/// The chat of a game, kept for as long as the game and saved with its record
+#[derive(Default)]
+pub(super) struct Chat {
+    log: Vec<ChatMessage>,
+    /// When each player last sent messages, within the rate limit period
+    recent: IntMap<i64, VecDeque<Instant>>,
+}
+
+impl Chat {
+    /// Stamp and log a message, unless the player has sent too many recently
+    pub fn post(&mut self, player: i64, text: String, spectators: bool) -> Option<ChatMessage> {
+        let now = Instant::now();
+        let period = Duration::from_secs(CHAT_RATE_PERIOD_SECS);
+
+        let recent = self.recent.entry(player).or_default();
+        while recent
+            .front()
+            .is_some_and(|sent| now.duration_since(*sent) >= period)
+        {
+            recent.pop_front();
+        }
+        if recent.len() >= CHAT_RATE_LIMIT {
+            return None;
+        }
+        recent.push_back(now);
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |time| u64::try_from(time.as_millis()).unwrap_or(u64::MAX));
+
+        let message = ChatMessage {
+            player,
+            timestamp,
+            spectators,
+            text,
+        };
+        self.log.push(message.clone());
+        Some(message)
+    }
+
+    /// Get the messages sent so far, leaving out the spectators' channel for players
+    pub fn history(&self, spectator: bool) -> Vec<ChatMessage> {
+        self.log
+            .iter()
+            .filter(|message| spectator || !message.spectators)
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::config::CHAT_RATE_LIMIT;
+
+    use super::Chat;
+
+    #[test]
+    fn test_chat() {
+        let mut chat = Chat::default();
+
+        for _ in 0..CHAT_RATE_LIMIT {
+            assert!(chat.post(1, "hello".to_owned(), false).is_some(), "message rate limited");
+        }
+        assert!(chat.post(1, "hello".to_owned(), false).is_none(), "message not rate limited");
+        assert!(chat.post(2, "hello".to_owned(), true).is_some(), "rate limit shared between players");
+
+        assert_eq!(chat.history(true).len(), CHAT_RATE_LIMIT + 1, "wrong spectator history");
+        assert_eq!(chat.history(false).len(), CHAT_RATE_LIMIT, "spectator message in player history");
+    }
+}
@@ -0,0 +1,103 @@
+use lazy_static::lazy_static;
+use tokio::sync::broadcast;
+
+use crate::config::LOBBY_BROADCAST_CAPACITY;
+
+use super::{code::token_to_code, Settings, GAMES};
+
+lazy_static! {
+    static ref LOBBY_UPDATES: broadcast::Sender<LobbyUpdate> =
+        broadcast::channel(LOBBY_BROADCAST_CAPACITY).0;
+}
+
+/// A game as shown in the lobby browser
+#[derive(Debug, Clone)]
+pub struct Lobby {
+    pub host: i64,
+    pub players: u8,
+    pub settings: Settings,
+    pub started: bool,
+}
+
+impl Lobby {
+    #[must_use]
+    pub const fn new(host: i64, settings: Settings) -> Self {
+        Self {
+            host,
+            players: 1,
+            settings,
+            started: false,
+        }
+    }
+
+    /// Get whether the game is listed, as a public game waiting for players
+    #[must_use]
+    pub const fn is_open(&self) -> bool {
+        self.settings.public && !self.started && self.players < self.settings.max_players
+    }
+
+    #[must_use]
+    pub const fn seats_left(&self) -> u8 {
+        self.settings.max_players.saturating_sub(self.players)
+    }
+}
+
+/// A change to the listed games
+#[derive(Debug, Clone)]
+pub enum LobbyUpdate {
+    /// A game was listed, or changed while listed
+    Open(String, Lobby),
+    /// A game was unlisted, because it filled, started or ended
+    Closed(String),
+}
+
+/// Get the codes and lobbies of the listed games
+pub fn open_lobbies() -> Vec<(String, Lobby)> {
+    let games = match GAMES.lock() {
+        Ok(games) => games,
+        Err(err) => err.into_inner(),
+    };
+
+    games
+        .iter()
+        .filter(|(_, game)| game.lobby.is_open())
+        .map(|(&token, game)| (token_to_code(token), game.lobby.clone()))
+        .collect()
+}
+
+/// Receive every change to the listed games
+pub fn subscribe() -> broadcast::Receiver<LobbyUpdate> {
+    LOBBY_UPDATES.subscribe()
+}
+
+/// Change the lobby of a game, and send the change if it is or was listed
+pub(super) fn update(token: u64, change: impl FnOnce(&mut Lobby)) {
+    let mut games = match GAMES.lock() {
+        Ok(games) => games,
+        Err(err) => err.into_inner(),
+    };
+
+    let Some(game) = games.get_mut(&token) else {
+        eprintln!(
+            "Error Updating Lobby: {} was not in the game list!",
+            token_to_code(token)
+        );
+        return;
+    };
+
+    let was_open = game.lobby.is_open();
+    change(&mut game.lobby);
+    let lobby = game.lobby.clone();
+    drop(games);
+
+    if lobby.is_open() {
+        send(LobbyUpdate::Open(token_to_code(token), lobby));
+    } else if was_open {
+        send(LobbyUpdate::Closed(token_to_code(token)));
+    }
+}
+
+/// Send a change to the listed games, which is only kept if someone is watching
+pub(super) fn send(update: LobbyUpdate) {
+    LOBBY_UPDATES.send(update).ok();
+}
@@ -0,0 +1,189 @@
+use chessehc::{clock::TimeControl, standard_pieces::BackRank};
+use lazy_static::lazy_static;
+use tokio::sync::mpsc;
+
+use crate::config::MATCHMAKING_RECEIVER_CAPACITY;
+
+use super::{create, GameMessage, PlayerMessage, Settings, Variant};
+
+lazy_static! {
+    /// The matchmaker is started the first time a player joins or leaves a queue
+    static ref MATCHMAKER: mpsc::Sender<QueueMessage> = {
+        let (tx, rx) = mpsc::channel(MATCHMAKING_RECEIVER_CAPACITY);
+        tokio::spawn(matchmaker(rx));
+        tx
+    };
+}
+
+/// The games a queued player can be matched into
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueueKey {
+    pub players: u8,
+    pub variant: Variant,
+    pub time_control: Option<TimeControl>,
+}
+
+impl QueueKey {
+    /// Get the settings of a matched game
+    fn settings(self) -> Settings {
+        Settings {
+            max_players: self.players,
+            variant: self.variant,
+            time_control: self.time_control,
+            ..Settings::default()
+        }
+    }
+}
+
+enum QueueMessage {
+    Join(i64, QueueKey, mpsc::Sender<GameMessage>),
+    Leave(i64),
+}
+
+struct Queued {
+    id: i64,
+    tp: mpsc::Sender<GameMessage>,
+}
+
+/// Add a player to a queue, replacing any queue they were already in
+pub async fn join(account_id: i64, key: QueueKey, tp: mpsc::Sender<GameMessage>) -> Result<(), ()> {
+    MATCHMAKER
+        .send(QueueMessage::Join(account_id, key, tp))
+        .await
+        .map_err(|_| ())
+}
+
+/// Remove a player from their queue, if they are in one
+pub async fn leave(account_id: i64) -> Result<(), ()> {
+    MATCHMAKER
+        .send(QueueMessage::Leave(account_id))
+        .await
+        .map_err(|_| ())
+}
+
+/// Group queued players in the order they joined, starting a game once a queue has enough players
+///
+/// Players are not grouped by rating, as accounts do not have one
+async fn matchmaker(mut receiver: mpsc::Receiver<QueueMessage>) {
+    let mut queues: Vec<(QueueKey, Vec<Queued>)> = Vec::new();
+
+    while let Some(msg) = receiver.recv().await {
+        match msg {
+            QueueMessage::Join(id, key, tp) => {
+                remove(&mut queues, id);
+
+                let index = queues.iter().position(|(queue_key, _)| *queue_key == key);
+                let index = index.unwrap_or_else(|| {
+                    queues.push((key, Vec::new()));
+                    queues.len() - 1
+                });
+                let queue = &mut queues[index].1;
+
+                // Forget players who disconnected while waiting
+                queue.retain(|queued| !queued.tp.is_closed());
+                queue.push(Queued { id, tp });
+
+                if queue.len() >= usize::from(key.players) {
+                    // Started separately, so slow players do not hold up the queues
+                    let players: Vec<Queued> = queue.drain(..usize::from(key.players)).collect();
+                    tokio::spawn(start_match(key, players));
+                }
+                send_status(key, queue);
+            }
+            QueueMessage::Leave(id) => remove(&mut queues, id),
+        }
+
+        queues.retain(|(_, queue)| !queue.is_empty());
+    }
+}
+
+/// Remove a player from every queue, updating the status of the players left
+fn remove(queues: &mut [(QueueKey, Vec<Queued>)], id: i64) {
+    for (key, queue) in queues {
+        let length = queue.len();
+        queue.retain(|queued| queued.id != id);
+
+        if queue.len() != length {
+            send_status(*key, queue);
+        }
+    }
+}
+
+/// Send the queue's length to the players in it, skipping players who are not keeping up
+fn send_status(key: QueueKey, queue: &[Queued]) {
+    let waiting = u8::try_from(queue.len()).expect("too many players in queue");
+
+    for queued in queue {
+        queued
+            .tp
+            .try_send(GameMessage::QueueStatus {
+                waiting,
+                players: key.players,
+            })
+            .ok();
+    }
+}
+
+/// Create and start a game for matched players, with the first to join as the host
+async fn start_match(key: QueueKey, players: Vec<Queued>) {
+    let Some((host, others)) = players.split_first() else { return };
+
+    let Ok((code, broadcast, game)) = create(host.id, host.tp.clone(), key.settings()) else {
+        eprintln!("Error Creating Matched Game!");
+        return;
+    };
+
+    if let Err(err) = host
+        .tp
+        .send(GameMessage::Matched {
+            code: code.clone(),
+            game: game.clone(),
+            broadcast: Some(broadcast),
+        })
+        .await
+    {
+        eprintln!("Error Sending Match: {err}");
+    }
+
+    for player in others {
+        // The game sends the player its broadcast when they join
+        if game
+            .send(PlayerMessage::Join(player.id, player.tp.clone()))
+            .await
+            .is_err()
+        {
+            eprintln!("Error Joining Matched Game: the game ended!");
+            return;
+        }
+
+        // Matched players do not need to ready up
+        if game
+            .send(PlayerMessage::Ready(player.id, true))
+            .await
+            .is_err()
+        {
+            eprintln!("Error Readying Matched Player: the game ended!");
+            return;
+        }
+
+        if let Err(err) = player
+            .tp
+            .send(GameMessage::Matched {
+                code: code.clone(),
+                game: game.clone(),
+                broadcast: None,
+            })
+            .await
+        {
+            eprintln!("Error Sending Match: {err}");
+        }
+    }
+
+    if game
+        .send(PlayerMessage::Start(host.id, BackRank::Standard))
+        .await
+        .is_err()
+    {
+        eprintln!("Error Starting Matched Game: the game ended!");
+    }
+}
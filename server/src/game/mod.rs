@@ -1,59 +1,461 @@
-use std::{collections::HashMap, sync::Mutex};
+use std::{collections::HashMap, sync::Mutex, time::Instant};
 
 use chessehc::{
-    delta::PartialDelta, piece_set::PieceSet, standard_pieces::StandardCompatiblePieceSet,
+    delta::PartialDelta,
+    engine::Setup,
+    error::Error,
+    piece_set::{PieceSet, NEUTRAL},
+    r#move::{partial_move_eq, Move, PartialMove},
+    standard_pieces::{BackRank, StandardCompatiblePieceSet},
+    tactics::Tactic,
 };
 use lazy_static::lazy_static;
 use nohash_hasher::{BuildNoHashHasher, IntMap};
-use tokio::sync::{broadcast, mpsc};
+use tokio::{
+    sync::{broadcast, mpsc},
+    time::sleep_until,
+};
 
 use crate::{
     config::{
-        GAME_BROADCAST_CAPACITY, GAME_MAX_CODE_SEARCH_TRIES, GAME_RECEIVER_CAPACITY, PLAYER_LIMIT,
+        GAME_BROADCAST_CAPACITY, GAME_MAX_CODE_SEARCH_TRIES, GAME_RECEIVER_CAPACITY,
+        GAME_SENDER_CAPACITY,
     },
-    response::err::inval_req,
+    response::err::{in_game, inval_req},
 };
 
-use self::code::{next_token, token_to_code};
+use self::{
+    bot::Strength,
+    chat::Chat,
+    code::{next_token, token_to_code},
+    record::Record,
+};
 
+pub mod bot;
+mod chat;
 mod code;
+mod lobby;
+pub mod matchmaking;
+mod record;
+mod settings;
+pub use chat::ChatMessage;
 pub use code::*;
+pub use lobby::{open_lobbies, subscribe, Lobby, LobbyUpdate};
+pub use settings::*;
+
+/// A running game, and how it is shown in the lobby browser
+struct Registered {
+    sender: mpsc::Sender<PlayerMessage>,
+    lobby: Lobby,
+}
 
 lazy_static! {
-    static ref GAMES: Mutex<IntMap<u64, mpsc::Sender<PlayerMessage>>> =
+    static ref GAMES: Mutex<IntMap<u64, Registered>> =
         Mutex::new(HashMap::with_hasher(BuildNoHashHasher::default()));
 }
 
 pub type PartialDeltas = Vec<PartialDelta<<StandardCompatiblePieceSet as PieceSet>::PieceId>>;
+pub type BoardView = Vec<(u8, <StandardCompatiblePieceSet as PieceSet>::PieceId)>;
+
+/// A spot hidden from the player by fog of war
+const HIDDEN_SPOT: (u8, <StandardCompatiblePieceSet as PieceSet>::PieceId) = (NEUTRAL, u8::MAX);
 
+type Players = Vec<(i64, Option<mpsc::Sender<GameMessage>>)>;
+/// Messages for players, sent once the game has been updated
+type Messages = Vec<(mpsc::Sender<GameMessage>, GameMessage)>;
+
+/// Messages for every player and spectator, which may not be received by anyone once players leave
 #[derive(Debug, Clone)]
 pub enum Broadcast {
     Join(i64),
-    Leave(i64, Option<PartialDeltas>),
-    Start {
-        players: Vec<i64>,
-        board: Vec<(u8, <StandardCompatiblePieceSet as PieceSet>::PieceId)>,
-    },
-    Turn(i64),
+    Leave(i64),
+    Host(i64),
+    Ready(i64, bool),
+    /// A player removed by the host, and whether they were banned
+    Kick(i64, bool),
+    /// The player whose turn it is, and the milliseconds each player has left in timed games
+    Turn(i64, Vec<u32>),
+    /// Every change made by a move, only sent on to spectators, as players may not see them all
     Move {
         player: i64,
         deltas: PartialDeltas,
         points: u16,
     },
+    Spectators(u16),
+    Chat(ChatMessage),
+    End(Vec<u16>),
 }
 
 pub enum PlayerMessage {
     Join(i64, mpsc::Sender<GameMessage>),
     Leave(i64),
-    Start(i64),
+    Start(i64, BackRank),
+    Kick {
+        host: i64,
+        player: i64,
+        ban: bool,
+    },
+    TransferHost(i64, i64),
+    Ready(i64, bool),
+    /// A bot added by the host to an empty seat
+    AddBot {
+        host: i64,
+        strength: Strength,
+    },
+    Move(i64, PartialMove),
+    Hints(i64),
+    Spectate(mpsc::Sender<GameMessage>),
+    StopSpectating(mpsc::Sender<GameMessage>),
+    Chat {
+        player: i64,
+        text: String,
+        tp: mpsc::Sender<GameMessage>,
+    },
 }
 
+/// Messages for a single player, as each player may see a different board
 #[allow(clippy::module_name_repetitions)]
 pub enum GameMessage {
-    Join(broadcast::Receiver<Broadcast>),
+    /// The game's broadcast and state for a new player, with the players already in it
+    Join {
+        broadcast: broadcast::Receiver<Broadcast>,
+        settings: Settings,
+        players: Vec<i64>,
+        host: i64,
+        ready: Vec<i64>,
+        chat: Vec<ChatMessage>,
+    },
+    /// The state of the game for a new spectator, with the board and turn once it has started
+    Spectate {
+        broadcast: broadcast::Receiver<Broadcast>,
+        settings: Settings,
+        players: Vec<i64>,
+        host: i64,
+        ready: Vec<i64>,
+        board: Option<BoardView>,
+        turn: Option<(i64, Vec<u32>)>,
+        spectators: u16,
+        chat: Vec<ChatMessage>,
+    },
+    SpectatingDisabled,
+    /// A queued player was matched into a game, with the broadcast if they are the host
+    Matched {
+        code: String,
+        game: mpsc::Sender<PlayerMessage>,
+        broadcast: Option<broadcast::Receiver<Broadcast>>,
+    },
+    QueueStatus {
+        waiting: u8,
+        players: u8,
+    },
     JoinRejection(inval_req::game::Game),
+    Banned,
     NotGameHost,
+    UnknownPlayer,
+    AlreadyStarted,
+    Full,
     TooFewPlayers,
+    NotReady,
+    Start {
+        players: Vec<i64>,
+        board: BoardView,
+    },
+    Move {
+        player: i64,
+        deltas: PartialDeltas,
+        points: u16,
+        board: Option<BoardView>,
+    },
+    MoveRejection(in_game::InGame),
+    /// The tactics a player can see, when they ask for hints
+    Hints(Vec<Tactic>),
+    ChatRateLimited,
+    /// The game as a bot sees it, whenever it is the bot's turn
+    Position(Box<chessehc::game::Game<StandardCompatiblePieceSet>>),
+}
+
+type GameError = Error<StandardCompatiblePieceSet>;
+
+/// Get the board as seen by a player
+fn view(game: &chessehc::game::Game<StandardCompatiblePieceSet>, player: u8) -> Result<BoardView, GameError> {
+    let (_, _, board) = game.export_for(player)?;
+    Ok(board
+        .into_iter()
+        .map(|spot| spot.unwrap_or(HIDDEN_SPOT))
+        .collect())
+}
+
+/// Get the tactics a player can find on their view of the board
+fn hints(game: &chessehc::game::Game<StandardCompatiblePieceSet>, player: u8) -> Result<Vec<Tactic>, GameError> {
+    game.view_for(player)?.board().tactics(player)
+}
+
+/// Get the spots seen by each player
+fn visible_spots(
+    game: &chessehc::game::Game<StandardCompatiblePieceSet>,
+    n_players: u8,
+) -> Result<Vec<Vec<bool>>, GameError> {
+    (0..n_players)
+        .map(|player| game.visible_spots(player))
+        .collect()
+}
+
+/// Get the milliseconds each player has left, or nothing if the game is not timed
+fn clocks(game: &chessehc::game::Game<StandardCompatiblePieceSet>) -> Vec<u32> {
+    let now = Instant::now();
+    (0..game.n_players())
+        .filter_map(|player| game.remaining_time(player, now))
+        .map(|remaining| u32::try_from(remaining.as_millis()).unwrap_or(u32::MAX))
+        .collect()
+}
+
+/// Get when the current player will run out of time, if the game is timed and not over
+fn turn_deadline(game: &chessehc::game::Game<StandardCompatiblePieceSet>) -> Option<tokio::time::Instant> {
+    if game.is_over() {
+        return None;
+    }
+
+    let now = Instant::now();
+    game.remaining_time(game.current_player(), now)
+        .map(|remaining| tokio::time::Instant::from_std(now + remaining))
+}
+
+/// Get what each player still in the game can see of the changes to the board,
+/// and broadcast every change to spectators
+fn move_messages(
+    game: &chessehc::game::Game<StandardCompatiblePieceSet>,
+    players: &Players,
+    tb: &broadcast::Sender<Broadcast>,
+    player_id: i64,
+    deltas: &PartialDeltas,
+    points: u16,
+    visible_before: &[Vec<bool>],
+) -> Result<Messages, GameError> {
+    let number_of_players = u8::try_from(players.len()).expect("too many players in game");
+    let visible_after = visible_spots(game, number_of_players)?;
+
+    tb.send(Broadcast::Move {
+        player: player_id,
+        deltas: deltas.clone(),
+        points,
+    })
+    .ok();
+
+    let mut messages = Vec::new();
+    for (player, (_, tp)) in players.iter().enumerate() {
+        let Some(tp) = tp else { continue };
+
+        let player_deltas = game.board().filter_deltas(
+            deltas.clone(),
+            &visible_before[player],
+            &visible_after[player],
+        );
+        let board = if game.is_fog_of_war() {
+            Some(view(game, u8::try_from(player).expect("too many players in game"))?)
+        } else {
+            None
+        };
+
+        messages.push((
+            tp.clone(),
+            GameMessage::Move {
+                player: player_id,
+                deltas: player_deltas,
+                points,
+                board,
+            },
+        ));
+    }
+
+    Ok(messages)
+}
+
+/// Send the moves, and any rejections, to the players
+async fn send_moves(messages: Messages) {
+    for (tp, message) in messages {
+        if let Err(err) = tp.send(message).await {
+            eprintln!("Error Sending Move: {err}");
+        }
+    }
+}
+
+/// Broadcast the end of the game, or whose turn it is next,
+/// sending the game as they see it to the current player if they are a bot
+fn send_turn(
+    game: &chessehc::game::Game<StandardCompatiblePieceSet>,
+    players: &Players,
+    bots: &[i64],
+    tb: &broadcast::Sender<Broadcast>,
+) {
+    if game.is_over() {
+        tb.send(Broadcast::End(game.scores())).ok();
+        return;
+    }
+
+    let (player_id, tp) = &players[usize::from(game.current_player())];
+    tb.send(Broadcast::Turn(*player_id, clocks(game))).ok();
+
+    // Bots only see what a player would under fog of war
+    if let Some(tp) = tp.as_ref().filter(|_| bots.contains(player_id)) {
+        match game.view_for(game.current_player()) {
+            Ok(view) => {
+                if let Err(err) = tp.try_send(GameMessage::Position(Box::new(view))) {
+                    eprintln!("Error Sending Position To Bot: {err}");
+                }
+            }
+            Err(err) => eprintln!("Error Getting Bot's View: {err}"),
+        }
+    }
+}
+
+/// Remove the current player if they have run out of time,
+/// getting the removal to send each player as a move by them with no points
+fn time_out(
+    game: &mut chessehc::game::Game<StandardCompatiblePieceSet>,
+    record: &mut Record,
+    players: &Players,
+    tb: &broadcast::Sender<Broadcast>,
+) -> Result<Option<Messages>, GameError> {
+    let now = Instant::now();
+    let player = game.current_player();
+    if game.is_over() || !game.clock().is_some_and(|clock| clock.is_flagged(player, now)) {
+        return Ok(None);
+    }
+    record.interrupt();
+
+    let number_of_players = u8::try_from(players.len()).expect("too many players in game");
+    let visible_before = visible_spots(game, number_of_players)?;
+    let deltas = game.check_timeout(now)?;
+
+    move_messages(
+        game,
+        players,
+        tb,
+        players[usize::from(player)].0,
+        &deltas,
+        0,
+        &visible_before,
+    )
+    .map(Some)
+}
+
+/// End a game which cannot go on after an error, so its players are not left waiting
+fn end_on_error(
+    game: &chessehc::game::Game<StandardCompatiblePieceSet>,
+    tb: &broadcast::Sender<Broadcast>,
+    err: &GameError,
+) {
+    eprintln!("Error In Game: {err}");
+    tb.send(Broadcast::End(game.scores())).ok();
+}
+
+/// Make a player's move, unless it is not their turn or not valid, broadcasting the next turn,
+/// and get the changes or the rejection to send to the players
+///
+/// Errors from the game are returned, as the game cannot go on after them
+#[allow(clippy::too_many_arguments)]
+fn play_move(
+    game: &mut chessehc::game::Game<StandardCompatiblePieceSet>,
+    record: &mut Record,
+    players: &Players,
+    bots: &[i64],
+    tb: &broadcast::Sender<Broadcast>,
+    tp: &mpsc::Sender<GameMessage>,
+    player_id: i64,
+    r#move: Move,
+) -> Result<Messages, GameError> {
+    // The last player in play still has moves once the game has ended
+    if game.is_over() {
+        return Ok(vec![(tp.clone(), GameMessage::MoveRejection(in_game::InGame::GameOver))]);
+    }
+
+    // A move made after running out of time is too late
+    let mut messages = Vec::new();
+    if let Some(time_out_messages) = time_out(game, record, players, tb)? {
+        messages = time_out_messages;
+        send_turn(game, players, bots, tb);
+    }
+
+    if game.current_player() != r#move.player {
+        messages.push((tp.clone(), GameMessage::MoveRejection(in_game::InGame::NotTurn)));
+        return Ok(messages);
+    }
+
+    if !game
+        .valid_moves()
+        .iter()
+        .any(|valid_move| partial_move_eq(valid_move, &(r#move.from, r#move.to, r#move.data)))
+    {
+        messages.push((tp.clone(), GameMessage::MoveRejection(in_game::InGame::InvalidMove)));
+        return Ok(messages);
+    }
+
+    let number_of_players = u8::try_from(players.len()).expect("too many players in game");
+    let visible_before = visible_spots(game, number_of_players)?;
+    let score_before = game.scores()[usize::from(r#move.player)];
+
+    let mut deltas = match game.make_move(&r#move) {
+        Ok(deltas) => deltas,
+        // The player ran out of time since it was checked
+        Err(Error::OutOfTime(_)) => {
+            if let Some(time_out_messages) = time_out(game, record, players, tb)? {
+                messages.extend(time_out_messages);
+                send_turn(game, players, bots, tb);
+            }
+            messages.push((tp.clone(), GameMessage::MoveRejection(in_game::InGame::NotTurn)));
+            return Ok(messages);
+        }
+        Err(err) => return Err(err),
+    };
+    record.push((r#move.from, r#move.to, r#move.data));
+    let points = game.scores()[usize::from(r#move.player)] - score_before;
+    deltas.extend(game.start_turn()?);
+
+    messages.extend(move_messages(game, players, tb, player_id, &deltas, points, &visible_before)?);
+    send_turn(game, players, bots, tb);
+
+    Ok(messages)
+}
+
+/// Remove the pieces of a player who left a started game, broadcasting the next turn if needed,
+/// and get the removal to send to the players
+///
+/// Errors from the game are returned, as the game cannot go on after them
+fn remove_player(
+    game: &mut chessehc::game::Game<StandardCompatiblePieceSet>,
+    record: &mut Record,
+    players: &Players,
+    bots: &[i64],
+    tb: &broadcast::Sender<Broadcast>,
+    player_id: i64,
+    index: u8,
+) -> Result<Messages, GameError> {
+    // Players out of play have no pieces to remove
+    if game.is_over() || !game.is_in_play(index) {
+        return Ok(Vec::new());
+    }
+
+    let number_of_players = u8::try_from(players.len()).expect("too many players in game");
+    let was_turn = game.current_player() == index;
+    let visible_before = visible_spots(game, number_of_players)?;
+    let deltas = game.remove_player(index)?;
+    record.interrupt();
+
+    // The removal is sent as a move by the player with no points, as for time outs
+    let messages = move_messages(game, players, tb, player_id, &deltas, 0, &visible_before)?;
+    if was_turn || game.is_over() {
+        send_turn(game, players, bots, tb);
+    }
+
+    Ok(messages)
+}
+
+/// Get the sender of a player still in the game
+fn sender(players: &Players, player: i64) -> Option<mpsc::Sender<GameMessage>> {
+    players
+        .iter()
+        .find(|(id, _)| *id == player)
+        .and_then(|(_, tp)| tp.clone())
 }
 
 type NewGame = (
@@ -62,17 +464,51 @@ type NewGame = (
     mpsc::Sender<PlayerMessage>,
 );
 
+#[allow(clippy::too_many_lines)]
 async fn game_handler(
     token: u64,
     tb: broadcast::Sender<Broadcast>,
     mut receiver: mpsc::Receiver<PlayerMessage>,
-    host_id: i64,
+    mut host_id: i64,
     host_sender: mpsc::Sender<GameMessage>,
+    settings: Settings,
 ) {
-    let mut players = vec![(host_id, Some(host_sender))];
+    let mut players: Players = vec![(host_id, Some(host_sender))];
+    let mut spectators: Vec<mpsc::Sender<GameMessage>> = Vec::new();
     let mut game: Option<chessehc::game::Game<StandardCompatiblePieceSet>> = None;
+    // Set with the game when it starts, and saved when it ends
+    let mut record: Option<Record> = None;
+    let mut chat = Chat::default();
+    // Players other than the host who are ready to start, and players who cannot join
+    let mut ready: Vec<i64> = Vec::new();
+    let mut banned: Vec<i64> = Vec::new();
+    // Players who are bots, which leave with the last player
+    let mut bots: Vec<i64> = Vec::new();
+
+    loop {
+        // Wake up when the current player runs out of time, as well as for messages
+        let deadline = game.as_ref().and_then(turn_deadline);
+        let msg = tokio::select! {
+            msg = receiver.recv() => match msg {
+                Some(msg) => msg,
+                None => break,
+            },
+            () = sleep_until(deadline.unwrap_or_else(tokio::time::Instant::now)), if deadline.is_some() => {
+                let (Some(game), Some(record)) = (game.as_mut(), record.as_mut()) else { continue };
+                let messages = match time_out(game, record, &players, &tb) {
+                    Ok(Some(messages)) => messages,
+                    Ok(None) => continue,
+                    Err(err) => {
+                        end_on_error(game, &tb, &err);
+                        break;
+                    }
+                };
+                send_moves(messages).await;
+                send_turn(game, &players, &bots, &tb);
+                continue;
+            }
+        };
 
-    while let Some(msg) = receiver.recv().await {
         match msg {
             PlayerMessage::Join(player_id, tp) => {
                 if game.is_some() {
@@ -82,7 +518,12 @@ async fn game_handler(
                     continue;
                 }
 
-                if players.len() >= PLAYER_LIMIT.into() {
+                if banned.contains(&player_id) {
+                    tp.send(GameMessage::Banned).await.ok();
+                    continue;
+                }
+
+                if players.len() >= settings.max_players.into() {
                     tp.send(GameMessage::JoinRejection(inval_req::game::Game::Full))
                         .await
                         .ok();
@@ -96,39 +537,151 @@ async fn game_handler(
                     continue;
                 }
 
-                let rb = tb.subscribe();
-                if let Err(err) = tp.send(GameMessage::Join(rb)).await {
+                let joined = GameMessage::Join {
+                    broadcast: tb.subscribe(),
+                    settings,
+                    players: players.iter().map(|(id, _)| *id).collect(),
+                    host: host_id,
+                    ready: ready.clone(),
+                    chat: chat.history(false),
+                };
+                if let Err(err) = tp.send(joined).await {
                     eprintln!("Error Sending Join Confirmation: {err}");
                     continue;
                 };
 
                 players.push((player_id, Some(tp)));
-                tb.send(Broadcast::Join(player_id))
-                    .expect("error sending broadcast");
+                tb.send(Broadcast::Join(player_id)).ok();
+
+                let number_of_players = u8::try_from(players.len()).expect("too many players in game");
+                lobby::update(token, |lobby| lobby.players = number_of_players);
+            }
+            PlayerMessage::Spectate(tp) => {
+                if !settings.spectators {
+                    tp.send(GameMessage::SpectatingDisabled).await.ok();
+                    continue;
+                }
+
+                spectators.retain(|spectator| !spectator.is_closed());
+                spectators.push(tp.clone());
+                let count = u16::try_from(spectators.len()).unwrap_or(u16::MAX);
+
+                // Spectators see the whole board, even with fog of war
+                let snapshot = GameMessage::Spectate {
+                    broadcast: tb.subscribe(),
+                    settings,
+                    players: players.iter().map(|(id, _)| *id).collect(),
+                    host: host_id,
+                    ready: ready.clone(),
+                    board: game.as_ref().map(|game| game.board().export().2),
+                    turn: game.as_ref().filter(|game| !game.is_over()).map(|game| {
+                        (players[usize::from(game.current_player())].0, clocks(game))
+                    }),
+                    spectators: count,
+                    chat: chat.history(true),
+                };
+                if let Err(err) = tp.send(snapshot).await {
+                    eprintln!("Error Sending Spectate: {err}");
+                    continue;
+                }
+
+                tb.send(Broadcast::Spectators(count)).ok();
+            }
+            PlayerMessage::StopSpectating(tp) => {
+                spectators.retain(|spectator| !spectator.same_channel(&tp) && !spectator.is_closed());
+                let count = u16::try_from(spectators.len()).unwrap_or(u16::MAX);
+
+                tb.send(Broadcast::Spectators(count)).ok();
+            }
+            PlayerMessage::Chat { player, text, tp } => {
+                let spectator = if players
+                    .iter()
+                    .any(|(id, sender)| *id == player && sender.is_some())
+                {
+                    false
+                } else if spectators.iter().any(|spectator| spectator.same_channel(&tp)) {
+                    true
+                } else {
+                    eprintln!("Error Sending Chat: player {player} is not in the game!");
+                    continue;
+                };
+
+                // Spectators talk to the players unless they have their own channel
+                let Some(message) = chat.post(player, text, spectator && settings.spectator_chat) else {
+                    tp.send(GameMessage::ChatRateLimited).await.ok();
+                    continue;
+                };
+                tb.send(Broadcast::Chat(message)).ok();
             }
             PlayerMessage::Leave(player_id) => {
                 let Some(index) = players.iter().position(|(id, _)| id == &player_id) else {
                     eprintln!("Error Removing Player From Game: player {player_id} is not in the game!");
                     continue;
                 };
-                players[index].1 = None;
+
+                // Free the seat, as the game has not started
+                let (Some(game), Some(record)) = (game.as_mut(), record.as_mut()) else {
+                    players.remove(index);
+                    ready.retain(|id| *id != player_id);
+
+                    // The game ends with the last player, as bots cannot host
+                    let Some(&(next_host, _)) = players.iter().find(|(id, _)| !bots.contains(id)) else {
+                        break;
+                    };
+
+                    tb.send(Broadcast::Leave(player_id)).ok();
+                    if player_id == host_id {
+                        host_id = next_host;
+                        ready.retain(|id| *id != host_id);
+                        tb.send(Broadcast::Host(host_id)).ok();
+                    }
+
+                    let number_of_players =
+                        u8::try_from(players.len()).expect("too many players in game");
+                    lobby::update(token, |lobby| {
+                        lobby.players = number_of_players;
+                        lobby.host = host_id;
+                    });
+                    continue;
+                };
+
+                // The player keeps their seat, so the order of players does not change
+                if players[index].1.take().is_none() {
+                    eprintln!("got message from left player");
+                    continue;
+                }
+
+                // The game ends with the last player, as bots do not play on their own
+                if players
+                    .iter()
+                    .all(|(id, tp)| tp.is_none() || bots.contains(id))
+                {
+                    tb.send(Broadcast::Leave(player_id)).ok();
+                    // The end was already sent if the game is over
+                    if !game.is_over() {
+                        tb.send(Broadcast::End(game.scores())).ok();
+                    }
+                    break;
+                }
+
                 let index = u8::try_from(index).expect("too many players in game");
+                tb.send(Broadcast::Leave(player_id)).ok();
 
-                let deltas = game.as_mut().map(|game| {
-                    game.remove_player(index)
-                        .expect("failed to remove player from game")
-                });
-                tb.send(Broadcast::Leave(player_id, deltas))
-                    .expect("error sending broadcast");
+                let messages = match remove_player(game, record, &players, &bots, &tb, player_id, index) {
+                    Ok(messages) => messages,
+                    Err(err) => {
+                        end_on_error(game, &tb, &err);
+                        break;
+                    }
+                };
+                send_moves(messages).await;
             }
-            PlayerMessage::Start(id) => {
+            PlayerMessage::Start(id, back_rank) => {
                 let number_of_players =
                     u8::try_from(players.len()).expect("too many players in game");
 
-                let Some(tp) = players
-                    .iter_mut()
-                    .find(|(player_id, _)| *player_id == id)
-                    .expect("player not in game").1.as_mut() else {
+                // The player may have been kicked since sending the message
+                let Some(tp) = sender(&players, id) else {
                     eprintln!("got message from left player");
                     continue;
                 };
@@ -140,48 +693,314 @@ async fn game_handler(
                     continue;
                 }
 
+                if game.is_some() {
+                    if let Err(err) = tp.send(GameMessage::AlreadyStarted).await {
+                        eprintln!("Error Sending Error: {err}");
+                    }
+                    continue;
+                }
+
                 if number_of_players < 2 {
                     if let Err(err) = tp.send(GameMessage::TooFewPlayers).await {
                         eprintln!("Error Sending Error: {err}");
                     }
+                    continue;
+                }
+
+                if !players
+                    .iter()
+                    .all(|(id, _)| *id == host_id || ready.contains(id))
+                {
+                    if let Err(err) = tp.send(GameMessage::NotReady).await {
+                        eprintln!("Error Sending Error: {err}");
+                    }
+                    continue;
                 }
 
                 let player_ids: Vec<i64> = players.iter().map(|(id, _)| *id).collect();
 
-                let new_game = chessehc::game::Game::new(
-                    number_of_players,
-                    8,
-                    7 * u16::from(number_of_players),
-                );
-                let (_, _, board) = new_game.board().export();
+                let seed = rand::random();
+                let mut new_game = match settings.new_game(number_of_players, seed, back_rank) {
+                    Ok(new_game) => new_game,
+                    Err(err) => {
+                        eprintln!("Error Setting Up Game: {err}");
+                        tb.send(Broadcast::End(vec![0; usize::from(number_of_players)])).ok();
+                        break;
+                    }
+                };
+
+                let boards = match (0..number_of_players)
+                    .map(|index| view(&new_game, index))
+                    .collect::<Result<Vec<BoardView>, GameError>>()
+                {
+                    Ok(boards) => boards,
+                    Err(err) => {
+                        end_on_error(&new_game, &tb, &err);
+                        break;
+                    }
+                };
+
+                // Send each player their view of the board
+                for ((_, tp), board) in players.iter().zip(boards) {
+                    let Some(tp) = tp else { continue };
+
+                    if let Err(err) = tp
+                        .send(GameMessage::Start {
+                            players: player_ids.clone(),
+                            board,
+                        })
+                        .await
+                    {
+                        eprintln!("Error Sending Game Start: {err}");
+                    }
+                }
+
+                new_game.start_clock(Instant::now());
+                send_turn(&new_game, &players, &bots, &tb);
                 game = Some(new_game);
+                record = Some(Record::new(
+                    Setup::Start {
+                        players: number_of_players,
+                        width: settings.width,
+                        rows: settings.rows_per_player,
+                        back_rank,
+                        seed,
+                    },
+                    settings.variant,
+                    player_ids,
+                ));
+                lobby::update(token, |lobby| lobby.started = true);
+            }
+            PlayerMessage::Kick { host, player, ban } => {
+                let Some(tp) = sender(&players, host) else {
+                    eprintln!("got message from left player");
+                    continue;
+                };
 
-                tb.send(Broadcast::Start {
-                    players: player_ids,
-                    board,
-                })
-                .expect("error sending broadcast");
+                if host != host_id {
+                    if let Err(err) = tp.send(GameMessage::NotGameHost).await {
+                        eprintln!("Error Sending Error: {err}");
+                    }
+                    continue;
+                }
+
+                // Players cannot be removed from a started game, as their pieces are on the board
+                if game.is_some() {
+                    if let Err(err) = tp.send(GameMessage::AlreadyStarted).await {
+                        eprintln!("Error Sending Error: {err}");
+                    }
+                    continue;
+                }
+
+                let Some(index) = players
+                    .iter()
+                    .position(|(id, _)| *id == player && *id != host_id)
+                else {
+                    if let Err(err) = tp.send(GameMessage::UnknownPlayer).await {
+                        eprintln!("Error Sending Error: {err}");
+                    }
+                    continue;
+                };
+
+                players.remove(index);
+                ready.retain(|id| *id != player);
+                bots.retain(|id| *id != player);
+                if ban {
+                    banned.push(player);
+                }
+                tb.send(Broadcast::Kick(player, ban)).ok();
+
+                let number_of_players = u8::try_from(players.len()).expect("too many players in game");
+                lobby::update(token, |lobby| lobby.players = number_of_players);
+            }
+            PlayerMessage::TransferHost(host, player) => {
+                let Some(tp) = sender(&players, host) else {
+                    eprintln!("got message from left player");
+                    continue;
+                };
+
+                if host != host_id {
+                    if let Err(err) = tp.send(GameMessage::NotGameHost).await {
+                        eprintln!("Error Sending Error: {err}");
+                    }
+                    continue;
+                }
+
+                if player == host_id || bots.contains(&player) || sender(&players, player).is_none() {
+                    if let Err(err) = tp.send(GameMessage::UnknownPlayer).await {
+                        eprintln!("Error Sending Error: {err}");
+                    }
+                    continue;
+                }
+
+                host_id = player;
+                ready.retain(|id| *id != player);
+                tb.send(Broadcast::Host(host_id)).ok();
+                lobby::update(token, |lobby| lobby.host = host_id);
+            }
+            PlayerMessage::Ready(player, is_ready) => {
+                let Some(tp) = sender(&players, player) else {
+                    eprintln!("got message from left player");
+                    continue;
+                };
+
+                if game.is_some() {
+                    if let Err(err) = tp.send(GameMessage::AlreadyStarted).await {
+                        eprintln!("Error Sending Error: {err}");
+                    }
+                    continue;
+                }
+
+                ready.retain(|id| *id != player);
+                if is_ready {
+                    ready.push(player);
+                }
+                tb.send(Broadcast::Ready(player, is_ready)).ok();
+            }
+            PlayerMessage::AddBot { host, strength } => {
+                let Some(tp) = sender(&players, host) else {
+                    eprintln!("got message from left player");
+                    continue;
+                };
+
+                if host != host_id {
+                    if let Err(err) = tp.send(GameMessage::NotGameHost).await {
+                        eprintln!("Error Sending Error: {err}");
+                    }
+                    continue;
+                }
+
+                if game.is_some() {
+                    if let Err(err) = tp.send(GameMessage::AlreadyStarted).await {
+                        eprintln!("Error Sending Error: {err}");
+                    }
+                    continue;
+                }
+
+                if players.len() >= settings.max_players.into() {
+                    if let Err(err) = tp.send(GameMessage::Full).await {
+                        eprintln!("Error Sending Error: {err}");
+                    }
+                    continue;
+                }
+
+                // Each strength has an account for every seat, so one is always free
+                let bot_id = (0..settings.max_players)
+                    .map(|seat| strength.account_id(seat))
+                    .find(|bot_id| players.iter().all(|(id, _)| id != bot_id))
+                    .expect("no free bot account");
+
+                let Some(game_sender) = get(token) else {
+                    eprintln!("Error Adding Bot: {} was not in the game list!", token_to_code(token));
+                    continue;
+                };
+                let (bot_sender, bot_receiver) = mpsc::channel(GAME_SENDER_CAPACITY);
+                tokio::spawn(bot::bot(bot_id, strength, settings.variant, bot_receiver, game_sender));
+
+                players.push((bot_id, Some(bot_sender)));
+                bots.push(bot_id);
+                ready.push(bot_id);
+                tb.send(Broadcast::Join(bot_id)).ok();
+                tb.send(Broadcast::Ready(bot_id, true)).ok();
+
+                let number_of_players = u8::try_from(players.len()).expect("too many players in game");
+                lobby::update(token, |lobby| lobby.players = number_of_players);
+            }
+            PlayerMessage::Move(player_id, (from, to, data)) => {
+                let Some(index) = players.iter().position(|(id, _)| id == &player_id) else {
+                    eprintln!("Error Making Move: player {player_id} is not in the game!");
+                    continue;
+                };
+                let Some(tp) = players[index].1.as_ref() else {
+                    eprintln!("got message from left player");
+                    continue;
+                };
+                let index = u8::try_from(index).expect("too many players in game");
+
+                let (Some(game), Some(record)) = (game.as_mut(), record.as_mut()) else {
+                    if let Err(err) = tp.send(GameMessage::MoveRejection(in_game::InGame::NotStarted)).await {
+                        eprintln!("Error Sending Error: {err}");
+                    }
+                    continue;
+                };
+
+                let r#move = Move {
+                    from,
+                    to,
+                    data,
+                    player: index,
+                };
+                let messages = match play_move(game, record, &players, &bots, &tb, tp, player_id, r#move) {
+                    Ok(messages) => messages,
+                    Err(err) => {
+                        end_on_error(game, &tb, &err);
+                        break;
+                    }
+                };
+                send_moves(messages).await;
+            }
+            PlayerMessage::Hints(player_id) => {
+                let Some(index) = players.iter().position(|(id, _)| id == &player_id) else {
+                    eprintln!("Error Finding Hints: player {player_id} is not in the game!");
+                    continue;
+                };
+                let Some(tp) = players[index].1.as_ref() else {
+                    eprintln!("got message from left player");
+                    continue;
+                };
+                let index = u8::try_from(index).expect("too many players in game");
+
+                let Some(game) = game.as_ref() else {
+                    if let Err(err) = tp.send(GameMessage::MoveRejection(in_game::InGame::NotStarted)).await {
+                        eprintln!("Error Sending Error: {err}");
+                    }
+                    continue;
+                };
+
+                let tactics = match hints(game, index) {
+                    Ok(tactics) => tactics,
+                    Err(err) => {
+                        eprintln!("Error Finding Hints: {err}");
+                        continue;
+                    }
+                };
+                if let Err(err) = tp.send(GameMessage::Hints(tactics)).await {
+                    eprintln!("Error Sending Hints: {err}");
+                }
             }
         }
     }
 
+    if let (Some(game), Some(record)) = (&game, record) {
+        record::save(&record.finish(game, &chat));
+    }
+
     let mut games = match GAMES.lock() {
         Ok(game) => game,
         Err(err) => err.into_inner(),
     };
 
-    if games.remove(&token).is_none() {
-        eprintln!(
+    let removed = games.remove(&token);
+    games.shrink_to_fit();
+    drop(games);
+
+    match removed {
+        Some(Registered { lobby, .. }) if lobby.is_open() => {
+            lobby::send(LobbyUpdate::Closed(token_to_code(token)));
+        }
+        Some(_) => {}
+        None => eprintln!(
             "Error Removing Game: {} was not in the game list!",
             token_to_code(token)
-        );
+        ),
     }
-
-    games.shrink_to_fit();
-    drop(games);
 }
 
-pub fn create(account_id: i64, th: mpsc::Sender<GameMessage>) -> Result<NewGame, ()> {
+pub fn create(
+    account_id: i64,
+    th: mpsc::Sender<GameMessage>,
+    settings: Settings,
+) -> Result<NewGame, ()> {
     let mut games = match GAMES.lock() {
         Ok(games) => games,
         Err(err) => err.into_inner(),
@@ -202,8 +1021,20 @@ pub fn create(account_id: i64, th: mpsc::Sender<GameMessage>) -> Result<NewGame,
     let (tb, rb) = broadcast::channel(GAME_BROADCAST_CAPACITY);
     let (tx, rx) = mpsc::channel(GAME_RECEIVER_CAPACITY);
 
-    games.insert(token, tx.clone());
-    tokio::spawn(game_handler(token, tb, rx, account_id, th));
+    let lobby = Lobby::new(account_id, settings);
+    if lobby.is_open() {
+        lobby::send(LobbyUpdate::Open(token_to_code(token), lobby.clone()));
+    }
+    games.insert(
+        token,
+        Registered {
+            sender: tx.clone(),
+            lobby,
+        },
+    );
+    drop(games);
+
+    tokio::spawn(game_handler(token, tb, rx, account_id, th, settings));
 
     Ok((token_to_code(token), rb, tx))
 }
@@ -214,5 +1045,92 @@ pub fn get(token: u64) -> Option<mpsc::Sender<PlayerMessage>> {
         Err(err) => err.into_inner(),
     };
 
-    games.get(&token).cloned()
+    games.get(&token).map(|game| game.sender.clone())
+}
+
+#[cfg(test)]
+mod test {
+    use chessehc::{
+        coordinate::Coordinate,
+        engine::Setup,
+        game::Game,
+        r#move::Move,
+        standard_pieces::{BackRank, King, Knight, Rook},
+        tactics::Tactic,
+    };
+    use tokio::sync::{broadcast, mpsc};
+
+    use crate::response::err::in_game::InGame;
+
+    use super::{hints, play_move, GameMessage, Record, Variant};
+
+    #[test]
+    fn test_move_after_end() {
+        let mut game = Game::new(2, 8, 16);
+        game.add_pieces([
+            (King::new(0), Coordinate(4, 0)),
+            (King::new(1), Coordinate(4, 8)),
+        ])
+        .expect("failed to add kings to board");
+        game.set_in_play(1, false).expect("failed to set in play");
+        assert!(game.is_over(), "game did not end");
+
+        let (tb, mut rb) = broadcast::channel(1);
+        let (tp, _rp) = mpsc::channel(1);
+        let players = vec![(1, Some(tp.clone())), (2, None)];
+        let r#move = Move {
+            from: Coordinate(4, 0),
+            to: Coordinate(4, 1),
+            data: 0,
+            player: 0,
+        };
+
+        let setup = Setup::Start {
+            players: 2,
+            width: 8,
+            rows: 8,
+            back_rank: BackRank::Standard,
+            seed: 0,
+        };
+        let mut record = Record::new(setup, Variant::Standard, vec![1, 2]);
+        let messages = play_move(&mut game, &mut record, &players, &[], &tb, &tp, 1, r#move).expect("failed to play move");
+        assert!(
+            matches!(
+                messages.as_slice(),
+                [(_, GameMessage::MoveRejection(InGame::GameOver))]
+            ),
+            "move after the end of the game was not rejected"
+        );
+        assert!(rb.try_recv().is_err(), "move after the end of the game was broadcast");
+    }
+
+    #[test]
+    fn test_hints_fog_of_war() {
+        let mut game = Game::new(2, 8, 16);
+        game.add_pieces([
+            (King::new(0), Coordinate(4, 0)),
+            (Rook::new(0), Coordinate(0, 4)),
+            (Knight::new(1), Coordinate(3, 4)),
+            (King::new(1), Coordinate(6, 4)),
+        ])
+        .expect("failed to add pieces to board");
+
+        let tactics = hints(&game, 0).expect("failed to find hints");
+        assert!(
+            tactics.iter().any(|tactic| matches!(tactic, Tactic::Pin(_))),
+            "pin was not found"
+        );
+
+        // The king behind the knight is hidden
+        game.set_fog_of_war(true);
+        let tactics = hints(&game, 0).expect("failed to find hints");
+        assert!(
+            tactics.iter().any(|tactic| matches!(tactic, Tactic::Hanging { .. })),
+            "hanging knight was not found"
+        );
+        assert!(
+            !tactics.iter().any(|tactic| matches!(tactic, Tactic::Pin(_))),
+            "pin on a hidden king was found"
+        );
+    }
 }
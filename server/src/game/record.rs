@@ -0,0 +1,183 @@
+use std::{env, fs::OpenOptions, io::Write};
+
+use chessehc::{
+    engine::{record::GameRecord, Position, Setup},
+    game::Game,
+    r#move::PartialMove,
+    standard_pieces::StandardCompatiblePieceSet,
+};
+
+use crate::config::RECORD_PATH_ENV_VARIABLE;
+
+use super::{chat::Chat, Variant};
+
+/// The record of a started game, with the moves made so far
+pub(super) struct Record {
+    position: Position,
+    variant: Variant,
+    players: Vec<i64>,
+    /// Whether the moves replay the whole game, which they stop doing once a player is removed
+    complete: bool,
+}
+
+impl Record {
+    pub const fn new(setup: Setup, variant: Variant, players: Vec<i64>) -> Self {
+        Self {
+            position: Position {
+                setup,
+                moves: Vec::new(),
+            },
+            variant,
+            players,
+            // Records are replayed with the standard pieces, without neutral pieces
+            complete: !matches!(variant, Variant::Atomic | Variant::Extinction | Variant::Duck),
+        }
+    }
+
+    /// Add a move, unless the moves are already incomplete
+    pub fn push(&mut self, r#move: PartialMove) {
+        if self.complete {
+            self.position.moves.push(r#move);
+        }
+    }
+
+    /// Stop adding moves, as a player leaving or running out of time cannot be replayed
+    pub const fn interrupt(&mut self) {
+        self.complete = false;
+    }
+
+    /// Get the game record, with the chat of both channels
+    pub fn finish(self, game: &Game<StandardCompatiblePieceSet>, chat: &Chat) -> GameRecord {
+        let mut tags = vec![
+            ("Event".to_owned(), "server".to_owned()),
+            ("Variant".to_owned(), self.variant.id().to_string()),
+        ];
+        for (seat, id) in self.players.iter().enumerate() {
+            tags.push((format!("Seat{seat}"), id.to_string()));
+        }
+
+        // The players still in play at the end share the point
+        let in_play: Vec<bool> = (0..game.n_players())
+            .map(|seat| game.is_in_play(seat))
+            .collect();
+        let n_in_play = in_play.iter().filter(|&&is_in_play| is_in_play).count().max(1);
+        let share = 1. / f64::from(u32::try_from(n_in_play).unwrap_or(u32::MAX));
+        let points: Vec<f64> = in_play
+            .iter()
+            .map(|&is_in_play| if is_in_play { share } else { 0. })
+            .collect();
+        tags.push((
+            "Result".to_owned(),
+            points.iter().map(f64::to_string).collect::<Vec<_>>().join(" "),
+        ));
+        tags.push((
+            "Termination".to_owned(),
+            if game.is_over() { "won" } else { "abandoned" }.to_owned(),
+        ));
+        if !self.complete {
+            tags.push(("Moves".to_owned(), "incomplete".to_owned()));
+        }
+
+        // A tag is one line, so line breaks in messages become spaces
+        for message in chat.history(true) {
+            tags.push((
+                "Chat".to_owned(),
+                format!(
+                    "{} {} {} {}",
+                    message.player,
+                    message.timestamp,
+                    u8::from(message.spectators),
+                    message.text.replace(['\r', '\n'], " ")
+                ),
+            ));
+        }
+
+        GameRecord {
+            tags,
+            position: self.position,
+        }
+    }
+}
+
+/// Append a game record to the file at the path in the environment variable, if it is set
+pub(super) fn save(record: &GameRecord) {
+    let Ok(path) = env::var(RECORD_PATH_ENV_VARIABLE.0) else { return };
+
+    let result = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .and_then(|mut file| file.write_all(format!("{record}\n").as_bytes()));
+    if let Err(err) = result {
+        eprintln!("Error Saving Game Record To {path}: {err}");
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chessehc::{
+        coordinate::Coordinate,
+        engine::{record::GameRecord, Position, Setup},
+        r#move::Move,
+        standard_pieces::BackRank,
+    };
+
+    use super::{super::chat::Chat, Record, Variant};
+
+    #[test]
+    fn test_record() {
+        let setup = Setup::Start {
+            players: 2,
+            width: 8,
+            rows: 8,
+            back_rank: BackRank::Standard,
+            seed: 0,
+        };
+        let mut record = Record::new(setup.clone(), Variant::Standard, vec![3, 4]);
+        let mut chat = Chat::default();
+        chat.post(3, "good\nluck".to_owned(), false);
+        chat.post(5, "watching".to_owned(), true);
+
+        let r#move = (Coordinate(4, 1), Coordinate(4, 3), 0);
+        let mut game = Position {
+            setup,
+            moves: Vec::new(),
+        }
+        .game()
+        .expect("failed to create game");
+        game.make_move(&Move {
+            from: r#move.0,
+            to: r#move.1,
+            data: r#move.2,
+            player: 0,
+        })
+        .expect("failed to make move");
+        record.push(r#move);
+        record.interrupt();
+        record.push((Coordinate(4, 9), Coordinate(4, 11), 0));
+
+        let text = record.finish(&game, &chat).to_string();
+        let parsed: GameRecord = text.parse().expect("failed to parse record");
+
+        assert_eq!(parsed.position.moves, vec![r#move], "moves after the interruption were recorded");
+        assert_eq!(parsed.tag("Seat1"), Some("4"), "wrong seat");
+        assert_eq!(parsed.tag("Moves"), Some("incomplete"), "interruption not tagged");
+        let messages: Vec<&str> = parsed
+            .tags
+            .iter()
+            .filter(|(key, _)| key == "Chat")
+            .map(|(_, value)| value.as_str())
+            .collect();
+        assert_eq!(messages.len(), 2, "chat not stored with both channels: {text}");
+        assert!(
+            messages[0].starts_with("3 ") && messages[0].ends_with(" 0 good luck"),
+            "wrong message: {}",
+            messages[0]
+        );
+        assert!(
+            messages[1].ends_with(" 1 watching"),
+            "wrong spectator message: {}",
+            messages[1]
+        );
+    }
+}
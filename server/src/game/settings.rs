@@ -0,0 +1,331 @@
+use std::time::Duration;
+
+use chessehc::{
+    clock::TimeControl,
+    condition::{Extinction, KingOfTheHill, ThreeCheck},
+    coordinate::Coordinate,
+    error::Error,
+    game::Game,
+    piece_set::PieceSet,
+    standard_pieces::{
+        place_neutral, setup, Atomic, BackRank, King, StandardCompatiblePieceSet, KING_ID,
+    },
+};
+
+use crate::config::{
+    BOARD_MAX_WIDTH, BOARD_MIN_WIDTH, MAX_ROWS_PER_PLAYER, MIN_ROWS_PER_PLAYER, PLAYER_LIMIT,
+};
+
+const PUBLIC_FLAG: u8 = 0b01;
+const NO_SPECTATORS_FLAG: u8 = 0b10;
+const SPECTATOR_CHAT_FLAG: u8 = 0b100;
+
+/// The rules a game is played with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Variant {
+    Standard,
+    Atomic,
+    ThreeCheck,
+    KingOfTheHill,
+    Extinction,
+    Duck,
+    FogOfWar,
+}
+
+impl Variant {
+    #[must_use]
+    pub const fn from_id(id: u8) -> Option<Self> {
+        Some(match id {
+            0 => Self::Standard,
+            1 => Self::Atomic,
+            2 => Self::ThreeCheck,
+            3 => Self::KingOfTheHill,
+            4 => Self::Extinction,
+            5 => Self::Duck,
+            6 => Self::FogOfWar,
+            _ => return None,
+        })
+    }
+
+    #[must_use]
+    pub const fn id(self) -> u8 {
+        match self {
+            Self::Standard => 0,
+            Self::Atomic => 1,
+            Self::ThreeCheck => 2,
+            Self::KingOfTheHill => 3,
+            Self::Extinction => 4,
+            Self::Duck => 5,
+            Self::FogOfWar => 6,
+        }
+    }
+}
+
+/// The settings a game is created with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Settings {
+    pub width: u16,
+    pub rows_per_player: u16,
+    pub max_players: u8,
+    pub variant: Variant,
+    pub time_control: Option<TimeControl>,
+    pub public: bool,
+    /// Whether clients can watch the game without playing, which can only be disabled in private games
+    pub spectators: bool,
+    /// Whether spectators chat in their own channel, rather than with the players
+    pub spectator_chat: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            width: 8,
+            rows_per_player: 7,
+            max_players: PLAYER_LIMIT,
+            variant: Variant::Standard,
+            time_control: None,
+            public: false,
+            spectators: true,
+            spectator_chat: false,
+        }
+    }
+}
+
+impl Settings {
+    /// Parse and validate settings, using the defaults if there are none
+    ///
+    /// Width (u16), rows per player (u16), maximum players (u8), variant (u8), flags (u8),
+    /// then an optional time control
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        if data.is_empty() {
+            return Some(Self::default());
+        }
+
+        let flags = *data.get(6)?;
+        if flags & !(PUBLIC_FLAG | NO_SPECTATORS_FLAG | SPECTATOR_CHAT_FLAG) != 0 {
+            return None;
+        }
+
+        let settings = Self {
+            width: u16::from_be_bytes([*data.first()?, *data.get(1)?]),
+            rows_per_player: u16::from_be_bytes([*data.get(2)?, *data.get(3)?]),
+            max_players: *data.get(4)?,
+            variant: Variant::from_id(*data.get(5)?)?,
+            public: flags & PUBLIC_FLAG != 0,
+            spectators: flags & NO_SPECTATORS_FLAG == 0,
+            spectator_chat: flags & SPECTATOR_CHAT_FLAG != 0,
+            time_control: parse_time_control(&data[7..]).ok()?,
+        };
+
+        settings.is_valid().then_some(settings)
+    }
+
+    fn is_valid(&self) -> bool {
+        (BOARD_MIN_WIDTH..=BOARD_MAX_WIDTH).contains(&self.width)
+            && (MIN_ROWS_PER_PLAYER..=MAX_ROWS_PER_PLAYER).contains(&self.rows_per_player)
+            && (2..=PLAYER_LIMIT).contains(&self.max_players)
+            && (self.spectators || !self.public)
+    }
+
+    /// Write the settings in the same format they are parsed from
+    pub fn write(&self, buffer: &mut Vec<u8>) {
+        buffer.extend_from_slice(&self.width.to_be_bytes());
+        buffer.extend_from_slice(&self.rows_per_player.to_be_bytes());
+        let mut flags = 0;
+        if self.public {
+            flags |= PUBLIC_FLAG;
+        }
+        if !self.spectators {
+            flags |= NO_SPECTATORS_FLAG;
+        }
+        if self.spectator_chat {
+            flags |= SPECTATOR_CHAT_FLAG;
+        }
+        buffer.extend_from_slice(&[self.max_players, self.variant.id(), flags]);
+        self.write_time_control(buffer);
+    }
+
+    /// Write the time control, if there is one
+    pub fn write_time_control(&self, buffer: &mut Vec<u8>) {
+        let Some(time_control) = self.time_control else { return };
+        let (id, times) = match time_control {
+            TimeControl::Fischer { initial, increment } => (0, vec![initial, increment]),
+            TimeControl::Bronstein { initial, delay } => (1, vec![initial, delay]),
+            TimeControl::PerMove(time) => (2, vec![time]),
+        };
+        buffer.push(id);
+        for time in times {
+            let millis = u32::try_from(time.as_millis()).unwrap_or(u32::MAX);
+            buffer.extend_from_slice(&millis.to_be_bytes());
+        }
+    }
+
+    /// Create a game with the settings, with the pieces set up
+    ///
+    /// # Errors
+    /// - Error from setting up the pieces or the variant's rules
+    pub fn new_game(
+        &self,
+        n_players: u8,
+        seed: u64,
+        back_rank: BackRank,
+    ) -> Result<Game<StandardCompatiblePieceSet>, Error<StandardCompatiblePieceSet>> {
+        let height = self.rows_per_player * u16::from(n_players);
+        let mut game = Game::new(n_players, self.width, height);
+        game.set_seed(seed);
+        game.set_time_control(self.time_control);
+
+        // Set the pieces up on an empty copy, so the variant's pieces can be placed at once
+        let mut start = game.clone();
+        setup(&mut start, back_rank)?;
+        game.add_pieces(pieces(&start).into_iter().map(|(piece, position)| {
+            let piece = match self.variant {
+                Variant::Atomic => Atomic::new(piece),
+                // Kings can be captured instead of being in check
+                Variant::Extinction if PieceSet::type_id(&piece) == KING_ID => {
+                    King::new_non_royal(piece.player())
+                }
+                _ => piece,
+            };
+            (piece, position)
+        }))?;
+
+        match self.variant {
+            Variant::Standard | Variant::Atomic => {}
+            Variant::ThreeCheck => game.add_condition(Box::new(ThreeCheck::new(n_players)))?,
+            Variant::KingOfTheHill => game.add_condition(Box::new(KingOfTheHill::new(
+                n_players,
+                usize::from(self.width),
+                usize::from(height),
+            )))?,
+            Variant::Extinction => game.add_condition(Box::new(Extinction::new()))?,
+            Variant::Duck => place_neutral(&mut game, 0, true)?,
+            Variant::FogOfWar => game.set_fog_of_war(true),
+        }
+
+        Ok(game)
+    }
+}
+
+/// Get every piece on the board and its position
+fn pieces(
+    game: &Game<StandardCompatiblePieceSet>,
+) -> Vec<(StandardCompatiblePieceSet, Coordinate)> {
+    game.board()
+        .raw()
+        .iter()
+        .enumerate()
+        .flat_map(|(y, row)| {
+            row.iter()
+                .enumerate()
+                .filter_map(move |(x, spot)| Some((spot.get().as_ref()?.clone(), Coordinate(x, y))))
+        })
+        .collect()
+}
+
+/// Parse an optional time control, where each player must start with some time
+///
+/// # Errors
+/// The data is not a valid time control
+pub fn parse_time_control(data: &[u8]) -> Result<Option<TimeControl>, ()> {
+    let read_duration = |index: usize| {
+        let bytes = data[index..index + 4].try_into().expect("slice has length 4");
+        Duration::from_millis(u64::from(u32::from_be_bytes(bytes)))
+    };
+
+    let time_control = match (data.first(), data.len()) {
+        (None, _) => return Ok(None),
+        (Some(0), 9) => TimeControl::Fischer {
+            initial: read_duration(1),
+            increment: read_duration(5),
+        },
+        (Some(1), 9) => TimeControl::Bronstein {
+            initial: read_duration(1),
+            delay: read_duration(5),
+        },
+        (Some(2), 5) => TimeControl::PerMove(read_duration(1)),
+        _ => return Err(()),
+    };
+
+    if time_control.initial().is_zero() {
+        return Err(());
+    }
+
+    Ok(Some(time_control))
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use chessehc::{clock::TimeControl, standard_pieces::BackRank};
+
+    use super::{parse_time_control, Settings, Variant};
+
+    #[test]
+    fn test_parse_time_control() {
+        let second = Duration::from_secs(1);
+
+        assert_eq!(parse_time_control(&[]), Ok(None), "untimed game");
+        assert_eq!(
+            parse_time_control(&[0, 0, 0, 0xEA, 0x60, 0, 0, 0x03, 0xE8]),
+            Ok(Some(TimeControl::Fischer {
+                initial: second * 60,
+                increment: second,
+            })),
+            "fischer time control"
+        );
+        assert_eq!(
+            parse_time_control(&[2, 0, 0, 0x13, 0x88]),
+            Ok(Some(TimeControl::PerMove(second * 5))),
+            "per move time control"
+        );
+
+        for data in [&[3, 0, 0, 0x13, 0x88][..], &[1, 0, 0, 0x13, 0x88], &[2, 0, 0, 0, 0]] {
+            assert!(parse_time_control(data).is_err(), "parsed invalid time control {data:?}");
+        }
+    }
+
+    #[test]
+    fn test_settings() {
+        assert_eq!(Settings::parse(&[]), Some(Settings::default()), "default settings");
+
+        let settings = Settings {
+            width: 10,
+            rows_per_player: 6,
+            max_players: 4,
+            variant: Variant::Duck,
+            time_control: Some(TimeControl::PerMove(Duration::from_secs(5))),
+            public: true,
+            spectators: true,
+            spectator_chat: true,
+        };
+        let mut buffer = Vec::new();
+        settings.write(&mut buffer);
+        assert_eq!(Settings::parse(&buffer), Some(settings), "settings changed when written and parsed");
+
+        for data in [
+            &[0, 7, 0, 7, 4, 0, 0][..],
+            &[0, 8, 0, 2, 4, 0, 0],
+            &[0, 8, 0, 7, 1, 0, 0],
+            &[0, 8, 0, 7, 4, 7, 0],
+            &[0, 8, 0, 7, 4, 0, 3],
+            &[0, 8, 0, 7, 4, 0, 8],
+            &[0, 8, 0, 7, 4, 0],
+        ] {
+            assert!(Settings::parse(data).is_none(), "parsed invalid settings {data:?}");
+        }
+
+        for id in 0..=6 {
+            let settings = Settings {
+                variant: Variant::from_id(id).expect("unknown variant"),
+                ..Settings::default()
+            };
+            let game = settings
+                .new_game(3, 0, BackRank::Standard)
+                .expect("failed to create game");
+            assert_eq!(game.board().height(), 21, "wrong board height");
+            assert!(!game.valid_moves().is_empty(), "no valid moves in {:?}", settings.variant);
+        }
+    }
+}
@@ -86,12 +86,16 @@ impl<'a> Requester<'a> for Account<'a> {
 }
 
 async fn log_out<'a>(client: &mut Client<'a>) -> Result<()> {
-    if client.log_in.take().is_none() {
+    if client.log_in.is_none() {
         return Err(Error::InvalReq(InvalidRequest::Perm(
             Permissions::NotLoggedIn,
         )));
     }
 
+    // Players are identified by their account, so cannot stay in a game
+    client.leave_game().await;
+    client.log_in = None;
+
     client
         .send(Response::Ok(Ok::Confirmation(LOG_OUT_OP_CODE)).into())
         .await
@@ -150,6 +154,10 @@ async fn change_public_key<'a>(client: &mut Client<'a>, new_public_key: &[u8]) -
 }
 
 async fn delete_account<'a>(client: &mut Client<'a>) -> Result<()> {
+    if client.log_in.is_some() {
+        client.leave_game().await;
+    }
+
     let Some(account_id) = client.log_in.take() else {
         return Err(Error::InvalReq(InvalidRequest::Perm(Permissions::NotLoggedIn)));
     };
@@ -1,14 +1,23 @@
 use std::borrow::Cow;
 
 use crate::{
-    game,
+    config::PLAYER_LIMIT,
+    game::{
+        self,
+        matchmaking::{self, QueueKey},
+        parse_time_control, Settings, Variant,
+    },
     response::{
         err::{
             inval_req::{self, perms::Permissions, InvalidRequest},
-            mal_req::MalformedRequest,
+            mal_req::{mal_bin::MalformedBinary, MalformedRequest},
             Error,
         },
-        ok::{in_game, Ok},
+        ok::{
+            in_game,
+            public::{lobbies::Lobbies, Public},
+            Ok,
+        },
         Response, Result,
     },
     server::handler::Client,
@@ -17,22 +26,49 @@ use crate::{
 use super::{Requester, RequesterRunResult};
 
 const JOIN_GAME_OP_CODE: u8 = 0b1010_0000;
+const SPECTATE_GAME_OP_CODE: u8 = 0b1010_1000;
+const WATCH_LOBBIES_OP_CODE: u8 = 0b1001_1000;
+const LEAVE_QUEUE_OP_CODE: u8 = 0b1011_1000;
 
 pub enum Game<'a> {
-    Create,
+    Create(Settings),
+    Lobbies,
+    WatchLobbies(bool),
     Join(Cow<'a, str>),
+    Spectate(Cow<'a, str>),
+    Queue(QueueKey),
+    LeaveQueue,
 }
 
 impl<'a> Requester<'a> for Game<'a> {
     fn parse(buffer: &'a [u8]) -> Result<Self> {
         let byte_zero = buffer.first().ok_or(MalformedRequest::op_err())?;
 
-        Ok(match (byte_zero >> 5) & 0b1 {
-            0 => Self::Create,
-            1 => {
+        Ok(match (byte_zero >> 4) & 0b11 {
+            0 => Self::Create(
+                Settings::parse(&buffer[1..])
+                    .ok_or(Error::MalReq(MalformedRequest::Bin(MalformedBinary::Data)))?,
+            ),
+            1 => match ((byte_zero >> 3) & 0b1, buffer.get(1)) {
+                (0, _) => Self::Lobbies,
+                (_, Some(0)) => Self::WatchLobbies(false),
+                (_, Some(1)) => Self::WatchLobbies(true),
+                _ => return Err(Error::MalReq(MalformedRequest::Bin(MalformedBinary::Data))),
+            },
+            2 => {
                 let code = String::from_utf8_lossy(&buffer[1..]);
-                Self::Join(code)
+                match (byte_zero >> 3) & 0b1 {
+                    0 => Self::Join(code),
+                    _ => Self::Spectate(code),
+                }
             }
+            3 => match (byte_zero >> 3) & 0b1 {
+                0 => Self::Queue(
+                    parse_queue_key(&buffer[1..])
+                        .ok_or(Error::MalReq(MalformedRequest::Bin(MalformedBinary::Data)))?,
+                ),
+                _ => Self::LeaveQueue,
+            },
             _ => unreachable!(),
         })
     }
@@ -42,18 +78,44 @@ impl<'a> Requester<'a> for Game<'a> {
         'b: 'a,
     {
         match self {
-            Self::Create => Box::pin(create_game(client)),
+            Self::Create(settings) => Box::pin(create_game(client, settings)),
+            Self::Lobbies => Box::pin(list_lobbies(client)),
+            Self::WatchLobbies(watch) => Box::pin(watch_lobbies(client, watch)),
             Self::Join(code) => Box::pin(join_game(client, code)),
+            Self::Spectate(code) => Box::pin(spectate_game(client, code)),
+            Self::Queue(key) => Box::pin(join_queue(client, key)),
+            Self::LeaveQueue => Box::pin(leave_queue(client)),
         }
     }
 }
 
-async fn create_game<'a>(client: &mut Client<'a>) -> Result<()> {
-    let Some(account_id) = client.log_in.take() else {
+/// Parse the player count, variant and optional time control of the games to be matched into
+fn parse_queue_key(data: &[u8]) -> Option<QueueKey> {
+    let players = *data.first()?;
+    if !(2..=PLAYER_LIMIT).contains(&players) {
+        return None;
+    }
+
+    Some(QueueKey {
+        players,
+        variant: Variant::from_id(*data.get(1)?)?,
+        time_control: parse_time_control(&data[2..]).ok()?,
+    })
+}
+
+async fn create_game<'a>(
+    client: &mut Client<'a>,
+    settings: Settings,
+) -> Result<()> {
+    let Some(account_id) = client.log_in else {
         return Err(Error::InvalReq(InvalidRequest::Perm(Permissions::NotLoggedIn)));
     };
 
-    let Ok(game) = game::create(account_id, client.game_handle.0.clone()) else {
+    // Playing in a created game instead of a matched one
+    matchmaking::leave(account_id).await.map_err(|()| Error::Server)?;
+    client.stop_spectating().await;
+
+    let Ok(game) = game::create(account_id, client.game_handle.0.clone(), settings) else {
         return Err(Error::Server);
     };
 
@@ -72,8 +134,34 @@ async fn create_game<'a>(client: &mut Client<'a>) -> Result<()> {
     Ok(())
 }
 
+async fn list_lobbies<'a>(client: &mut Client<'a>) -> Result<()> {
+    let lobbies = game::open_lobbies();
+
+    client
+        .send(Response::Ok(Ok::Public(Public::Lobbies(Lobbies::List(&lobbies)))).into())
+        .await
+        .ok();
+
+    Ok(())
+}
+
+async fn watch_lobbies<'a>(client: &mut Client<'a>, watch: bool) -> Result<()> {
+    if !watch {
+        client.lobbies = None;
+        client
+            .send(Response::Ok(Ok::Confirmation(WATCH_LOBBIES_OP_CODE)).into())
+            .await
+            .ok();
+        return Ok(());
+    }
+
+    // Subscribe before listing, so no change is missed
+    client.lobbies = Some(game::subscribe());
+    list_lobbies(client).await
+}
+
 async fn join_game<'a, 'b>(client: &mut Client<'a>, code: Cow<'b, str>) -> Result<()> {
-    let Some(account_id) = client.log_in.take() else {
+    let Some(account_id) = client.log_in else {
         return Err(Error::InvalReq(InvalidRequest::Perm(Permissions::NotLoggedIn)));
     };
 
@@ -84,6 +172,9 @@ async fn join_game<'a, 'b>(client: &mut Client<'a>, code: Cow<'b, str>) -> Resul
         inval_req::game::Game::UnknownGameId,
     )))?;
 
+    matchmaking::leave(account_id).await.map_err(|()| Error::Server)?;
+    client.stop_spectating().await;
+
     game_sender
         .send(game::PlayerMessage::Join(
             account_id,
@@ -102,11 +193,73 @@ async fn join_game<'a, 'b>(client: &mut Client<'a>, code: Cow<'b, str>) -> Resul
     Ok(())
 }
 
+/// Watch a game without playing, which does not need an account
+async fn spectate_game<'a, 'b>(client: &mut Client<'a>, code: Cow<'b, str>) -> Result<()> {
+    if client.game.1.is_some() {
+        return Err(Error::InvalReq(InvalidRequest::Game(inval_req::game::Game::In)));
+    }
+
+    let token = game::code_to_token(code.as_bytes())
+        .map_err(|_| Error::InvalReq(InvalidRequest::Game(inval_req::game::Game::InvalidGameId)))?;
+
+    let game_sender = game::get(token).ok_or(Error::InvalReq(inval_req::InvalidRequest::Game(
+        inval_req::game::Game::UnknownGameId,
+    )))?;
+
+    // The game sends the client its state once it has the message
+    game_sender
+        .send(game::PlayerMessage::Spectate(client.game_handle.0.clone()))
+        .await
+        .map_err(|_| Error::Server)?;
+
+    client.game.1 = Some(game_sender);
+    client.spectating = true;
+
+    client
+        .send(Response::Ok(Ok::Confirmation(SPECTATE_GAME_OP_CODE)).into())
+        .await
+        .ok();
+
+    Ok(())
+}
+
+async fn join_queue<'a>(client: &Client<'a>, key: QueueKey) -> Result<()> {
+    let Some(account_id) = client.log_in else {
+        return Err(Error::InvalReq(InvalidRequest::Perm(Permissions::NotLoggedIn)));
+    };
+
+    if client.game.1.is_some() {
+        return Err(Error::InvalReq(InvalidRequest::Game(inval_req::game::Game::In)));
+    }
+
+    // The queue's status is sent back by the matchmaker
+    matchmaking::join(account_id, key, client.game_handle.0.clone())
+        .await
+        .map_err(|()| Error::Server)
+}
+
+async fn leave_queue<'a>(client: &mut Client<'a>) -> Result<()> {
+    let Some(account_id) = client.log_in else {
+        return Err(Error::InvalReq(InvalidRequest::Perm(Permissions::NotLoggedIn)));
+    };
+
+    matchmaking::leave(account_id).await.map_err(|()| Error::Server)?;
+
+    client
+        .send(Response::Ok(Ok::Confirmation(LEAVE_QUEUE_OP_CODE)).into())
+        .await
+        .ok();
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod test {
     use crate::request::{game::Game, Request, Requester};
 
-    use super::JOIN_GAME_OP_CODE;
+    use super::{
+        JOIN_GAME_OP_CODE, LEAVE_QUEUE_OP_CODE, SPECTATE_GAME_OP_CODE, WATCH_LOBBIES_OP_CODE,
+    };
 
     #[test]
     fn test_join_game_op_code() {
@@ -119,4 +272,40 @@ mod test {
             "op-code {JOIN_GAME_OP_CODE:0>8b} is not the join game op-code"
         );
     }
+
+    #[test]
+    fn test_spectate_game_op_code() {
+        let request = [SPECTATE_GAME_OP_CODE];
+
+        let spectate_game = Request::parse(&request);
+
+        assert!(
+            matches!(spectate_game, Ok(Request::Game(Game::Spectate(_)))),
+            "op-code {SPECTATE_GAME_OP_CODE:0>8b} is not the spectate game op-code"
+        );
+    }
+
+    #[test]
+    fn test_watch_lobbies_op_code() {
+        let request = [WATCH_LOBBIES_OP_CODE, 1];
+
+        let watch_lobbies = Request::parse(&request);
+
+        assert!(
+            matches!(watch_lobbies, Ok(Request::Game(Game::WatchLobbies(true)))),
+            "op-code {WATCH_LOBBIES_OP_CODE:0>8b} is not the watch lobbies op-code"
+        );
+    }
+
+    #[test]
+    fn test_leave_queue_op_code() {
+        let request = [LEAVE_QUEUE_OP_CODE];
+
+        let leave_queue = Request::parse(&request);
+
+        assert!(
+            matches!(leave_queue, Ok(Request::Game(Game::LeaveQueue))),
+            "op-code {LEAVE_QUEUE_OP_CODE:0>8b} is not the leave queue op-code"
+        );
+    }
 }
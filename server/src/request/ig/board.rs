@@ -0,0 +1,118 @@
+use chessehc::{coordinate::Coordinate, r#move::PartialMove};
+
+use crate::{
+    game::PlayerMessage,
+    request::{Requester, RequesterRunResult},
+    response::{
+        err::{
+            inval_req::{self, perms::Permissions, InvalidRequest},
+            mal_req::{mal_bin::MalformedBinary, MalformedRequest},
+            Error,
+        },
+        Result,
+    },
+    server::handler::Client,
+};
+
+pub enum Board {
+    Move(PartialMove),
+    Hints,
+}
+
+impl<'a> Requester<'a> for Board {
+    fn parse(buffer: &[u8]) -> Result<Self> {
+        let byte_zero = buffer.first().ok_or(MalformedRequest::op_err())?;
+
+        Ok(match (byte_zero >> 3) & 0b1 {
+            0 => {
+                if buffer.len() != 10 {
+                    return Err(Error::MalReq(MalformedRequest::Bin(MalformedBinary::Data)));
+                }
+
+                let read_u16 =
+                    |index: usize| usize::from(u16::from_be_bytes([buffer[index], buffer[index + 1]]));
+
+                Self::Move((
+                    Coordinate(read_u16(1), read_u16(3)),
+                    Coordinate(read_u16(5), read_u16(7)),
+                    buffer[9],
+                ))
+            }
+            1 => Self::Hints,
+            _ => unreachable!(),
+        })
+    }
+
+    fn run<'b>(self, client: &'a mut Client<'b>) -> RequesterRunResult<'a>
+    where
+        'b: 'a,
+    {
+        match self {
+            Self::Move(partial_move) => Box::pin(make_move(client, partial_move)),
+            Self::Hints => Box::pin(get_hints(client)),
+        }
+    }
+}
+
+async fn make_move<'a>(client: &Client<'a>, partial_move: PartialMove) -> Result<()> {
+    let Some(account_id) = client.log_in else {
+        return Err(Error::InvalReq(InvalidRequest::Perm(Permissions::NotLoggedIn)));
+    };
+
+    let Some(game_sender) = client.game.1.as_ref().filter(|_| !client.spectating) else {
+        return Err(Error::InvalReq(InvalidRequest::Game(inval_req::game::Game::NotIn)));
+    };
+
+    // The result is sent back by the game
+    game_sender
+        .send(PlayerMessage::Move(account_id, partial_move))
+        .await
+        .map_err(|_| Error::Server)
+}
+
+/// Ask the game for the tactics the player can see on the board
+async fn get_hints<'a>(client: &Client<'a>) -> Result<()> {
+    let Some(account_id) = client.log_in else {
+        return Err(Error::InvalReq(InvalidRequest::Perm(Permissions::NotLoggedIn)));
+    };
+
+    let Some(game_sender) = client.game.1.as_ref().filter(|_| !client.spectating) else {
+        return Err(Error::InvalReq(InvalidRequest::Game(inval_req::game::Game::NotIn)));
+    };
+
+    // The hints are sent back by the game
+    game_sender
+        .send(PlayerMessage::Hints(account_id))
+        .await
+        .map_err(|_| Error::Server)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::request::{ig::InGame, Request, Requester};
+
+    use super::Board;
+
+    const MOVE_OP_CODE: u8 = 0b1101_0000;
+    const HINTS_OP_CODE: u8 = 0b1101_1000;
+
+    #[test]
+    fn test_move_op_code() {
+        let request = [MOVE_OP_CODE, 0, 1, 0, 2, 0, 1, 0, 4, 0];
+
+        let r#move = Request::parse(&request);
+        assert!(
+            matches!(r#move, Ok(Request::IG(InGame::Board(Board::Move(_))))),
+            "op-code {MOVE_OP_CODE:0>8b} is not the move op-code"
+        );
+    }
+
+    #[test]
+    fn test_hints_op_code() {
+        let hints = Request::parse(&[HINTS_OP_CODE]);
+        assert!(
+            matches!(hints, Ok(Request::IG(InGame::Board(Board::Hints)))),
+            "op-code {HINTS_OP_CODE:0>8b} is not the hints op-code"
+        );
+    }
+}
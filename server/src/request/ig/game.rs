@@ -0,0 +1,182 @@
+use crate::{
+    config::CHAT_MAX_LENGTH,
+    game::{bot::Strength, PlayerMessage},
+    request::{Requester, RequesterRunResult},
+    response::{
+        err::{
+            inval_req::{self, chat::Chat, perms::Permissions, InvalidRequest},
+            mal_req::{mal_bin::MalformedBinary, MalformedRequest},
+            Error,
+        },
+        ok::Ok,
+        Response, Result,
+    },
+    server::handler::Client,
+};
+
+const MUTE_OP_CODE: u8 = 0b1100_0100;
+
+pub enum Game {
+    Chat(String),
+    Mute(i64, bool),
+    AddBot(Strength),
+}
+
+impl<'a> Requester<'a> for Game {
+    fn parse(buffer: &[u8]) -> Result<Self> {
+        let byte_zero = buffer.first().ok_or(MalformedRequest::op_err())?;
+
+        Ok(match (byte_zero >> 2) & 0b11 {
+            0 => {
+                let text = std::str::from_utf8(&buffer[1..])
+                    .map_err(|_| Error::MalReq(MalformedRequest::Bin(MalformedBinary::Data)))?;
+                if text.is_empty() {
+                    return Err(Error::MalReq(MalformedRequest::Bin(MalformedBinary::Data)));
+                }
+
+                Self::Chat(text.to_owned())
+            }
+            1 => {
+                if buffer.len() != 10 {
+                    return Err(Error::MalReq(MalformedRequest::Bin(MalformedBinary::Data)));
+                }
+
+                let player = i64::from_be_bytes(buffer[1..9].try_into().expect("slice has length 8"));
+                let mute = match buffer[9] {
+                    0 => false,
+                    1 => true,
+                    _ => return Err(Error::MalReq(MalformedRequest::Bin(MalformedBinary::Data))),
+                };
+
+                Self::Mute(player, mute)
+            }
+            2 => {
+                if buffer.len() != 2 {
+                    return Err(Error::MalReq(MalformedRequest::Bin(MalformedBinary::Data)));
+                }
+
+                Self::AddBot(
+                    Strength::from_id(buffer[1])
+                        .ok_or(Error::MalReq(MalformedRequest::Bin(MalformedBinary::Data)))?,
+                )
+            }
+            _ => return Err(MalformedRequest::op_err()),
+        })
+    }
+
+    fn run<'b>(self, client: &'a mut Client<'b>) -> RequesterRunResult<'a>
+    where
+        'b: 'a,
+    {
+        match self {
+            Self::Chat(text) => Box::pin(chat(client, text)),
+            Self::Mute(player, mute) => Box::pin(mute_player(client, player, mute)),
+            Self::AddBot(strength) => Box::pin(add_bot(client, strength)),
+        }
+    }
+}
+
+async fn chat<'a>(client: &Client<'a>, text: String) -> Result<()> {
+    let Some(account_id) = client.log_in else {
+        return Err(Error::InvalReq(InvalidRequest::Perm(Permissions::NotLoggedIn)));
+    };
+
+    let Some(game_sender) = client.game.1.as_ref() else {
+        return Err(Error::InvalReq(InvalidRequest::Game(inval_req::game::Game::NotIn)));
+    };
+
+    if text.chars().count() > CHAT_MAX_LENGTH {
+        return Err(Error::InvalReq(InvalidRequest::Chat(Chat::TooLong)));
+    }
+
+    // The message is sent back by the game once it is stamped
+    game_sender
+        .send(PlayerMessage::Chat {
+            player: account_id,
+            text,
+            tp: client.game_handle.0.clone(),
+        })
+        .await
+        .map_err(|_| Error::Server)
+}
+
+/// Stop or start receiving a player's chat messages
+async fn mute_player<'a>(client: &mut Client<'a>, player: i64, mute: bool) -> Result<()> {
+    client.muted.retain(|id| *id != player);
+    if mute {
+        client.muted.push(player);
+    }
+
+    client
+        .send(Response::Ok(Ok::Confirmation(MUTE_OP_CODE)).into())
+        .await
+        .ok();
+
+    Ok(())
+}
+
+async fn add_bot<'a>(client: &Client<'a>, strength: Strength) -> Result<()> {
+    let Some(account_id) = client.log_in else {
+        return Err(Error::InvalReq(InvalidRequest::Perm(Permissions::NotLoggedIn)));
+    };
+
+    let Some(game_sender) = client.game.1.as_ref().filter(|_| !client.spectating) else {
+        return Err(Error::InvalReq(InvalidRequest::Game(inval_req::game::Game::NotIn)));
+    };
+
+    // The bot joining is broadcast by the game
+    game_sender
+        .send(PlayerMessage::AddBot {
+            host: account_id,
+            strength,
+        })
+        .await
+        .map_err(|_| Error::Server)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        game::bot::Strength,
+        request::{ig::InGame, Request, Requester},
+    };
+
+    use super::{Game, MUTE_OP_CODE};
+
+    const CHAT_OP_CODE: u8 = 0b1100_0000;
+    const ADD_BOT_OP_CODE: u8 = 0b1100_1000;
+
+    #[test]
+    fn test_chat_op_code() {
+        let request = [CHAT_OP_CODE, b'h', b'i'];
+
+        let chat = Request::parse(&request);
+        assert!(
+            matches!(chat, Ok(Request::IG(InGame::Game(Game::Chat(_))))),
+            "op-code {CHAT_OP_CODE:0>8b} is not the chat op-code"
+        );
+    }
+
+    #[test]
+    fn test_mute_op_code() {
+        let request = [MUTE_OP_CODE, 0, 0, 0, 0, 0, 0, 0, 1, 1];
+
+        let mute = Request::parse(&request);
+        assert!(
+            matches!(mute, Ok(Request::IG(InGame::Game(Game::Mute(1, true))))),
+            "op-code {MUTE_OP_CODE:0>8b} is not the mute op-code"
+        );
+    }
+
+    #[test]
+    fn test_add_bot_op_code() {
+        let request = [ADD_BOT_OP_CODE, 2];
+
+        let add_bot = Request::parse(&request);
+        assert!(
+            matches!(add_bot, Ok(Request::IG(InGame::Game(Game::AddBot(Strength::Searched))))),
+            "op-code {ADD_BOT_OP_CODE:0>8b} is not the add bot op-code"
+        );
+        assert!(Request::parse(&[ADD_BOT_OP_CODE, 3]).is_err(), "unknown bot strength accepted");
+    }
+}
@@ -0,0 +1,208 @@
+use chessehc::standard_pieces::BackRank;
+
+use crate::{
+    game::PlayerMessage,
+    request::{Requester, RequesterRunResult},
+    response::{
+        err::{
+            inval_req::{self, perms::Permissions, InvalidRequest},
+            mal_req::{mal_bin::MalformedBinary, MalformedRequest},
+            Error,
+        },
+        Result,
+    },
+    server::handler::Client,
+};
+
+pub enum Manage {
+    Start(BackRank),
+    Kick(i64, bool),
+    TransferHost(i64),
+    Ready(bool),
+}
+
+impl<'a> Requester<'a> for Manage {
+    fn parse(buffer: &[u8]) -> Result<Self> {
+        let byte_zero = buffer.first().ok_or(MalformedRequest::op_err())?;
+
+        Ok(match (byte_zero >> 2) & 0b11 {
+            0 => Self::Start(match buffer.get(1) {
+                None | Some(0) => BackRank::Standard,
+                Some(1) => BackRank::Shared,
+                Some(2) => BackRank::Individual,
+                Some(_) => return Err(Error::MalReq(MalformedRequest::Bin(MalformedBinary::Data))),
+            }),
+            1 => {
+                if buffer.len() != 10 {
+                    return Err(Error::MalReq(MalformedRequest::Bin(MalformedBinary::Data)));
+                }
+
+                Self::Kick(read_id(buffer), read_flag(buffer[9])?)
+            }
+            2 => {
+                if buffer.len() != 9 {
+                    return Err(Error::MalReq(MalformedRequest::Bin(MalformedBinary::Data)));
+                }
+
+                Self::TransferHost(read_id(buffer))
+            }
+            3 => {
+                if buffer.len() != 2 {
+                    return Err(Error::MalReq(MalformedRequest::Bin(MalformedBinary::Data)));
+                }
+
+                Self::Ready(read_flag(buffer[1])?)
+            }
+            _ => unreachable!(),
+        })
+    }
+
+    fn run<'b>(self, client: &'a mut Client<'b>) -> RequesterRunResult<'a>
+    where
+        'b: 'a,
+    {
+        match self {
+            Self::Start(back_rank) => Box::pin(start_game(client, back_rank)),
+            Self::Kick(player, ban) => Box::pin(kick_player(client, player, ban)),
+            Self::TransferHost(player) => Box::pin(transfer_host(client, player)),
+            Self::Ready(ready) => Box::pin(set_ready(client, ready)),
+        }
+    }
+}
+
+/// Read the account id after the op-code
+fn read_id(buffer: &[u8]) -> i64 {
+    i64::from_be_bytes(buffer[1..9].try_into().expect("slice has length 8"))
+}
+
+const fn read_flag(byte: u8) -> Result<bool> {
+    match byte {
+        0 => Ok(false),
+        1 => Ok(true),
+        _ => Err(Error::MalReq(MalformedRequest::Bin(MalformedBinary::Data))),
+    }
+}
+
+async fn start_game<'a>(client: &Client<'a>, back_rank: BackRank) -> Result<()> {
+    let Some(account_id) = client.log_in else {
+        return Err(Error::InvalReq(InvalidRequest::Perm(Permissions::NotLoggedIn)));
+    };
+
+    let Some(game_sender) = client.game.1.as_ref().filter(|_| !client.spectating) else {
+        return Err(Error::InvalReq(InvalidRequest::Game(inval_req::game::Game::NotIn)));
+    };
+
+    // The board is sent back by the game
+    game_sender
+        .send(PlayerMessage::Start(account_id, back_rank))
+        .await
+        .map_err(|_| Error::Server)
+}
+
+async fn kick_player<'a>(client: &Client<'a>, player: i64, ban: bool) -> Result<()> {
+    let Some(account_id) = client.log_in else {
+        return Err(Error::InvalReq(InvalidRequest::Perm(Permissions::NotLoggedIn)));
+    };
+
+    let Some(game_sender) = client.game.1.as_ref().filter(|_| !client.spectating) else {
+        return Err(Error::InvalReq(InvalidRequest::Game(inval_req::game::Game::NotIn)));
+    };
+
+    // The removal is broadcast by the game
+    game_sender
+        .send(PlayerMessage::Kick {
+            host: account_id,
+            player,
+            ban,
+        })
+        .await
+        .map_err(|_| Error::Server)
+}
+
+async fn transfer_host<'a>(client: &Client<'a>, player: i64) -> Result<()> {
+    let Some(account_id) = client.log_in else {
+        return Err(Error::InvalReq(InvalidRequest::Perm(Permissions::NotLoggedIn)));
+    };
+
+    let Some(game_sender) = client.game.1.as_ref().filter(|_| !client.spectating) else {
+        return Err(Error::InvalReq(InvalidRequest::Game(inval_req::game::Game::NotIn)));
+    };
+
+    // The new host is broadcast by the game
+    game_sender
+        .send(PlayerMessage::TransferHost(account_id, player))
+        .await
+        .map_err(|_| Error::Server)
+}
+
+async fn set_ready<'a>(client: &Client<'a>, ready: bool) -> Result<()> {
+    let Some(account_id) = client.log_in else {
+        return Err(Error::InvalReq(InvalidRequest::Perm(Permissions::NotLoggedIn)));
+    };
+
+    let Some(game_sender) = client.game.1.as_ref().filter(|_| !client.spectating) else {
+        return Err(Error::InvalReq(InvalidRequest::Game(inval_req::game::Game::NotIn)));
+    };
+
+    // The change is broadcast by the game
+    game_sender
+        .send(PlayerMessage::Ready(account_id, ready))
+        .await
+        .map_err(|_| Error::Server)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::request::{ig::InGame, Request, Requester};
+
+    use super::Manage;
+
+    const START_GAME_OP_CODE: u8 = 0b1110_0000;
+    const KICK_OP_CODE: u8 = 0b1110_0100;
+    const TRANSFER_HOST_OP_CODE: u8 = 0b1110_1000;
+    const READY_OP_CODE: u8 = 0b1110_1100;
+
+    #[test]
+    fn test_start_game_op_code() {
+        let request = [START_GAME_OP_CODE];
+
+        let start_game = Request::parse(&request);
+        assert!(
+            matches!(start_game, Ok(Request::IG(InGame::Manage(Manage::Start(_))))),
+            "op-code {START_GAME_OP_CODE:0>8b} is not the start game op-code"
+        );
+    }
+
+    #[test]
+    fn test_kick_op_code() {
+        let request = [KICK_OP_CODE, 0, 0, 0, 0, 0, 0, 0, 1, 1];
+
+        let kick = Request::parse(&request);
+        assert!(
+            matches!(kick, Ok(Request::IG(InGame::Manage(Manage::Kick(1, true))))),
+            "op-code {KICK_OP_CODE:0>8b} is not the kick op-code"
+        );
+    }
+
+    #[test]
+    fn test_transfer_host_op_code() {
+        let request = [TRANSFER_HOST_OP_CODE, 0, 0, 0, 0, 0, 0, 0, 1];
+
+        let transfer_host = Request::parse(&request);
+        assert!(
+            matches!(transfer_host, Ok(Request::IG(InGame::Manage(Manage::TransferHost(1))))),
+            "op-code {TRANSFER_HOST_OP_CODE:0>8b} is not the transfer host op-code"
+        );
+    }
+
+    #[test]
+    fn test_ready_op_code() {
+        let request = [READY_OP_CODE, 1];
+
+        let ready = Request::parse(&request);
+        assert!(
+            matches!(ready, Ok(Request::IG(InGame::Manage(Manage::Ready(true))))),
+            "op-code {READY_OP_CODE:0>8b} is not the ready op-code"
+        );
+    }
+}
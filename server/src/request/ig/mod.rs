@@ -1,14 +1,30 @@
 use crate::{
-    response::{err::mal_req::MalformedRequest, Result},
+    response::{
+        err::{
+            inval_req::{self, InvalidRequest},
+            mal_req::MalformedRequest,
+            Error,
+        },
+        ok::Ok,
+        Response, Result,
+    },
     server::handler::Client,
 };
 
 use super::{Requester, RequesterRunResult};
 
+pub mod board;
+pub mod game;
+pub mod manage;
+
+use self::{board::Board, game::Game, manage::Manage};
+
+const LEAVE_GAME_OP_CODE: u8 = 0b1111_0000;
+
 pub enum InGame {
-    Game,
-    Board,
-    Manage,
+    Game(Game),
+    Board(Board),
+    Manage(Manage),
     Leave,
 }
 
@@ -17,18 +33,55 @@ impl<'a> Requester<'a> for InGame {
         let byte_zero = buffer.first().ok_or(MalformedRequest::op_err())?;
 
         Ok(match (byte_zero >> 4) & 0b11 {
-            0 => Self::Game,
-            1 => Self::Board,
-            2 => Self::Manage,
+            0 => Self::Game(Game::parse(buffer)?),
+            1 => Self::Board(Board::parse(buffer)?),
+            2 => Self::Manage(Manage::parse(buffer)?),
             3 => Self::Leave,
             _ => unreachable!(),
         })
     }
 
-    fn run<'b>(self, _client: &'a mut Client<'b>) -> RequesterRunResult<'a>
+    fn run<'b>(self, client: &'a mut Client<'b>) -> RequesterRunResult<'a>
     where
         'b: 'a,
     {
-        todo!()
+        match self {
+            Self::Game(req) => req.run(client),
+            Self::Board(req) => req.run(client),
+            Self::Manage(req) => req.run(client),
+            Self::Leave => Box::pin(leave_game(client)),
+        }
+    }
+}
+
+/// Leave the game being played or watched
+async fn leave_game<'a>(client: &mut Client<'a>) -> Result<()> {
+    if !client.leave_game().await {
+        return Err(Error::InvalReq(InvalidRequest::Game(inval_req::game::Game::NotIn)));
+    }
+
+    client
+        .send(Response::Ok(Ok::Confirmation(LEAVE_GAME_OP_CODE)).into())
+        .await
+        .ok();
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::request::{Request, Requester};
+
+    use super::{InGame, LEAVE_GAME_OP_CODE};
+
+    #[test]
+    fn test_leave_game_op_code() {
+        let request = [LEAVE_GAME_OP_CODE];
+
+        let leave_game = Request::parse(&request);
+        assert!(
+            matches!(leave_game, Ok(Request::IG(InGame::Leave))),
+            "op-code {LEAVE_GAME_OP_CODE:0>8b} is not the leave game op-code"
+        );
     }
 }
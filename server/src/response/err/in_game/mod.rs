@@ -1,9 +1,62 @@
 use crate::response::Responder;
 
-pub enum InGame {}
+pub enum InGame {
+    NotStarted,
+    NotTurn,
+    InvalidMove,
+    NotReady,
+    GameOver,
+}
 
 impl Responder for InGame {
-    fn write(self, _buffer: &mut Vec<u8>) {
-        todo!()
+    fn write(self, buffer: &mut Vec<u8>) {
+        let Some(byte_zero) = buffer.get_mut(0) else { return };
+
+        // The third bit was added after the first four errors, which keep their types
+        *byte_zero |= match self {
+            Self::NotStarted => 0,
+            Self::GameOver => 1,
+            Self::NotTurn => 2,
+            Self::InvalidMove => 4,
+            Self::NotReady => 6,
+        } << 2;
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        response::{err::Error, Response},
+        test_type,
+    };
+
+    test_type!(
+        test_type_not_started,
+        Response::Err(Error::IG(super::InGame::NotStarted)),
+        0b1010_0000
+    );
+
+    test_type!(
+        test_type_not_turn,
+        Response::Err(Error::IG(super::InGame::NotTurn)),
+        0b1010_1000
+    );
+
+    test_type!(
+        test_type_invalid_move,
+        Response::Err(Error::IG(super::InGame::InvalidMove)),
+        0b1011_0000
+    );
+
+    test_type!(
+        test_type_not_ready,
+        Response::Err(Error::IG(super::InGame::NotReady)),
+        0b1011_1000
+    );
+
+    test_type!(
+        test_type_game_over,
+        Response::Err(Error::IG(super::InGame::GameOver)),
+        0b1010_0100
+    );
+}
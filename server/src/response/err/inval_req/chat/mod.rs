@@ -0,0 +1,40 @@
+use crate::response::Responder;
+
+pub enum Chat {
+    TooLong,
+    RateLimited,
+}
+
+impl Responder for Chat {
+    fn write(self, buffer: &mut Vec<u8>) {
+        let Some(byte_zero) = buffer.get_mut(0) else { return };
+
+        *byte_zero |= match self {
+            Self::TooLong => 0,
+            Self::RateLimited => 1,
+        } << 2;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        response::{
+            err::{inval_req::InvalidRequest, Error},
+            Response,
+        },
+        test_type,
+    };
+
+    test_type!(
+        test_type_message_too_long,
+        Response::Err(Error::InvalReq(InvalidRequest::Chat(super::Chat::TooLong))),
+        0b1101_1000
+    );
+
+    test_type!(
+        test_type_rate_limited,
+        Response::Err(Error::InvalReq(InvalidRequest::Chat(super::Chat::RateLimited))),
+        0b1101_1100
+    );
+}
@@ -1,15 +1,17 @@
 use crate::response::Responder;
 
 pub mod auth;
+pub mod chat;
 pub mod game;
 pub mod perms;
 
-use self::{auth::Authentication, game::Game, perms::Permissions};
+use self::{auth::Authentication, chat::Chat, game::Game, perms::Permissions};
 
 pub enum InvalidRequest {
     Perm(Permissions),
     Auth(Authentication),
     Game(Game),
+    Chat(Chat),
 }
 
 impl Responder for InvalidRequest {
@@ -20,12 +22,14 @@ impl Responder for InvalidRequest {
             Self::Perm(_) => 0,
             Self::Auth(_) => 1,
             Self::Game(_) => 2,
+            Self::Chat(_) => 3,
         } << 3;
 
         match self {
             Self::Perm(err) => err.write(buffer),
             Self::Auth(err) => err.write(buffer),
             Self::Game(err) => err.write(buffer),
+            Self::Chat(err) => err.write(buffer),
         }
     }
 }
@@ -3,6 +3,8 @@ use crate::response::Responder;
 pub enum Permissions {
     NotLoggedIn,
     NotGameHost,
+    Banned,
+    SpectatingDisabled,
 }
 
 impl Responder for Permissions {
@@ -10,9 +12,11 @@ impl Responder for Permissions {
         let Some(byte_zero) = buffer.get_mut(0) else { return };
 
         *byte_zero |= match self {
-            Self::NotLoggedIn => 0,
-            Self::NotGameHost => 1,
-        } << 2;
+            Self::NotLoggedIn => 0b000,
+            Self::NotGameHost => 0b100,
+            Self::Banned => 0b101,
+            Self::SpectatingDisabled => 0b110,
+        };
     }
 }
 
@@ -41,4 +45,20 @@ mod test {
         ))),
         0b1100_0100
     );
+
+    test_type!(
+        test_type_banned,
+        Response::Err(Error::InvalReq(InvalidRequest::Perm(
+            super::Permissions::Banned
+        ))),
+        0b1100_0101
+    );
+
+    test_type!(
+        test_type_spectating_disabled,
+        Response::Err(Error::InvalReq(InvalidRequest::Perm(
+            super::Permissions::SpectatingDisabled
+        ))),
+        0b1100_0110
+    );
 }
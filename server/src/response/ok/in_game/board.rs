@@ -1,9 +1,218 @@
-use crate::response::Responder;
+use chessehc::{coordinate::Coordinate, delta::PartialDelta, tactics::Tactic};
 
-pub enum Board {}
+use crate::{game::ChatMessage, response::Responder};
 
-impl Responder for Board {
-    fn write(self, _buffer: &mut Vec<u8>) {
-        todo!()
+pub enum Board<'a> {
+    Turn {
+        player: i64,
+        clocks: &'a [u32],
+    },
+    Move {
+        player: i64,
+        points: u16,
+        deltas: &'a [PartialDelta<u8>],
+    },
+    View(&'a [(u8, u8)]),
+    Chat(&'a ChatMessage),
+    Hints(&'a [Tactic]),
+}
+
+impl<'a> Responder for Board<'a> {
+    fn write(self, buffer: &mut Vec<u8>) {
+        let Some(byte_zero) = buffer.get_mut(0) else { return };
+
+        // The third bit was added after the first four responses, which keep their types
+        *byte_zero |= match &self {
+            Self::Turn { .. } => 0,
+            Self::Hints(_) => 1,
+            Self::Move { .. } => 2,
+            Self::View(_) => 4,
+            Self::Chat(_) => 6,
+        } << 1;
+
+        match self {
+            Self::Turn { player, clocks } => {
+                buffer.reserve(8 + clocks.len() * 4);
+
+                buffer.extend_from_slice(&player.to_be_bytes());
+                for clock in clocks {
+                    buffer.extend_from_slice(&clock.to_be_bytes());
+                }
+            }
+            Self::Move {
+                player,
+                points,
+                deltas,
+            } => {
+                buffer.reserve(8 + 2 + deltas.len() * 9);
+
+                buffer.extend_from_slice(&player.to_be_bytes());
+                buffer.extend_from_slice(&points.to_be_bytes());
+                for delta in deltas {
+                    write_delta(delta, buffer);
+                }
+            }
+            Self::View(board) => {
+                buffer.reserve(board.len() * 2);
+                buffer.extend(board.iter().flat_map(|(player, id)| [player, id]));
+            }
+            Self::Chat(message) => {
+                buffer.reserve(8 + 8 + 1 + message.text.len());
+
+                buffer.extend_from_slice(&message.player.to_be_bytes());
+                buffer.extend_from_slice(&message.timestamp.to_be_bytes());
+                buffer.push(u8::from(message.spectators));
+                buffer.extend_from_slice(message.text.as_bytes());
+            }
+            Self::Hints(tactics) => {
+                for tactic in tactics {
+                    write_tactic(tactic, buffer);
+                }
+            }
+        }
+    }
+}
+
+fn write_coordinate(coordinate: &Coordinate, buffer: &mut Vec<u8>) {
+    for value in [coordinate.0, coordinate.1] {
+        let value = u16::try_from(value).expect("coordinate too large");
+        buffer.extend_from_slice(&value.to_be_bytes());
+    }
+}
+
+fn write_delta(delta: &PartialDelta<u8>, buffer: &mut Vec<u8>) {
+    match delta {
+        PartialDelta::Move(from, to) => {
+            buffer.push(0);
+            write_coordinate(from, buffer);
+            write_coordinate(to, buffer);
+        }
+        PartialDelta::Delete(position) => {
+            buffer.push(1);
+            write_coordinate(position, buffer);
+        }
+        PartialDelta::Replace(position, id, player) => {
+            buffer.push(2);
+            write_coordinate(position, buffer);
+            buffer.extend_from_slice(&[*player, *id]);
+        }
+        PartialDelta::Checkmate(player) => buffer.extend_from_slice(&[3, *player]),
+        PartialDelta::Stalemate(player) => buffer.extend_from_slice(&[4, *player]),
+        PartialDelta::Eliminated(player) => buffer.extend_from_slice(&[5, *player]),
+        PartialDelta::Won(player) => buffer.extend_from_slice(&[6, *player]),
+        PartialDelta::Timeout(player) => buffer.extend_from_slice(&[7, *player]),
+    }
+}
+
+fn write_tactic(tactic: &Tactic, buffer: &mut Vec<u8>) {
+    match tactic {
+        Tactic::Hanging { piece, gain } => {
+            buffer.push(0);
+            write_coordinate(piece, buffer);
+            buffer.extend_from_slice(&gain.to_be_bytes());
+        }
+        Tactic::Fork { attacker, targets } => {
+            buffer.push(1);
+            write_coordinate(attacker, buffer);
+            buffer.push(u8::try_from(targets.len()).unwrap_or(u8::MAX));
+            for target in targets.iter().take(usize::from(u8::MAX)) {
+                write_coordinate(target, buffer);
+            }
+        }
+        Tactic::Pin(pin) => {
+            buffer.push(2);
+            for position in [&pin.pinned, &pin.pinner, &pin.royal] {
+                write_coordinate(position, buffer);
+            }
+        }
+        Tactic::Skewer {
+            attacker,
+            front,
+            behind,
+        } => {
+            buffer.push(3);
+            for position in [attacker, front, behind] {
+                write_coordinate(position, buffer);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        response::{
+            ok::{in_game::InGame, Ok},
+            Response,
+        },
+        test_type,
+    };
+
+    use super::{Board, ChatMessage, Coordinate, Tactic};
+
+    test_type!(
+        test_type_turn,
+        Response::Ok(Ok::InGame(InGame::Board(Board::Turn {
+            player: 0,
+            clocks: &[],
+        }))),
+        0b0111_0000
+    );
+
+    test_type!(
+        test_type_move,
+        Response::Ok(Ok::InGame(InGame::Board(Board::Move {
+            player: 0,
+            points: 0,
+            deltas: &[],
+        }))),
+        0b0111_0100
+    );
+
+    test_type!(
+        test_type_view,
+        Response::Ok(Ok::InGame(InGame::Board(Board::View(&[])))),
+        0b0111_1000
+    );
+
+    test_type!(
+        test_type_hints,
+        Response::Ok(Ok::InGame(InGame::Board(Board::Hints(&[])))),
+        0b0111_0010
+    );
+
+    test_type!(
+        test_type_chat,
+        Response::Ok(Ok::InGame(InGame::Board(Board::Chat(&ChatMessage {
+            player: 0,
+            timestamp: 0,
+            spectators: false,
+            text: String::new(),
+        })))),
+        0b0111_1100
+    );
+
+    #[test]
+    fn test_write_hints() {
+        let tactics = [
+            Tactic::Hanging {
+                piece: Coordinate(1, 2),
+                gain: 3,
+            },
+            Tactic::Fork {
+                attacker: Coordinate(0, 0),
+                targets: vec![Coordinate(2, 1), Coordinate(1, 2)],
+            },
+        ];
+        let response: Vec<u8> = Response::Ok(Ok::InGame(InGame::Board(Board::Hints(&tactics)))).into();
+
+        assert_eq!(
+            response,
+            [
+                0b0111_0010,
+                0, 0, 1, 0, 2, 0, 0, 0, 3,
+                1, 0, 0, 0, 0, 2, 0, 2, 0, 1, 0, 1, 0, 2,
+            ]
+        );
     }
 }
@@ -0,0 +1,74 @@
+use crate::{game::Settings, response::Responder};
+
+pub enum Lobby<'a> {
+    Settings(&'a Settings),
+    Host(i64),
+    Ready(i64, bool),
+    /// A player was removed from the game by the host, and whether they were banned
+    Kick(i64, bool),
+}
+
+impl<'a> Responder for Lobby<'a> {
+    fn write(self, buffer: &mut Vec<u8>) {
+        let Some(byte_zero) = buffer.get_mut(0) else { return };
+
+        *byte_zero |= match &self {
+            Self::Settings(_) => 0,
+            Self::Host(_) => 1,
+            Self::Ready(..) => 2,
+            Self::Kick(..) => 3,
+        };
+
+        match self {
+            Self::Settings(settings) => settings.write(buffer),
+            Self::Host(player) => buffer.extend_from_slice(&player.to_be_bytes()),
+            Self::Ready(player, flag) | Self::Kick(player, flag) => {
+                buffer.reserve(8 + 1);
+
+                buffer.extend_from_slice(&player.to_be_bytes());
+                buffer.push(u8::from(flag));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        game::Settings,
+        response::{
+            ok::{
+                in_game::{game::Game, InGame},
+                Ok,
+            },
+            Response,
+        },
+        test_type,
+    };
+
+    use super::Lobby;
+
+    test_type!(
+        test_type_settings,
+        Response::Ok(Ok::InGame(InGame::Game(Game::Lobby(Lobby::Settings(&Settings::default()))))),
+        0b0110_1100
+    );
+
+    test_type!(
+        test_type_host,
+        Response::Ok(Ok::InGame(InGame::Game(Game::Lobby(Lobby::Host(0))))),
+        0b0110_1101
+    );
+
+    test_type!(
+        test_type_ready,
+        Response::Ok(Ok::InGame(InGame::Game(Game::Lobby(Lobby::Ready(0, true))))),
+        0b0110_1110
+    );
+
+    test_type!(
+        test_type_kick,
+        Response::Ok(Ok::InGame(InGame::Game(Game::Lobby(Lobby::Kick(0, false))))),
+        0b0110_1111
+    );
+}
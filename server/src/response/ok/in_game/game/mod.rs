@@ -1,7 +1,8 @@
 use crate::response::Responder;
 
-use self::{players::Players, status::Status};
+use self::{lobby::Lobby, players::Players, status::Status};
 
+pub mod lobby;
 pub mod players;
 pub mod status;
 
@@ -9,6 +10,7 @@ pub enum Game<'a> {
     Code(String),
     Players(Players),
     Status(Status<'a>),
+    Lobby(Lobby<'a>),
 }
 
 impl<'a> Responder for Game<'a> {
@@ -19,12 +21,14 @@ impl<'a> Responder for Game<'a> {
             Self::Code(_) => 0,
             Self::Players(_) => 1,
             Self::Status(_) => 2,
+            Self::Lobby(_) => 3,
         } << 2;
 
         match self {
             Self::Code(code) => buffer.extend_from_slice(code.as_bytes()),
             Self::Players(res) => res.write(buffer),
             Self::Status(res) => res.write(buffer),
+            Self::Lobby(res) => res.write(buffer),
         }
     }
 }
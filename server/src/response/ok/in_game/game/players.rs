@@ -4,10 +4,67 @@ pub enum Players {
     Join(i64),
     Leave(i64),
     List(Vec<i64>),
+    Spectators(u16),
 }
 
 impl Responder for Players {
-    fn write(self, _buffer: &mut Vec<u8>) {
-        todo!()
+    fn write(self, buffer: &mut Vec<u8>) {
+        let Some(byte_zero) = buffer.get_mut(0) else { return };
+
+        *byte_zero |= match &self {
+            Self::Join(_) => 0,
+            Self::Leave(_) => 1,
+            Self::List(_) => 2,
+            Self::Spectators(_) => 3,
+        };
+
+        match self {
+            Self::Join(player) | Self::Leave(player) => buffer.extend_from_slice(&player.to_be_bytes()),
+            Self::List(players) => {
+                buffer.reserve(players.len() * 8);
+                buffer.extend(players.iter().flat_map(|id| id.to_be_bytes()));
+            }
+            Self::Spectators(count) => buffer.extend_from_slice(&count.to_be_bytes()),
+        }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        response::{
+            ok::{
+                in_game::{game::Game, InGame},
+                Ok,
+            },
+            Response,
+        },
+        test_type,
+    };
+
+    use super::Players;
+
+    test_type!(
+        test_type_player_join,
+        Response::Ok(Ok::InGame(InGame::Game(Game::Players(Players::Join(0))))),
+        0b0110_0100
+    );
+
+    test_type!(
+        test_type_player_leave,
+        Response::Ok(Ok::InGame(InGame::Game(Game::Players(Players::Leave(0))))),
+        0b0110_0101
+    );
+
+    test_type!(
+        test_type_player_list,
+        Response::Ok(Ok::InGame(InGame::Game(Game::Players(Players::List(Vec::new()))))),
+        0b0110_0110
+    );
+
+    test_type!(
+        test_type_spectators,
+        Response::Ok(Ok::InGame(InGame::Game(Game::Players(Players::Spectators(0))))),
+        0b0110_0111
+    );
+}
@@ -7,7 +7,7 @@ pub mod game;
 
 pub enum InGame<'a> {
     Game(Game<'a>),
-    Board(Board),
+    Board(Board<'a>),
 }
 
 impl<'a> Responder for InGame<'a> {
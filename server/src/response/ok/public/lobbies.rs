@@ -0,0 +1,88 @@
+use crate::{
+    game::{Lobby, LobbyUpdate},
+    response::Responder,
+};
+
+pub enum Lobbies<'a> {
+    List(&'a [(String, Lobby)]),
+    Update(&'a LobbyUpdate),
+    Queue { waiting: u8, players: u8 },
+}
+
+impl<'a> Responder for Lobbies<'a> {
+    fn write(self, buffer: &mut Vec<u8>) {
+        let Some(byte_zero) = buffer.get_mut(0) else { return };
+
+        *byte_zero |= match &self {
+            Self::List(_) => 0,
+            Self::Queue { .. } => 1,
+            Self::Update(_) => 2,
+        } << 1;
+
+        match self {
+            Self::List(lobbies) => {
+                for (code, lobby) in lobbies {
+                    write_lobby(code, lobby, buffer);
+                }
+            }
+            Self::Update(LobbyUpdate::Open(code, lobby)) => {
+                buffer.push(0);
+                write_lobby(code, lobby, buffer);
+            }
+            Self::Update(LobbyUpdate::Closed(code)) => {
+                buffer.push(1);
+                buffer.extend_from_slice(code.as_bytes());
+            }
+            Self::Queue { waiting, players } => buffer.extend_from_slice(&[waiting, players]),
+        }
+    }
+}
+
+fn write_lobby(code: &str, lobby: &Lobby, buffer: &mut Vec<u8>) {
+    buffer.extend_from_slice(code.as_bytes());
+    buffer.extend_from_slice(&lobby.host.to_be_bytes());
+    buffer.extend_from_slice(&[lobby.players, lobby.seats_left(), lobby.settings.variant.id()]);
+
+    // Untimed games have no time control type
+    if lobby.settings.time_control.is_none() {
+        buffer.push(u8::MAX);
+    }
+    lobby.settings.write_time_control(buffer);
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        game::LobbyUpdate,
+        response::{
+            ok::{public::Public, Ok},
+            Response,
+        },
+        test_type,
+    };
+
+    use super::Lobbies;
+
+    test_type!(
+        test_type_lobby_list,
+        Response::Ok(Ok::Public(Public::Lobbies(Lobbies::List(&[])))),
+        0b0001_0000
+    );
+
+    test_type!(
+        test_type_lobby_update,
+        Response::Ok(Ok::Public(Public::Lobbies(Lobbies::Update(&LobbyUpdate::Closed(
+            String::new()
+        ))))),
+        0b0001_0100
+    );
+
+    test_type!(
+        test_type_queue_status,
+        Response::Ok(Ok::Public(Public::Lobbies(Lobbies::Queue {
+            waiting: 0,
+            players: 0,
+        }))),
+        0b0001_0010
+    );
+}
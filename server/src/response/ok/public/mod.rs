@@ -1,12 +1,14 @@
 use crate::{config::CHALLENGE_LENGTH, response::Responder};
 
-use self::profile::Profile;
+use self::{lobbies::Lobbies, profile::Profile};
 
+pub mod lobbies;
 pub mod profile;
 
 pub enum Public<'a> {
     Status,
     Profile(Profile<'a>),
+    Lobbies(Lobbies<'a>),
     LogInChallenge(&'a [u8; CHALLENGE_LENGTH]),
 }
 
@@ -17,12 +19,14 @@ impl<'a> Responder for Public<'a> {
         *byte_zero |= match &self {
             Self::Status => 0,
             Self::Profile(_) => 1,
+            Self::Lobbies(_) => 2,
             Self::LogInChallenge(_) => 3,
         } << 3;
 
         match self {
             Self::Status => (),
             Self::Profile(res) => res.write(buffer),
+            Self::Lobbies(res) => res.write(buffer),
             Self::LogInChallenge(challenge) => buffer.extend_from_slice(challenge),
         }
     }
@@ -21,16 +21,21 @@ use tokio_tungstenite::{
 
 use crate::{
     config::{BASE64_ENGINE, CHALLENGE_LENGTH, GAME_SENDER_CAPACITY},
-    game::{Broadcast, GameMessage, PlayerMessage},
+    game::{
+        matchmaking, open_lobbies, Broadcast, ChatMessage, GameMessage, LobbyUpdate, PlayerMessage,
+        Settings,
+    },
     request::{Request, Requester},
     response::{
         self,
         err::mal_req::MalformedRequest,
         ok::{
             in_game::{
-                game::{players::Players, status::Status, Game},
+                board::Board,
+                game::{lobby::Lobby, players::Players, status::Status, Game},
                 InGame,
             },
+            public::{lobbies::Lobbies, Public},
             Ok,
         },
         Response,
@@ -48,6 +53,11 @@ pub struct Client<'a> {
         Option<broadcast::Receiver<Broadcast>>,
         Option<mpsc::Sender<PlayerMessage>>,
     ),
+    pub lobbies: Option<broadcast::Receiver<LobbyUpdate>>,
+    /// Whether the client is watching the game, rather than playing in it
+    pub spectating: bool,
+    /// The players whose chat messages are not sent to the client
+    pub muted: Vec<i64>,
 }
 
 impl<'a> Client<'a> {
@@ -63,7 +73,41 @@ impl<'a> Client<'a> {
             log_in: None,
             game_handle: mpsc::channel(GAME_SENDER_CAPACITY),
             game: (None, None),
+            lobbies: None,
+            spectating: false,
+            muted: Vec::new(),
+        }
+    }
+
+    /// Leave the game the client is playing in or watching, returning whether it was in one
+    pub async fn leave_game(&mut self) -> bool {
+        if self.spectating {
+            self.stop_spectating().await;
+            return true;
+        }
+
+        let Some(game) = self.game.1.take() else { return false };
+        self.game.0 = None;
+
+        if let Some(account_id) = self.log_in {
+            game.send(PlayerMessage::Leave(account_id)).await.ok();
+        }
+        true
+    }
+
+    /// Stop watching a game, if the client is
+    pub async fn stop_spectating(&mut self) {
+        if !self.spectating {
+            return;
+        }
+        self.spectating = false;
+
+        if let Some(game) = self.game.1.take() {
+            game.send(PlayerMessage::StopSpectating(self.game_handle.0.clone()))
+                .await
+                .ok();
         }
+        self.game.0 = None;
     }
 
     pub async fn send(&mut self, data: Vec<u8>) -> Result<(), ()> {
@@ -96,14 +140,24 @@ pub async fn handle(socket: TcpStream) {
         let in_game = client.game.0.is_some();
         let game_broadcast: OptionFuture<_> =
             client.game.0.as_mut().map(broadcast::Receiver::recv).into();
+        let watching_lobbies = client.lobbies.is_some();
+        let lobby_update: OptionFuture<_> =
+            client.lobbies.as_mut().map(broadcast::Receiver::recv).into();
 
         select! {
             update = read.next() => handle_socket(&mut client, update).await,
             update = client.game_handle.1.recv() => handle_game_message(&mut client, update).await,
             update = game_broadcast, if in_game => handle_game_broadcast(&mut client, update).await,
+            update = lobby_update, if watching_lobbies => handle_lobby_update(&mut client, update).await,
         }
     }
 
+    // Stop waiting for a game
+    if let Some(account_id) = client.log_in {
+        matchmaking::leave(account_id).await.ok();
+    }
+    client.leave_game().await;
+
     println!("{}: Disconnected", client.peer_address);
 }
 
@@ -168,12 +222,102 @@ async fn handle_socket<'a>(client: &mut Client<'a>, update: Option<Result<Messag
     }
 }
 
+#[allow(clippy::too_many_lines)]
 async fn handle_game_message<'a>(client: &mut Client<'a>, update: Option<GameMessage>) {
     let Some(message) = update else { return };
 
     match message {
-        GameMessage::Join(broadcast) => {
+        GameMessage::Join {
+            broadcast,
+            settings,
+            players,
+            host,
+            ready,
+            chat,
+        } => {
             client.game.0 = Some(broadcast);
+
+            for response in lobby_responses(&settings, &players, host, &ready) {
+                client.send(response).await.ok();
+            }
+            send_chat(client, &chat).await;
+        }
+        GameMessage::Spectate {
+            broadcast,
+            settings,
+            players,
+            host,
+            ready,
+            board,
+            turn,
+            spectators,
+            chat,
+        } => {
+            client.game.0 = Some(broadcast);
+
+            let mut responses = lobby_responses(&settings, &players, host, &ready);
+            if let Some(board) = &board {
+                responses.push(
+                    Response::Ok(Ok::InGame(InGame::Game(Game::Status(Status::Start(&players, board)))))
+                        .into(),
+                );
+            }
+            if let Some((player, clocks)) = &turn {
+                responses.push(
+                    Response::Ok(Ok::InGame(InGame::Board(Board::Turn {
+                        player: *player,
+                        clocks,
+                    })))
+                    .into(),
+                );
+            }
+            responses.push(
+                Response::Ok(Ok::InGame(InGame::Game(Game::Players(Players::Spectators(spectators)))))
+                    .into(),
+            );
+
+            for response in responses {
+                client.send(response).await.ok();
+            }
+            send_chat(client, &chat).await;
+        }
+        GameMessage::SpectatingDisabled => {
+            client.game = (None, None);
+            client.spectating = false;
+            client
+                .send(
+                    Response::Err(response::err::Error::InvalReq(
+                        response::err::inval_req::InvalidRequest::Perm(
+                            response::err::inval_req::perms::Permissions::SpectatingDisabled,
+                        ),
+                    ))
+                    .into(),
+                )
+                .await
+                .ok();
+        }
+        GameMessage::Matched {
+            code,
+            game,
+            broadcast,
+        } => {
+            client.game.1 = Some(game);
+            if let Some(broadcast) = broadcast {
+                client.game.0 = Some(broadcast);
+            }
+            client
+                .send(Response::Ok(Ok::InGame(InGame::Game(Game::Code(code)))).into())
+                .await
+                .ok();
+        }
+        GameMessage::QueueStatus { waiting, players } => {
+            client
+                .send(
+                    Response::Ok(Ok::Public(Public::Lobbies(Lobbies::Queue { waiting, players })))
+                        .into(),
+                )
+                .await
+                .ok();
         }
         GameMessage::JoinRejection(reason) => {
             client.game = (None, None);
@@ -187,6 +331,67 @@ async fn handle_game_message<'a>(client: &mut Client<'a>, update: Option<GameMes
                 .await
                 .ok();
         }
+        GameMessage::Banned => {
+            client.game = (None, None);
+            client
+                .send(
+                    Response::Err(response::err::Error::InvalReq(
+                        response::err::inval_req::InvalidRequest::Perm(
+                            response::err::inval_req::perms::Permissions::Banned,
+                        ),
+                    ))
+                    .into(),
+                )
+                .await
+                .ok();
+        }
+        GameMessage::UnknownPlayer => {
+            client
+                .send(
+                    Response::Err(response::err::Error::InvalReq(
+                        response::err::inval_req::InvalidRequest::Auth(
+                            response::err::inval_req::auth::Authentication::Id(
+                                response::err::inval_req::auth::id::Identity::UnknownId,
+                            ),
+                        ),
+                    ))
+                    .into(),
+                )
+                .await
+                .ok();
+        }
+        GameMessage::AlreadyStarted => {
+            client
+                .send(
+                    Response::Err(response::err::Error::InvalReq(
+                        response::err::inval_req::InvalidRequest::Game(
+                            response::err::inval_req::game::Game::Started,
+                        ),
+                    ))
+                    .into(),
+                )
+                .await
+                .ok();
+        }
+        GameMessage::Full => {
+            client
+                .send(
+                    Response::Err(response::err::Error::InvalReq(
+                        response::err::inval_req::InvalidRequest::Game(
+                            response::err::inval_req::game::Game::Full,
+                        ),
+                    ))
+                    .into(),
+                )
+                .await
+                .ok();
+        }
+        GameMessage::NotReady => {
+            client
+                .send(Response::Err(response::err::Error::IG(response::err::in_game::InGame::NotReady)).into())
+                .await
+                .ok();
+        }
         GameMessage::NotGameHost => {
             client
                 .send(
@@ -213,14 +418,88 @@ async fn handle_game_message<'a>(client: &mut Client<'a>, update: Option<GameMes
                 .await
                 .ok();
         }
+        GameMessage::Start { players, board } => {
+            client
+                .send(
+                    Response::Ok(Ok::InGame(InGame::Game(Game::Status(Status::Start(
+                        &players, &board,
+                    )))))
+                    .into(),
+                )
+                .await
+                .ok();
+        }
+        GameMessage::Move {
+            player,
+            deltas,
+            points,
+            board,
+        } => {
+            client
+                .send(
+                    Response::Ok(Ok::InGame(InGame::Board(Board::Move {
+                        player,
+                        points,
+                        deltas: &deltas,
+                    })))
+                    .into(),
+                )
+                .await
+                .ok();
+
+            // With fog of war, the player's view of the board is refreshed
+            if let Some(board) = board {
+                client
+                    .send(Response::Ok(Ok::InGame(InGame::Board(Board::View(&board)))).into())
+                    .await
+                    .ok();
+            }
+        }
+        GameMessage::MoveRejection(reason) => {
+            client
+                .send(Response::Err(response::err::Error::IG(reason)).into())
+                .await
+                .ok();
+        }
+        GameMessage::Hints(tactics) => {
+            client
+                .send(Response::Ok(Ok::InGame(InGame::Board(Board::Hints(&tactics)))).into())
+                .await
+                .ok();
+        }
+        GameMessage::ChatRateLimited => {
+            client
+                .send(
+                    Response::Err(response::err::Error::InvalReq(
+                        response::err::inval_req::InvalidRequest::Chat(
+                            response::err::inval_req::chat::Chat::RateLimited,
+                        ),
+                    ))
+                    .into(),
+                )
+                .await
+                .ok();
+        }
+        // Only sent to bots
+        GameMessage::Position(_) => {}
     }
 }
 
+#[allow(clippy::too_many_lines)]
 async fn handle_game_broadcast<'a>(
     client: &mut Client<'a>,
     update: Option<Result<Broadcast, RecvError>>,
 ) {
-    let Some(Ok(message)) = update else { return };
+    let message = match update {
+        Some(Ok(message)) => message,
+        // The game ended
+        Some(Err(RecvError::Closed)) => {
+            client.game = (None, None);
+            client.spectating = false;
+            return;
+        }
+        Some(Err(RecvError::Lagged(_))) | None => return,
+    };
 
     match message {
         Broadcast::Join(id) => {
@@ -231,23 +510,146 @@ async fn handle_game_broadcast<'a>(
                 .await
                 .ok();
         }
-        Broadcast::Leave(_player, _deltas) => todo!(),
-        Broadcast::Start { players, board } => {
+        Broadcast::Leave(player) => {
             client
                 .send(
-                    Response::Ok(Ok::InGame(InGame::Game(Game::Status(Status::Start(
-                        &players, &board,
-                    )))))
-                    .into(),
+                    Response::Ok(Ok::InGame(InGame::Game(Game::Players(Players::Leave(player)))))
+                        .into(),
+                )
+                .await
+                .ok();
+        }
+        Broadcast::Host(player) => {
+            client
+                .send(Response::Ok(Ok::InGame(InGame::Game(Game::Lobby(Lobby::Host(player))))).into())
+                .await
+                .ok();
+        }
+        Broadcast::Ready(player, ready) => {
+            client
+                .send(
+                    Response::Ok(Ok::InGame(InGame::Game(Game::Lobby(Lobby::Ready(player, ready)))))
+                        .into(),
+                )
+                .await
+                .ok();
+        }
+        Broadcast::Kick(player, ban) => {
+            client
+                .send(
+                    Response::Ok(Ok::InGame(InGame::Game(Game::Lobby(Lobby::Kick(player, ban)))))
+                        .into(),
+                )
+                .await
+                .ok();
+
+            // The kicked player is no longer in the game
+            if client.log_in == Some(player) && !client.spectating {
+                client.game = (None, None);
+            }
+        }
+        Broadcast::End(scores) => {
+            client
+                .send(
+                    Response::Ok(Ok::InGame(InGame::Game(Game::Status(Status::End(&scores)))))
+                        .into(),
                 )
                 .await
                 .ok();
         }
-        Broadcast::Turn(_player) => todo!(),
         Broadcast::Move {
-            player: _player,
-            deltas: _deltas,
-            points: _points,
-        } => todo!(),
+            player,
+            deltas,
+            points,
+        } => {
+            // Players are sent only what they can see of the move
+            if !client.spectating {
+                return;
+            }
+
+            client
+                .send(
+                    Response::Ok(Ok::InGame(InGame::Board(Board::Move {
+                        player,
+                        points,
+                        deltas: &deltas,
+                    })))
+                    .into(),
+                )
+                .await
+                .ok();
+        }
+        Broadcast::Spectators(count) => {
+            client
+                .send(
+                    Response::Ok(Ok::InGame(InGame::Game(Game::Players(Players::Spectators(count)))))
+                        .into(),
+                )
+                .await
+                .ok();
+        }
+        Broadcast::Chat(message) => send_chat(client, &[message]).await,
+        Broadcast::Turn(player, clocks) => {
+            client
+                .send(
+                    Response::Ok(Ok::InGame(InGame::Board(Board::Turn {
+                        player,
+                        clocks: &clocks,
+                    })))
+                    .into(),
+                )
+                .await
+                .ok();
+        }
+    }
+}
+
+/// Get the responses describing a lobby to a client entering it
+fn lobby_responses(settings: &Settings, players: &[i64], host: i64, ready: &[i64]) -> Vec<Vec<u8>> {
+    let mut responses = vec![
+        Response::Ok(Ok::InGame(InGame::Game(Game::Lobby(Lobby::Settings(settings))))).into(),
+        Response::Ok(Ok::InGame(InGame::Game(Game::Players(Players::List(players.to_vec()))))).into(),
+        Response::Ok(Ok::InGame(InGame::Game(Game::Lobby(Lobby::Host(host))))).into(),
+    ];
+    responses.extend(ready.iter().map(|player| {
+        Response::Ok(Ok::InGame(InGame::Game(Game::Lobby(Lobby::Ready(*player, true))))).into()
+    }));
+    responses
+}
+
+/// Send chat messages, leaving out muted players and the spectators' channel for players
+async fn send_chat<'a>(client: &mut Client<'a>, messages: &[ChatMessage]) {
+    for message in messages {
+        if client.muted.contains(&message.player) || (message.spectators && !client.spectating) {
+            continue;
+        }
+
+        client
+            .send(Response::Ok(Ok::InGame(InGame::Board(Board::Chat(message)))).into())
+            .await
+            .ok();
+    }
+}
+
+async fn handle_lobby_update<'a>(
+    client: &mut Client<'a>,
+    update: Option<Result<LobbyUpdate, RecvError>>,
+) {
+    match update {
+        Some(Ok(update)) => {
+            client
+                .send(Response::Ok(Ok::Public(Public::Lobbies(Lobbies::Update(&update)))).into())
+                .await
+                .ok();
+        }
+        // Updates were missed, so send every listed game again
+        Some(Err(RecvError::Lagged(_))) => {
+            let lobbies = open_lobbies();
+            client
+                .send(Response::Ok(Ok::Public(Public::Lobbies(Lobbies::List(&lobbies)))).into())
+                .await
+                .ok();
+        }
+        Some(Err(RecvError::Closed)) | None => client.lobbies = None,
     }
 }
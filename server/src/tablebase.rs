@@ -0,0 +1,72 @@
+#![warn(
+    clippy::all,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::perf,
+    clippy::cargo
+)]
+
+use std::{env, error, path::PathBuf, process, time::Instant};
+
+use chessehc::tablebase::{Material, Tablebases};
+
+const USAGE: &str = "\
+Usage: chessehc_tablebase [options] <material>...
+
+Builds tablebases for two players, such as KQK, KRK or KBNK,
+and the tablebases for their material after each capture
+
+Options:
+  --width <n>          board width (default 8)
+  --height <n>         board height (default 8)
+  --output <path>      directory to write the tablebases to";
+
+fn main() -> Result<(), Box<dyn error::Error>> {
+    let mut width = 8;
+    let mut height = 8;
+    let mut output = None;
+    let mut materials = Vec::new();
+
+    let mut arguments = env::args().skip(1);
+    while let Some(argument) = arguments.next() {
+        let mut value = || arguments.next().unwrap_or_default();
+        match argument.as_str() {
+            "--width" => width = value().parse().unwrap_or(0),
+            "--height" => height = value().parse().unwrap_or(0),
+            "--output" => output = Some(PathBuf::from(value())),
+            _ => materials.push(argument.parse::<Material>()?),
+        }
+    }
+    if width == 0 || height == 0 || materials.is_empty() {
+        eprintln!("{USAGE}");
+        process::exit(2);
+    }
+
+    let mut tablebases = Tablebases::new();
+    for material in &materials {
+        let start = Instant::now();
+        let statistics = tablebases.generate(material, width, height)?.statistics();
+
+        println!("{material} on a {width}x{height} tube, in {:.1?}:", start.elapsed());
+        for (side, [wins, draws, losses]) in ["First", "Second"].iter().zip(&statistics.results) {
+            let total = (wins + draws + losses).max(1);
+            let percent = |n: u64| {
+                let tenths = n * 1000 / total;
+                format!("{}.{}%", tenths / 10, tenths % 10)
+            };
+            println!(
+                "  {side} player to move: {} wins, {} draws, {} losses",
+                percent(*wins),
+                percent(*draws),
+                percent(*losses),
+            );
+        }
+        println!("  Longest win: {} plies", statistics.longest_win);
+    }
+
+    if let Some(directory) = output {
+        tablebases.write_dir(&directory)?;
+    }
+
+    Ok(())
+}
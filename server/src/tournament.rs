@@ -0,0 +1,371 @@
+#![warn(
+    clippy::all,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::perf,
+    clippy::cargo
+)]
+
+use std::{
+    env, error,
+    fmt::Write as _,
+    fs,
+    time::{Duration, Instant},
+};
+
+use chessehc::{
+    engine::{client::Engine, record::GameRecord, Limit, Position, Setup},
+    game::Game,
+    mcts::{Budget, Mcts},
+    r#move::{Move, PartialMove},
+    rng::Rng,
+    standard_pieces::{setup, BackRank, StandardCompatiblePieceSet},
+};
+
+const USAGE: &str = "\
+Usage: chessehc_tournament [options] <entrant>...
+
+Entrants:
+  random               plays a random valid move
+  mcts[:<iterations>]  searches with the library's engine, for the move time or a number of iterations
+  engine:<path>        an engine program speaking the engine protocol
+
+Options:
+  --players <n>        players in each game (default 2)
+  --width <n>          board width (default 8)
+  --height <n>         board height (default 8 per player)
+  --back-rank <kind>   standard, shared or individual (default standard)
+  --games <n>          games to play (default 10)
+  --move-time <ms>     time limit for each move (default 100)
+  --max-moves <n>      moves before a game is drawn (default 200)
+  --seed <n>           seed for the games and random movers (default 0)
+  --records <path>     file to write the game records to";
+
+/// Extra time allowed over the move time, for communication with engines
+const GRACE: Duration = Duration::from_millis(100);
+
+struct Config {
+    players: u8,
+    width: u16,
+    height: u16,
+    back_rank: BackRank,
+    games: u32,
+    move_time: Duration,
+    max_moves: u32,
+    seed: u64,
+    records: Option<String>,
+    entrants: Vec<String>,
+}
+
+impl Config {
+    fn parse(mut arguments: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut config = Self {
+            players: 2,
+            width: 8,
+            height: 0,
+            back_rank: BackRank::Standard,
+            games: 10,
+            move_time: Duration::from_millis(100),
+            max_moves: 200,
+            seed: 0,
+            records: None,
+            entrants: Vec::new(),
+        };
+
+        while let Some(argument) = arguments.next() {
+            let Some(option) = argument.strip_prefix("--") else {
+                config.entrants.push(argument);
+                continue;
+            };
+
+            let value = arguments
+                .next()
+                .ok_or_else(|| format!("missing value for --{option}"))?;
+            let invalid = || format!("invalid value for --{option}: {value}");
+
+            match option {
+                "players" => config.players = value.parse().map_err(|_| invalid())?,
+                "width" => config.width = value.parse().map_err(|_| invalid())?,
+                "height" => config.height = value.parse().map_err(|_| invalid())?,
+                "back-rank" => {
+                    config.back_rank = match value.as_str() {
+                        "standard" => BackRank::Standard,
+                        "shared" => BackRank::Shared,
+                        "individual" => BackRank::Individual,
+                        _ => return Err(invalid()),
+                    };
+                }
+                "games" => config.games = value.parse().map_err(|_| invalid())?,
+                "move-time" => {
+                    config.move_time = Duration::from_millis(value.parse().map_err(|_| invalid())?);
+                }
+                "max-moves" => config.max_moves = value.parse().map_err(|_| invalid())?,
+                "seed" => config.seed = value.parse().map_err(|_| invalid())?,
+                "records" => config.records = Some(value),
+                _ => return Err(format!("unknown option: --{option}")),
+            }
+        }
+
+        if config.players == 0 {
+            return Err("there must be at least one player".to_owned());
+        }
+        if config.entrants.is_empty() {
+            return Err("there must be at least one entrant".to_owned());
+        }
+        if config.height == 0 {
+            config.height = 8 * u16::from(config.players);
+        }
+
+        Ok(config)
+    }
+}
+
+/// Something which chooses moves
+enum Player {
+    Random(Rng),
+    Mcts(Mcts<StandardCompatiblePieceSet>),
+    Engine(Engine),
+}
+
+impl Player {
+    fn new(entrant: &str, config: &Config, seed: u64) -> Result<Self, Box<dyn error::Error>> {
+        Ok(match entrant.split_once(':').unwrap_or((entrant, "")) {
+            ("random", "") => Self::Random(Rng::new(seed)),
+            ("mcts", iterations) => {
+                let mut mcts = Mcts::new(seed);
+                mcts.set_budget(if iterations.is_empty() {
+                    Budget::Time(config.move_time)
+                } else {
+                    Budget::Iterations(iterations.parse()?)
+                });
+                Self::Mcts(mcts)
+            }
+            ("engine", path) => Self::Engine(Engine::spawn(path, [])?),
+            _ => return Err(format!("unknown entrant: {entrant}").into()),
+        })
+    }
+
+    /// Start a new game
+    fn new_game(&mut self) -> Result<(), Box<dyn error::Error>> {
+        if let Self::Engine(engine) = self {
+            engine.send(&chessehc::engine::Command::NewGame)?;
+            engine.is_ready()?;
+        }
+
+        Ok(())
+    }
+
+    /// Choose a move, or `None` if no move could be chosen
+    fn choose(
+        &mut self,
+        game: &Game<StandardCompatiblePieceSet>,
+        position: &Position,
+        move_time: Duration,
+    ) -> Option<PartialMove> {
+        match self {
+            Self::Random(rng) => {
+                let moves = game.valid_moves();
+                moves.get(rng.below(moves.len().max(1))).copied()
+            }
+            Self::Mcts(mcts) => mcts
+                .search(game)
+                .ok()
+                .flatten()
+                .map(|search| (search.r#move.from, search.r#move.to, search.r#move.data)),
+            Self::Engine(engine) => engine
+                .go(
+                    position.clone(),
+                    Limit {
+                        move_time: Some(move_time),
+                        ..Limit::default()
+                    },
+                )
+                .ok()
+                .and_then(|(r#move, _)| r#move),
+        }
+    }
+}
+
+/// How a game ended
+enum Termination {
+    Won,
+    NoMoves,
+    MoveLimit,
+    Forfeit(u8),
+}
+
+/// Play a game, returning its record and the points for each seat
+fn play(
+    config: &Config,
+    players: &mut [Player],
+    seats: &[usize],
+    number: u32,
+) -> Result<(GameRecord, Vec<f64>), Box<dyn error::Error>> {
+    let seed = config.seed.wrapping_add(u64::from(number));
+
+    let mut game = Game::new(config.players, config.width, config.height);
+    game.set_seed(seed);
+    setup(&mut game, config.back_rank)?;
+
+    // Use the compact start position when the board splits evenly between the players
+    let rows = config.height / u16::from(config.players);
+    let mut position = Position {
+        setup: if rows * u16::from(config.players) == config.height {
+            Setup::Start {
+                players: config.players,
+                width: config.width,
+                rows,
+                back_rank: config.back_rank,
+                seed,
+            }
+        } else {
+            Setup::from_game(&game).ok_or("start position cannot be sent to engines")?
+        },
+        moves: Vec::new(),
+    };
+
+    for &seat in seats {
+        players[seat].new_game()?;
+    }
+
+    let mut termination = Termination::MoveLimit;
+    for _ in 0..config.max_moves {
+        if game.is_over() {
+            termination = Termination::Won;
+            break;
+        }
+        if game.valid_moves().is_empty() {
+            termination = Termination::NoMoves;
+            break;
+        }
+
+        let seat = game.current_player();
+        let start = Instant::now();
+        let choice = players[seats[usize::from(seat)]].choose(&game, &position, config.move_time);
+
+        // Forfeit the game for moving too slowly or making an invalid move
+        let Some((from, to, data)) = choice.filter(|&(from, to, data)| {
+            start.elapsed() <= config.move_time + GRACE
+                && game
+                    .valid_moves()
+                    .iter()
+                    .any(|valid| valid.0 == from && valid.1 == to && valid.2 == data)
+        }) else {
+            termination = Termination::Forfeit(seat);
+            break;
+        };
+
+        game.make_move(&Move {
+            from,
+            to,
+            data,
+            player: seat,
+        })?;
+        game.start_turn()?;
+        position.moves.push((from, to, data));
+    }
+    if game.is_over() {
+        termination = Termination::Won;
+    }
+
+    // The players still in play share the point
+    let in_play: Vec<bool> = (0..config.players)
+        .map(|seat| game.is_in_play(seat) && !matches!(termination, Termination::Forfeit(forfeit) if forfeit == seat))
+        .collect();
+    let n_in_play = in_play.iter().filter(|&&is_in_play| is_in_play).count().max(1);
+    let share = 1. / f64::from(u32::try_from(n_in_play).unwrap_or(u32::MAX));
+    let points: Vec<f64> = in_play
+        .iter()
+        .map(|&is_in_play| if is_in_play { share } else { 0. })
+        .collect();
+
+    let mut tags = vec![
+        ("Event".to_owned(), "tournament".to_owned()),
+        ("Game".to_owned(), number.to_string()),
+    ];
+    for (seat, &entrant) in seats.iter().enumerate() {
+        tags.push((format!("Seat{seat}"), config.entrants[entrant].clone()));
+    }
+    tags.push((
+        "Result".to_owned(),
+        points.iter().map(f64::to_string).collect::<Vec<_>>().join(" "),
+    ));
+    tags.push((
+        "Termination".to_owned(),
+        match termination {
+            Termination::Won => "won".to_owned(),
+            Termination::NoMoves => "no moves".to_owned(),
+            Termination::MoveLimit => "move limit".to_owned(),
+            Termination::Forfeit(seat) => format!("forfeit {seat}"),
+        },
+    ));
+
+    Ok((GameRecord { tags, position }, points))
+}
+
+fn main() -> Result<(), Box<dyn error::Error>> {
+    let config = match Config::parse(env::args().skip(1)) {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("{err}\n\n{USAGE}");
+            std::process::exit(2);
+        }
+    };
+
+    let mut players = Vec::with_capacity(config.entrants.len());
+    for (i, entrant) in config.entrants.iter().enumerate() {
+        players.push(Player::new(entrant, &config, config.seed.wrapping_add(i as u64))?);
+    }
+
+    let n_seats = usize::from(config.players);
+    let n_entrants = config.entrants.len();
+    // Points and games for each entrant, and points for each seat
+    let mut standings = vec![(0., 0); n_entrants];
+    let mut seat_points = vec![0.; n_seats];
+    let mut records = String::new();
+
+    for number in 0..config.games {
+        // Rotate the entrants around the seats
+        let seats: Vec<usize> = (0..n_seats)
+            .map(|seat| (seat + number as usize) % n_entrants)
+            .collect();
+
+        let (record, points) = play(&config, &mut players, &seats, number)?;
+        for (seat, &entrant) in seats.iter().enumerate() {
+            standings[entrant].0 += points[seat];
+            standings[entrant].1 += 1;
+            seat_points[seat] += points[seat];
+        }
+
+        if !records.is_empty() {
+            records.push('\n');
+        }
+        write!(records, "{record}")?;
+        println!(
+            "Game {number}: {}",
+            record.tag("Result").unwrap_or_default()
+        );
+    }
+
+    if let Some(path) = &config.records {
+        fs::write(path, records)?;
+    }
+
+    println!("\nStandings:");
+    let mut order: Vec<usize> = (0..n_entrants).collect();
+    order.sort_by(|&a, &b| standings[b].0.total_cmp(&standings[a].0));
+    for entrant in order {
+        let (points, games) = standings[entrant];
+        println!("  {:>8.1} / {games:<4} {}", points, config.entrants[entrant]);
+    }
+
+    println!("\nSeats:");
+    for (seat, points) in seat_points.iter().enumerate() {
+        println!(
+            "  {seat:>3}: {:>5.1}% win rate",
+            points / f64::from(config.games.max(1)) * 100.
+        );
+    }
+
+    Ok(())
+}